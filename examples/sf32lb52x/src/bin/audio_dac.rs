@@ -56,7 +56,7 @@ async fn main(_spawner: Spawner) {
     }
 
     // Create DAC (blocking mode, 48kHz stereo)
-    let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
     let mut dac = AudioDac::new_blocking(p.AUDPRC, p.DMAC1_CH1, &pll, DacConfig::default());
     let _ = writeln!(usart, "AudioDac initialized");
 
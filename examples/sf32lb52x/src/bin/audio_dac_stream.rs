@@ -164,7 +164,7 @@ async fn main(_spawner: Spawner) {
     }
 
     // Create async AudioDac
-    let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
     let mut dac = AudioDac::new(p.AUDPRC, p.DMAC1_CH1, &pll, Irqs, DacConfig::default());
     let _ = writeln!(usart, "AudioDac initialized");
 
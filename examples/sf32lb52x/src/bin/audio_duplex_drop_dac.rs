@@ -0,0 +1,127 @@
+//! Audio full-duplex — drop the DAC half while the ADC keeps capturing.
+//!
+//! Creates a DAC/ADC pair with `audio::new_duplex()`, starts an ADC stream,
+//! plays a short 1kHz tone, then drops the `AudioDac`. The ADC stream must
+//! keep delivering blocks afterwards: dropping one half only disables its own
+//! AUDPRC path.
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+use embassy_executor::Spawner;
+use embassy_futures::select::select;
+use embassy_time::Timer;
+use embedded_io::Write as _;
+
+use sifli_hal::aud_pll::{AudioPll, AudPllFreq};
+use sifli_hal::audio;
+use sifli_hal::bind_interrupts;
+use sifli_hal::usart::{Config as UartConfig, Uart};
+
+use libm::sinf;
+
+bind_interrupts!(struct Irqs {
+    AUDPRC => audio::InterruptHandler;
+});
+
+const PI: f32 = core::f32::consts::PI;
+const SAMPLE_RATE: u32 = 48000;
+const SINE_FREQ: f32 = 1000.0;
+const SAMPLES_PER_PERIOD: usize = (SAMPLE_RATE as f32 / SINE_FREQ) as usize; // 48
+
+// DMA buffers must be in SRAM (DMAC1 cannot access PSRAM).
+static mut TONE: [u32; 4800] = [0u32; 4800]; // 100ms @ 48kHz stereo
+static mut RX_DMA_BUF: [u32; 960] = [0u32; 960];
+static mut SAMPLES: [u32; 480] = [0u32; 480];
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let mut p = sifli_hal::init(Default::default());
+
+    let mut uart_config = UartConfig::default();
+    uart_config.baudrate = 1000000;
+    let mut usart = Uart::new_blocking(p.USART1, p.PA18, p.PA19, uart_config).unwrap();
+    let _ = writeln!(usart, "\r\n=== Audio duplex: drop DAC, keep ADC ===");
+
+    let tone = unsafe { &mut *core::ptr::addr_of_mut!(TONE) };
+    for (i, w) in tone.iter_mut().enumerate() {
+        let t = (i % SAMPLES_PER_PERIOD) as f32 / SAMPLE_RATE as f32;
+        let s16 = (sinf(2.0 * PI * SINE_FREQ * t) * 20000.0) as i16 as u16 as u32;
+        *w = s16 | (s16 << 16);
+    }
+
+    // PA26: speaker amplifier enable
+    {
+        let pa = sifli_hal::gpio::Output::new(&mut p.PA26, sifli_hal::gpio::Level::High);
+        core::mem::forget(pa);
+    }
+
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
+    let (mut dac, mut adc) = audio::new_duplex(
+        p.AUDPRC,
+        p.DMAC1_CH1,
+        p.DMAC1_CH2,
+        &pll,
+        Irqs,
+        audio::DacConfig::default(),
+        audio::AdcConfig::default(),
+    );
+
+    let rx_buf = unsafe { &mut *core::ptr::addr_of_mut!(RX_DMA_BUF) };
+    let samples = unsafe { &mut *core::ptr::addr_of_mut!(SAMPLES) };
+    let mut stream = adc.start_stream(rx_buf);
+
+    // Capture while the tone plays, so the ring is drained the whole time.
+    let play = async {
+        for _ in 0..5 {
+            if let Err(e) = dac.write(tone).await {
+                let _ = writeln!(usart, "DAC write error: {:?}", e);
+            }
+        }
+    };
+    let capture = async {
+        loop {
+            let _ = stream.read(samples).await;
+        }
+    };
+    select(play, capture).await;
+
+    drop(dac);
+    let _ = writeln!(usart, "AudioDac dropped, ADC still streaming:");
+
+    let mut ok = 0u32;
+    for round in 0..100u32 {
+        match stream.read(samples).await {
+            Ok(_) => {
+                ok += 1;
+                if round % 20 == 0 {
+                    let w = samples[0];
+                    let _ = writeln!(
+                        usart,
+                        "[{:3}] L={:6} R={:6}",
+                        round,
+                        w as i16,
+                        (w >> 16) as i16
+                    );
+                }
+            }
+            Err(e) => {
+                let _ = writeln!(usart, "[{:3}] ADC read error: {:?}", round, e);
+                Timer::after_millis(10).await;
+            }
+        }
+    }
+
+    if ok == 100 {
+        let _ = writeln!(usart, "PASS: 100/100 ADC blocks after dropping the DAC");
+    } else {
+        let _ = writeln!(usart, "FAIL: {}/100 ADC blocks after dropping the DAC", ok);
+    }
+
+    loop {
+        Timer::after_secs(1).await;
+    }
+}
@@ -42,7 +42,7 @@ async fn main(_spawner: Spawner) {
     let _ = writeln!(usart, "\r\n=== Audio ADC HAL Recording ===");
 
     // Create async AudioAdc with default config (48kHz stereo)
-    let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
     let mut adc = AudioAdc::new(
         p.AUDPRC,
         p.DMAC1_CH2,
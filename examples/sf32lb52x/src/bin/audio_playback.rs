@@ -35,7 +35,7 @@ static mut DMA_BUF: [u32; 9600] = [0u32; 9600];
 async fn main(_spawner: Spawner) {
     let p = sifli_hal::init(sifli_hal::Config::default());
 
-    let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
     let mut dac = AudioDac::new(
         p.AUDPRC,
         p.DMAC1_CH1,
@@ -367,7 +367,7 @@ async fn main(_spawner: Spawner) {
     };
 
     // ===== Audio init =====
-    let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
     let mut adc = AudioAdc::new(
         p.AUDPRC,
         p.DMAC1_CH2,
@@ -319,7 +319,7 @@ async fn main(_spawner: Spawner) {
     };
 
     // ===== Audio init =====
-    let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+    let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
     let mut adc = AudioAdc::new(p.AUDPRC, p.DMAC1_CH2, &pll, Irqs, audio::AdcConfig::default());
     let dma_buf = unsafe { &mut *core::ptr::addr_of_mut!(DMA_BUF) };
     let mut stream = adc.start_stream(dma_buf);
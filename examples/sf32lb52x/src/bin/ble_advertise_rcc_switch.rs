@@ -132,7 +132,7 @@ async fn main(_spawner: Spawner) {
 
     // --- Switch to LP before entering advertising loop ---
     info!("Entering low-power advertising mode (48 MHz)");
-    reconfigure_sysclk(LP_MODE);
+    unwrap!(reconfigure_sysclk(LP_MODE));
     print_clocks("LP mode");
 
     let _ = join(
@@ -150,7 +150,7 @@ async fn main(_spawner: Spawner) {
                     Ok(conn) => {
                         // Connected — switch to 240 MHz for performance
                         info!("Connected! Switching to 240 MHz");
-                        reconfigure_sysclk(HP_MODE);
+                        unwrap!(reconfigure_sysclk(HP_MODE));
                         print_clocks("HP mode");
                         unwrap!(usart.write_all(b"[240MHz] BLE connected\r\n").await);
 
@@ -160,7 +160,7 @@ async fn main(_spawner: Spawner) {
 
                         // Disconnected — switch back to 48 MHz
                         info!("Disconnected, back to 48 MHz");
-                        reconfigure_sysclk(LP_MODE);
+                        unwrap!(reconfigure_sysclk(LP_MODE));
                         print_clocks("LP mode");
                         unwrap!(usart.write_all(b"[48MHz] BLE disconnected\r\n").await);
                     }
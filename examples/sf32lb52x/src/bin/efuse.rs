@@ -27,7 +27,7 @@ async fn main(_spawner: Spawner) {
     let cal = *efuse.calibration();
 
     loop {
-        info!("UID: {:x}", uid.bytes());
+        info!("UID: {}", uid);
         info!("Bank1 is_io18: {}", cal.primary.low.is_io18());
         info!("Bank1 primary low: {:?}", cal.primary.low);
         info!("Bank1 primary high: {:?}", cal.primary.high);
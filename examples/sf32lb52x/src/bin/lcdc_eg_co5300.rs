@@ -61,7 +61,8 @@ impl Co5300Spec for MyCo5300 {
 }
 
 // Framebuffer configuration
-// Using BigEndian for direct compatibility with the display controller's byte order
+// Using BigEndian for direct compatibility with the display controller's byte order.
+// A LittleEndian framebuffer works too with `byte_swap: true` in the LCDC config.
 type FramebufferType =
     Framebuffer<Rgb565, RawU16, BigEndian, WIDTH, HEIGHT, { buffer_size::<Rgb565>(WIDTH, HEIGHT) }>;
 
@@ -11,6 +11,9 @@
 //! others keep retrying until enumeration eventually succeeds.
 //!
 //! This issue is unrelated to the software (SiFli-rs).
+//!
+//! Pressing the button while the host is asleep (bus suspended) wakes it
+//! with USB remote wakeup instead of sending a key.
 
 #![no_std]
 #![no_main]
@@ -20,6 +23,9 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use defmt::*;
 use embassy_executor::Spawner;
 use embassy_futures::join::join;
+use embassy_futures::select::{select, Either};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
 use {defmt_rtt as _, panic_probe as _};
 
 use embassy_usb::class::hid::{HidReaderWriter, ReportId, RequestHandler, State};
@@ -72,6 +78,7 @@ async fn main(_spawner: Spawner) {
     config.serial_number = Some("12345678");
     config.max_power = 100;
     config.max_packet_size_0 = 64;
+    config.supports_remote_wakeup = true;
 
     // Required for windows compatibility.
     // https://developer.nordicsemi.com/nRF_Connect_SDK/doc/1.9.1/kconfig/CONFIG_CDC_ACM_IAD.html#help
@@ -117,8 +124,22 @@ async fn main(_spawner: Spawner) {
     // Build the builder.
     let mut usb = builder.build();
 
-    // Run the USB device.
-    let usb_fut = usb.run();
+    let remote_wakeup: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+    // Run the USB device, waking the host when asked to while suspended.
+    let usb_fut = async {
+        loop {
+            usb.run_until_suspend().await;
+            match select(usb.wait_resume(), remote_wakeup.wait()).await {
+                Either::First(_) => (),
+                Either::Second(_) => {
+                    if let Err(e) = usb.remote_wakeup().await {
+                        warn!("Remote wakeup failed: {:?}", e);
+                    }
+                }
+            }
+        }
+    };
 
     let (reader, mut writer) = hid.split();
 
@@ -130,6 +151,12 @@ async fn main(_spawner: Spawner) {
         loop {
             button.wait_for_rising_edge().await;
             info!("Button pressed!");
+            if sifli_hal::usb::is_suspended() {
+                info!("Host suspended, triggering remote wakeup");
+                remote_wakeup.signal(());
+                button.wait_for_falling_edge().await;
+                continue;
+            }
             // Create a report with the A key pressed. (no shift modifier)
             let report = KeyboardReport {
                 keycodes: [4, 0, 0, 0, 0, 0],
@@ -233,4 +260,12 @@ impl Handler for MyDeviceHandler {
             info!("Device is no longer configured, the Vbus current limit is 100mA.");
         }
     }
+
+    fn suspended(&mut self, suspended: bool) {
+        if suspended {
+            info!("Device suspended, the Vbus current limit is 2.5mA");
+        } else {
+            info!("Device resumed");
+        }
+    }
 }
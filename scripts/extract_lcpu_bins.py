@@ -100,6 +100,11 @@ def main():
                 f.write(data)
             print(f"    {len(data)} bytes")
 
+    print("\nSources (keep the `C name:` doc comments in sifli-hal/src/lcpu/mod.rs in sync):")
+    for sdk_path, extractions in SDK_FILES.items():
+        for out_name, symbol in extractions:
+            print(f"  {out_name}: C name `{symbol}` ({sdk_path})")
+
     print("\nDone.")
 
 
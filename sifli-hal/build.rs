@@ -152,6 +152,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let pin_impls = generate_pin_impls(&pinmux, &pinmux_signals, &cfg_ir.fieldsets);
     token_stream.extend(pin_impls);
 
+    // Generate per-pin valid FSEL table
+    let pin_fsel_masks = generate_pin_fsel_masks(&pinmux);
+    token_stream.extend(pin_fsel_masks);
+
     // Generate ADC implementations
     let adc_impls = generate_adc_impls(&adc);
     token_stream.extend(adc_impls);
@@ -239,6 +243,11 @@ fn generate_rcc_impls(
                     while !crate::pac::HPSYS_RCC.#rstr_reg_ident().read().#field_name_ident() {};
                     crate::pac::HPSYS_RCC.#rstr_reg_ident().modify(|w| w.#field_set_ident(false));
                 }
+
+                #[inline(always)]
+                fn rcc_is_enabled() -> bool {
+                    crate::pac::HPSYS_RCC.#enr_reg_ident().read().#field_name_ident()
+                }
             }
             impl crate::rcc::RccEnableReset for #peripheral_name_ident {}
         };
@@ -308,6 +317,11 @@ fn generate_rcc_impls(
                                 while !crate::pac::LPSYS_RCC.#rstr_reg_ident().read().#field_name_ident() {};
                                 crate::pac::LPSYS_RCC.#rstr_reg_ident().modify(|w| w.#field_set_ident(false));
                             }
+
+                            #[inline(always)]
+                            fn rcc_is_enabled() -> bool {
+                                crate::pac::LPSYS_RCC.#enr_reg_ident().read().#field_name_ident()
+                            }
                         }
                         impl crate::rcc::RccEnableReset for #peripheral_name_ident {}
                     };
@@ -347,12 +361,22 @@ fn generate_rcc_impls(
             });
             let clock_name_ident = format_ident!("{}", clock);
             let clock_token_ident = format_ident!("{}", domain.token);
+            let clock_enabled = if peripheral.enable_reset {
+                quote! {
+                    fn clock_enabled() -> bool {
+                        <Self as crate::rcc::SealedRccEnableReset>::rcc_is_enabled()
+                    }
+                }
+            } else {
+                quote! {}
+            };
             let peripheral_name_ident = format_ident!("{}", peripheral.name);
             let impl_tokens = quote! {
                 impl crate::rcc::SealedRccGetFreq for #peripheral_name_ident {
                     fn get_freq() -> Option<Hertz> {
                         crate::rcc::clocks().#clock_name_ident.into()
                     }
+                    #clock_enabled
                 }
                 impl crate::rcc::RccGetFreq for #peripheral_name_ident {
                     type Clock = crate::rcc::#clock_token_ident;
@@ -380,12 +404,22 @@ fn generate_rcc_impls(
                 .as_ref()
                 .unwrap_or_else(|| panic!("LPSYS clock domain '{}' must have a read_fn", clock));
             let read_fn_ident = format_ident!("{}", read_fn_name);
+            let clock_enabled = if peripheral.enable_reset {
+                quote! {
+                    fn clock_enabled() -> bool {
+                        <Self as crate::rcc::SealedRccEnableReset>::rcc_is_enabled()
+                    }
+                }
+            } else {
+                quote! {}
+            };
             let peripheral_name_ident = format_ident!("{}", peripheral.name);
             let impl_tokens = quote! {
                 impl crate::rcc::SealedRccGetFreq for #peripheral_name_ident {
                     fn get_freq() -> Option<Hertz> {
                         crate::rcc::#read_fn_ident()
                     }
+                    #clock_enabled
                 }
                 impl crate::rcc::RccGetFreq for #peripheral_name_ident {
                     type Clock = crate::rcc::#clock_token_ident;
@@ -546,6 +580,33 @@ fn generate_pin_impls(
     implementations
 }
 
+/// Generates `PIN_FSEL_MASKS`: for each PA pin, bit `n` is set if FSEL value
+/// `n` selects a function listed in pinmux.yaml.
+fn generate_pin_fsel_masks(pinmux: &build_serde::Pinmux) -> TokenStream {
+    let mut masks: Vec<u16> = Vec::new();
+
+    for pin in &pinmux.hcpu {
+        let Some(num) = pin.pin.strip_prefix("GPIO_A") else {
+            continue;
+        };
+        let num: usize = num
+            .parse()
+            .unwrap_or_else(|_| panic!("bad pin name {}", pin.pin));
+        if masks.len() <= num {
+            masks.resize(num + 1, 0);
+        }
+        for func in &pin.functions {
+            assert!(func.value < 16, "FSEL {} out of range for {}", func.value, pin.pin);
+            masks[num] |= 1 << func.value;
+        }
+    }
+
+    let len = masks.len();
+    quote! {
+        pub(crate) const PIN_FSEL_MASKS: [u16; #len] = [#(#masks),*];
+    }
+}
+
 fn generate_signal_impls(
     implementations: &mut TokenStream,
     signal_def: &build_serde::SignalDefinition,
@@ -924,7 +985,8 @@ fn generate_dma_impls(dma: &build_serde::Dma) -> TokenStream {
                 implementations.extend(quote! {
                     dma_channel_impl!(#channel_ident, #channel_index);
 
-                    // Note: DMAC2 interrupts are LCPU-side, not handled here
+                    // DMAC2 interrupts are LCPU-side; HCPU services these
+                    // channels by polling (see `AnyChannel::poll_irq`).
                 });
             }
         }
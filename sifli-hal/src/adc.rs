@@ -5,25 +5,49 @@ use core::marker::PhantomData;
 use core::sync::atomic::{compiler_fence, AtomicBool, Ordering};
 use core::task::Poll;
 
-use embassy_hal_internal::Peripheral;
+use embassy_hal_internal::{into_ref, Peripheral};
 use embassy_sync::waitqueue::AtomicWaker;
 use sifli_pac::HPSYS_CFG;
 
 use crate::_generated::{FIRST_CHANNEL_PIN, VBAT_CHANNEL_ID, VOL_OFFSET, VOL_RATIO};
+use crate::dma::{Transfer, TransferOptions};
 use crate::gpio::{self, Analog};
 use crate::interrupt::typelevel::Binding;
 use crate::interrupt::InterruptExt;
 use crate::mode::{Async, Blocking, Mode};
-use crate::pac::gpadc::vals as AdcVals;
+use crate::ownership::Claim;
 use crate::pac::GPADC;
 use crate::peripherals;
 use crate::{blocking_delay_us, interrupt, rcc};
 
+/// GPADC reference voltage (VSP) selection; the variant name is the nominal
+/// reference in volts.
+pub use crate::pac::gpadc::vals::Vsp;
+
 static WAKER: AtomicWaker = AtomicWaker::new();
 static IRQ_DONE: AtomicBool = AtomicBool::new(false);
+/// Set while an [`Adc`] exists; the GPADC LDO and slot setup are global.
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// ADC input mode.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InputMode {
+    /// Each channel is measured against ground.
+    #[default]
+    SingleEnded,
+    /// A channel is measured against its paired negative channel.
+    /// See [`Channel::new_differential`] for valid pairs.
+    Differential,
+}
 
 /// ADC configuration.
 /// f_ADCCLK = f_PCLK / (DATA_SAMP_DLY + CONV_WIDTH + SAMP_WIDTH + 2)
+///
+/// A single conversion takes one ADCCLK period: 194 PCLK cycles with the
+/// default widths, i.e. about 1.6 µs at PCLK = 120 MHz and 52 µs at
+/// 3.75 MHz. The 200 µs LDO and core settling waits before each read are not
+/// counted against [`timeout_us`](Self::timeout_us).
 #[non_exhaustive]
 pub struct Config {
     /// Sample width in ADCCLK cycles. Affects sample rate.
@@ -32,6 +56,31 @@ pub struct Config {
     pub conv_width: u8,
     /// Data sample delay in PCLK cycles. Affects sample rate.
     pub data_samp_dly: u8,
+    /// Single-ended or differential input.
+    pub input_mode: InputMode,
+    /// Reference voltage (`ANAU_GPADC_VSP`). Sets the full-scale input range:
+    /// the input is scaled against this reference, so a lower VSP gives finer
+    /// resolution over a smaller range and a higher VSP the opposite.
+    ///
+    /// The factory calibration behind [`Sample::to_mv`] (`VOL_OFFSET` /
+    /// `VOL_RATIO`) is only valid for the default [`Vsp::V0_642`]. The SDK
+    /// does not characterise the input range of the other settings; measure
+    /// two known voltages and convert raw samples yourself.
+    pub vsp: Vsp,
+    /// Common-mode voltage trim (`ANAU_GPADC_CMM`, 5 bits). Default `0x10`.
+    pub cmm: u8,
+    /// Set for a 1.8 V analog supply (`ANAU_GPADC_EN_V18`). SF32LB52x boards
+    /// run AVDD at 3.3 V, so the default is `false`.
+    pub en_v18: bool,
+    /// How long a single conversion may take before the read fails with
+    /// [`Error::Timeout`], in µs. Default 1000, well above the conversion
+    /// time at any PCLK setting. The async read only enforces it with the
+    /// `time` feature.
+    pub timeout_us: u32,
+    /// NVIC priority of the GPADC interrupt in async mode. Default
+    /// [`Priority::P0`](interrupt::Priority::P0), the reset value; lower it
+    /// below latency-sensitive interrupts such as audio DMA.
+    pub irq_priority: interrupt::Priority,
 }
 
 impl Default for Config {
@@ -41,6 +90,12 @@ impl Default for Config {
             sample_width: 0x71,
             conv_width: 75,
             data_samp_dly: 0x4,
+            input_mode: InputMode::SingleEnded,
+            vsp: Vsp::V0_642,
+            cmm: 0x10,
+            en_v18: false,
+            timeout_us: 1000,
+            irq_priority: interrupt::Priority::P0,
         }
     }
 }
@@ -49,8 +104,22 @@ impl Default for Config {
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
-    /// Conversion failed.
-    ConversionFailed,
+    /// The conversion did not complete within [`Config::timeout_us`]. The
+    /// ADC core is powered down again unless [`Adc::enable`]d; the next read
+    /// starts from scratch.
+    Timeout,
+    /// The result is at full scale (or, in differential mode, at either end
+    /// of the range), so the input is outside what [`Config::vsp`] can
+    /// measure and the code does not represent its voltage.
+    Saturated,
+    /// The channel type does not match the ADC's [`InputMode`].
+    InputModeMismatch,
+    /// A non-blocking conversion is already in progress on another channel.
+    Busy,
+    /// The GPADC has no hardware for the requested operation.
+    Unsupported,
+    /// [`Adc::read_nb`] was called before [`Adc::enable`].
+    NotEnabled,
 }
 
 /// ADC sample.
@@ -68,6 +137,9 @@ impl Sample {
     }
 
     /// Convert the sample to millivolts.
+    ///
+    /// Uses the factory calibration, valid only with the default
+    /// [`Config::vsp`], `cmm` and `en_v18`.
     pub fn to_mv(&self) -> u16 {
         if self.0 <= VOL_OFFSET {
             0 // Below the offset, return 0 mV
@@ -78,6 +150,15 @@ impl Sample {
         }
     }
 
+    /// Interpret the sample as a signed differential result.
+    ///
+    /// In differential mode the 12-bit result is offset binary: mid-scale
+    /// (`0x800`) means both inputs are equal. Positive values mean the
+    /// positive input is higher.
+    pub fn to_signed(&self) -> i16 {
+        (self.0 & 0xfff) as i16 - 0x800
+    }
+
     /// Convert the sample to volts (float).
     pub fn to_v_float(&self) -> f32 {
         if self.0 <= VOL_OFFSET {
@@ -93,12 +174,15 @@ impl Sample {
 /// An ADC channel, which can be a pin or an internal source.
 pub struct Channel<'p> {
     pub id: u8,
+    /// Negative input channel for differential channels.
+    neg_id: Option<u8>,
     phantom: PhantomData<&'p ()>,
 }
 
 /// A GPIO pin that can be used as an ADC channel.
 ///
-/// The user must configure the pin's MUX to the ADC function.
+/// The user must configure the pin's MUX to the ADC function, e.g. with
+/// [`gpio::set_function`]`(&mut pin, `[`gpio::Func::ANALOG`]`)`.
 pub trait AdcPin: gpio::Pin {
     fn adc_channel_id(&self) -> u8 {
         self.pin() - FIRST_CHANNEL_PIN
@@ -112,16 +196,56 @@ impl<'p> Channel<'p> {
         Analog::new(pin);
         Self {
             id,
+            neg_id: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create a differential ADC channel from a pair of GPIO pins.
+    ///
+    /// On SF32LB52x the differential pairs are adjacent channels with the
+    /// positive input on the even channel:
+    ///
+    /// | Pair | Positive     | Negative     |
+    /// |------|--------------|--------------|
+    /// | 0    | PA28 (CH0)   | PA29 (CH1)   |
+    /// | 1    | PA30 (CH2)   | PA31 (CH3)   |
+    /// | 2    | PA32 (CH4)   | PA33 (CH5)   |
+    ///
+    /// PA34 (CH6) and the VBAT monitor (CH7) can only be read single-ended.
+    /// The ADC must be created with [`InputMode::Differential`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pins do not form one of the pairs above.
+    pub fn new_differential(pos_pin: impl AdcPin + 'p, neg_pin: impl AdcPin + 'p) -> Self {
+        let id = pos_pin.adc_channel_id();
+        let neg_id = neg_pin.adc_channel_id();
+        assert!(
+            id % 2 == 0 && neg_id == id + 1 && neg_id < VBAT_CHANNEL_ID - 1,
+            "invalid ADC differential pair"
+        );
+        Analog::new(pos_pin);
+        Analog::new(neg_pin);
+        Self {
+            id,
+            neg_id: Some(neg_id),
             phantom: PhantomData,
         }
     }
 
+    /// Whether this is a differential channel.
+    pub fn is_differential(&self) -> bool {
+        self.neg_id.is_some()
+    }
+
     /// Create a new ADC channel for the internal battery voltage monitor.
     /// This corresponds to ADC channel 7.
     /// An ownership token for `ADC_VBAT` is required to ensure exclusive access.
     pub fn new_vbat(_vbat: impl Peripheral<P = peripherals::ADC_VBAT> + 'p) -> Self {
         Self {
             id: VBAT_CHANNEL_ID,
+            neg_id: None,
             phantom: PhantomData,
         }
     }
@@ -129,12 +253,22 @@ impl<'p> Channel<'p> {
 
 /// ADC driver.
 pub struct Adc<'d, M: Mode> {
+    input_mode: InputMode,
+    timeout_us: u32,
+    /// Channel of the conversion started by `read_nb`, if any.
+    pending: Option<u8>,
+    /// Kept powered up by [`Adc::enable`] between conversions.
+    enabled: bool,
+    _claim: Claim,
     _phantom: PhantomData<(&'d peripherals::GPADC, M)>,
 }
 
 impl<'d, M: Mode> Adc<'d, M> {
     /// Common initialization logic for both blocking and async modes.
     fn new_inner(_inner: impl Peripheral<P = peripherals::GPADC> + 'd, config: Config) -> Self {
+        if TAKEN.swap(true, Ordering::SeqCst) {
+            panic!("Adc: already taken");
+        }
         rcc::enable_and_reset::<peripherals::GPADC>();
         let regs = GPADC;
 
@@ -146,8 +280,9 @@ impl<'d, M: Mode> Adc<'d, M> {
         // This driver enables it but does not disable it on Drop, leaving that to the application owner.
         HPSYS_CFG.anau_cr().modify(|r| r.set_en_bg(true));
 
-        // 2. Set ADC to single-ended mode by default.
-        regs.cfg_reg1().modify(|r| r.set_anau_gpadc_se(true));
+        // 2. Select single-ended or differential input.
+        regs.cfg_reg1()
+            .modify(|r| r.set_anau_gpadc_se(config.input_mode == InputMode::SingleEnded));
 
         // 3. Configure timing/width parameters from the Config struct.
         regs.ctrl_reg2().write(|w| {
@@ -162,11 +297,15 @@ impl<'d, M: Mode> Adc<'d, M> {
             r.set_timer_trig_en(false);
         });
 
-        // 4. Set default analog tuning parameters from the C HAL for SF32LB52x.
+        // 4. Analog tuning parameters. The defaults match the C HAL for SF32LB52x.
+        assert!(config.cmm < 0x20, "adc: cmm is a 5-bit field");
+        if config.vsp != Vsp::V0_642 {
+            warn!("adc: non-default VSP, Sample::to_mv() calibration does not apply");
+        }
         regs.cfg_reg1().modify(|r| {
-            r.set_anau_gpadc_vsp(AdcVals::Vsp::V0_642); // Value '2'
-            r.set_anau_gpadc_cmm(0x10);
-            r.set_anau_gpadc_en_v18(false); // SF32LB52x is 3.3V AVDD
+            r.set_anau_gpadc_vsp(config.vsp);
+            r.set_anau_gpadc_cmm(config.cmm);
+            r.set_anau_gpadc_en_v18(config.en_v18);
         });
 
         // 5. Disable all conversion slots initially.
@@ -175,11 +314,61 @@ impl<'d, M: Mode> Adc<'d, M> {
         }
 
         Self {
+            input_mode: config.input_mode,
+            timeout_us: config.timeout_us,
+            pending: None,
+            enabled: false,
+            _claim: Claim::new::<peripherals::GPADC>(),
             _phantom: PhantomData,
         }
     }
 
-    /// Prepares the ADC for a conversion by powering it up and waiting for stabilization.
+    /// Check that the channel matches the configured input mode.
+    fn check_channel(&self, channel: &Channel) -> Result<(), Error> {
+        let differential = self.input_mode == InputMode::Differential;
+        if channel.is_differential() != differential {
+            return Err(Error::InputModeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Select the positive (and, for differential channels, negative) input.
+    fn select_channel(&mut self, channel: &Channel) {
+        GPADC.cfg_reg1().modify(|r| {
+            r.set_anau_gpadc_sel_pch(channel.id);
+            if let Some(neg_id) = channel.neg_id {
+                r.set_anau_gpadc_sel_nch(neg_id);
+            }
+        });
+    }
+
+    /// Power up the ADC and keep it powered between conversions.
+    ///
+    /// Blocks for the LDO and core settling delays (~400us). Afterwards
+    /// conversions skip the power-up, and [`read_nb`](Adc::read_nb), which
+    /// must not block, requires it. [`disable`](Self::disable) powers the
+    /// ADC down again.
+    pub fn enable(&mut self) {
+        if !self.enabled {
+            self.power_up();
+            self.enabled = true;
+        }
+    }
+
+    /// Power the ADC down after [`enable`](Self::enable).
+    ///
+    /// A conversion started by [`read_nb`](Adc::read_nb) and not yet
+    /// collected is abandoned.
+    pub fn disable(&mut self) {
+        if self.enabled {
+            self.pending = None;
+            self.power_down();
+            self.enabled = false;
+        }
+    }
+
+    /// Prepares the ADC for a conversion by powering it up (unless
+    /// [`enable`](Self::enable)d) and connecting `channel`.
     fn prepare(&mut self, channel: &Channel) {
         // From manual and `HAL_ADC_Prepare`.
 
@@ -191,6 +380,13 @@ impl<'d, M: Mode> Adc<'d, M> {
         // Necessary! Otherwise the data is incorrect (but why?)
         GPADC.slot(channel.id as _).modify(|r| r.set_slot_en(true));
 
+        if !self.enabled {
+            self.power_up();
+        }
+    }
+
+    /// Power up the reference LDO and ADC core and wait for them to settle.
+    fn power_up(&mut self) {
         // 1. Enable the LDO that provides the reference voltage to the ADC.
         GPADC
             .cfg_reg1()
@@ -207,7 +403,8 @@ impl<'d, M: Mode> Adc<'d, M> {
         blocking_delay_us(200);
     }
 
-    /// Powers down ADC components after a conversion to save power.
+    /// Powers down ADC components after a conversion to save power, unless
+    /// the ADC is [`enable`](Self::enable)d.
     fn finish(&mut self, channel: &Channel) {
         // Reverse of the `prepare` sequence.
 
@@ -216,6 +413,12 @@ impl<'d, M: Mode> Adc<'d, M> {
             HPSYS_CFG.anau_cr().modify(|r| r.set_en_vbat_mon(false));
         }
 
+        if !self.enabled {
+            self.power_down();
+        }
+    }
+
+    fn power_down(&mut self) {
         GPADC.ctrl_reg().modify(|r| r.set_frc_en_adc(false));
         GPADC.cfg_reg1().modify(|r| {
             r.set_anau_gpadc_ldoref_en(false);
@@ -224,8 +427,22 @@ impl<'d, M: Mode> Adc<'d, M> {
         });
     }
 
+    /// Turn a raw single-conversion result into a [`Sample`].
+    fn sample(&self, raw: u16) -> Result<Sample, Error> {
+        let raw = raw & 0xfff;
+        let differential = self.input_mode == InputMode::Differential;
+        if raw == 0xfff || (differential && raw == 0) {
+            return Err(Error::Saturated);
+        }
+        Ok(Sample(raw))
+    }
+
     /// Perform a single conversion on a channel in blocking mode.
+    ///
+    /// Fails with [`Error::Timeout`] if the conversion does not complete
+    /// within [`Config::timeout_us`].
     pub fn blocking_read(&mut self, ch: &mut Channel) -> Result<Sample, Error> {
+        self.check_channel(ch)?;
         self.prepare(ch);
 
         // Use forced channel selection for single-shot conversions.
@@ -233,13 +450,21 @@ impl<'d, M: Mode> Adc<'d, M> {
             r.set_adc_op_mode(false); // Single conversion mode
             r.set_chnl_sel_frc_en(true); // Enable forced channel selection
         });
-        GPADC.cfg_reg1().modify(|r| r.set_anau_gpadc_sel_pch(ch.id));
+        self.select_channel(ch);
 
         // Start the conversion.
         GPADC.ctrl_reg().modify(|r| r.set_adc_start(true));
 
         // Poll for completion flag (GPADC_IRSR).
-        while !GPADC.gpadc_irq().read().gpadc_irsr() {}
+        let mut waited_us = 0;
+        while !GPADC.gpadc_irq().read().gpadc_irsr() {
+            if waited_us >= self.timeout_us {
+                self.finish(ch);
+                return Err(Error::Timeout);
+            }
+            blocking_delay_us(1);
+            waited_us += 1;
+        }
 
         // Clear the interrupt flag by writing 1 to ICR.
         GPADC.gpadc_irq().write(|w| w.set_gpadc_icr(true));
@@ -249,7 +474,14 @@ impl<'d, M: Mode> Adc<'d, M> {
 
         self.finish(ch);
 
-        Ok(Sample(result & 0xfff))
+        self.sample(result)
+    }
+
+    /// Perform a single differential conversion in blocking mode.
+    ///
+    /// Returns the signed result, see [`Sample::to_signed`].
+    pub fn blocking_read_differential(&mut self, ch: &mut Channel) -> Result<i16, Error> {
+        self.blocking_read(ch).map(|s| s.to_signed())
     }
 }
 
@@ -262,6 +494,8 @@ impl<'d, M: Mode> Drop for Adc<'d, M> {
             .modify(|r| r.set_anau_gpadc_ldoref_en(false));
         // The shared HPSYS bandgap (`EN_BG`) is not disabled here.
         // The application is responsible for managing it if it's no longer needed by any peripheral.
+
+        TAKEN.store(false, Ordering::SeqCst);
     }
 }
 
@@ -271,12 +505,72 @@ impl<'d> Adc<'d, Blocking> {
     /// - `inner`: The ADC peripheral singleton.
     /// - `hpsys`: The HPSYS_CFG peripheral singleton, required for managing shared analog resources.
     /// - `config`: ADC timing and operational configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another `Adc` exists (e.g. one built from a stolen
+    /// peripheral).
     pub fn new_blocking(
         inner: impl Peripheral<P = peripherals::GPADC> + 'd,
         config: Config,
     ) -> Self {
         Self::new_inner(inner, config)
     }
+
+    /// Non-blocking single conversion (`nb` style).
+    ///
+    /// The ADC must be powered up with a blocking [`enable`](Adc::enable)
+    /// call first, since its settling delays cannot be polled; otherwise
+    /// this fails with [`Error::NotEnabled`]. The first call then connects
+    /// `ch` and starts a conversion and returns `WouldBlock`; later calls
+    /// only poll the completion flag. The ADC stays powered after the result
+    /// is returned. Polling with a different channel while a conversion is
+    /// pending returns [`Error::Busy`].
+    ///
+    /// ```rust,ignore
+    /// adc.enable();
+    /// loop {
+    ///     match adc.read_nb(&mut ch) {
+    ///         Ok(sample) => handle(sample),
+    ///         Err(nb::Error::WouldBlock) => do_other_work(),
+    ///         Err(nb::Error::Other(e)) => panic!("{:?}", e),
+    ///     }
+    /// }
+    /// ```
+    pub fn read_nb(&mut self, ch: &mut Channel) -> nb::Result<Sample, Error> {
+        match self.pending {
+            None => {
+                if !self.enabled {
+                    return Err(nb::Error::Other(Error::NotEnabled));
+                }
+                self.check_channel(ch)?;
+                self.prepare(ch);
+
+                GPADC.ctrl_reg().modify(|r| {
+                    r.set_adc_op_mode(false);
+                    r.set_chnl_sel_frc_en(true);
+                });
+                self.select_channel(ch);
+                GPADC.ctrl_reg().modify(|r| r.set_adc_start(true));
+
+                self.pending = Some(ch.id);
+                Err(nb::Error::WouldBlock)
+            }
+            Some(id) if id != ch.id => Err(nb::Error::Other(Error::Busy)),
+            Some(_) => {
+                if !GPADC.gpadc_irq().read().gpadc_irsr() {
+                    return Err(nb::Error::WouldBlock);
+                }
+                GPADC.gpadc_irq().write(|w| w.set_gpadc_icr(true));
+
+                let result = GPADC.rdata(0).read().even_slot_rdata();
+                self.finish(ch);
+                self.pending = None;
+
+                self.sample(result).map_err(nb::Error::Other)
+            }
+        }
+    }
 }
 
 /// ADC interrupt handler.
@@ -294,14 +588,21 @@ impl interrupt::typelevel::Handler<interrupt::typelevel::GPADC> for InterruptHan
 
 impl<'d> Adc<'d, Async> {
     /// Create a new ADC driver in asynchronous mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another `Adc` exists (e.g. one built from a stolen
+    /// peripheral).
     pub fn new(
         inner: impl Peripheral<P = peripherals::GPADC> + 'd,
         _irq: impl Binding<interrupt::typelevel::GPADC, InterruptHandler>,
         config: Config,
     ) -> Self {
+        let irq_priority = config.irq_priority;
         let s = Self::new_inner(inner, config);
 
         let irq = crate::interrupt::GPADC;
+        irq.set_priority(irq_priority);
         irq.unpend();
         unsafe { irq.enable() };
 
@@ -328,7 +629,11 @@ impl<'d> Adc<'d, Async> {
     }
 
     /// Perform a single conversion on a channel asynchronously.
+    ///
+    /// With the `time` feature, fails with [`Error::Timeout`] if the
+    /// conversion does not complete within [`Config::timeout_us`].
     pub async fn read(&mut self, ch: &mut Channel<'_>) -> Result<Sample, Error> {
+        self.check_channel(ch)?;
         self.prepare(ch);
 
         // Configure for single-shot forced channel conversion.
@@ -336,7 +641,7 @@ impl<'d> Adc<'d, Async> {
             r.set_adc_op_mode(false);
             r.set_chnl_sel_frc_en(true);
         });
-        GPADC.cfg_reg1().modify(|r| r.set_anau_gpadc_sel_pch(ch.id));
+        self.select_channel(ch);
 
         // Enable interrupt and start conversion.
         GPADC.gpadc_irq().modify(|r| r.set_gpadc_imr(false));
@@ -346,13 +651,105 @@ impl<'d> Adc<'d, Async> {
         compiler_fence(Ordering::SeqCst);
 
         GPADC.ctrl_reg().modify(|r| r.set_adc_start(true));
+        #[cfg(feature = "time")]
+        {
+            let timeout = embassy_time::Duration::from_micros(self.timeout_us as u64);
+            if embassy_time::with_timeout(timeout, self.wait_for_completion())
+                .await
+                .is_err()
+            {
+                self.finish(ch);
+                return Err(Error::Timeout);
+            }
+        }
+        #[cfg(not(feature = "time"))]
         self.wait_for_completion().await;
 
         let result = GPADC.rdata(0).read().even_slot_rdata();
 
         self.finish(ch);
 
-        Ok(Sample(result & 0xfff))
+        self.sample(result)
+    }
+
+    /// Perform a single differential conversion asynchronously.
+    ///
+    /// Returns the signed result, see [`Sample::to_signed`].
+    pub async fn read_differential(&mut self, ch: &mut Channel<'_>) -> Result<i16, Error> {
+        self.read(ch).await.map(|s| s.to_signed())
+    }
+
+    /// Wait for a conversion of `ch` outside `[low, high]`.
+    ///
+    /// Always fails with [`Error::Unsupported`]: the SF32LB52x GPADC has no
+    /// analog watchdog or threshold comparator. `GPADC_IRQ` only signals
+    /// conversion done, so every sample would have to wake the CPU to be
+    /// compared, which is what this call was meant to avoid.
+    ///
+    /// For low duty-cycle level detection, take single [`read`](Self::read)s
+    /// from a timer and power the ADC down in between; for an edge that
+    /// must wake the chip, route the signal to a GPIO or PMU wakeup pin.
+    pub async fn watch(
+        &mut self,
+        ch: &mut Channel<'_>,
+        low: u16,
+        high: u16,
+    ) -> Result<Sample, Error> {
+        let _ = (ch, low, high);
+        Err(Error::Unsupported)
+    }
+
+    /// Convert `ch` continuously until `buf` is full, moving samples by DMA.
+    ///
+    /// The GPADC data register is 16 bits wide, so the buffer is `u16` and the
+    /// DMA runs with 16-bit peripheral and memory words; a byte buffer would
+    /// only capture the low half of each sample. Samples are raw 12-bit codes
+    /// as returned by [`Sample::value`].
+    ///
+    /// ```rust,ignore
+    /// let mut buf = [0u16; 256];
+    /// adc.read_continuous(p.DMAC1_CH2, &mut ch, &mut buf).await?;
+    /// ```
+    pub async fn read_continuous(
+        &mut self,
+        dma: impl Peripheral<P = impl Dma<peripherals::GPADC>>,
+        ch: &mut Channel<'_>,
+        buf: &mut [u16],
+    ) -> Result<(), Error> {
+        into_ref!(dma);
+        self.check_channel(ch)?;
+        self.prepare(ch);
+
+        GPADC.ctrl_reg().modify(|r| {
+            r.set_adc_op_mode(true); // Continuous conversion mode
+            r.set_chnl_sel_frc_en(true);
+            r.set_dma_en(true);
+        });
+        self.select_channel(ch);
+
+        let request = dma.request();
+        let transfer = unsafe {
+            Transfer::new_read(
+                dma,
+                request,
+                GPADC.dma_rdata().as_ptr() as *mut u16,
+                &mut *buf,
+                TransferOptions::default(),
+            )
+        };
+        GPADC.ctrl_reg().modify(|r| r.set_adc_start(true));
+        transfer.await;
+
+        GPADC.ctrl_reg().modify(|r| {
+            r.set_adc_op_mode(false);
+            r.set_dma_en(false);
+        });
+        self.finish(ch);
+
+        for sample in buf.iter_mut() {
+            *sample &= 0xfff;
+        }
+        Ok(())
     }
 }
 
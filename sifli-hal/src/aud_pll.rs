@@ -12,11 +12,63 @@
 //! ```ignore
 //! use sifli_hal::aud_pll::{AudioPll, AudPllFreq};
 //!
-//! let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+//! let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
 //! let dac = audio::AudioDac::new_blocking(p.AUDPRC, p.DMAC1_CH1, &pll, config);
 //! ```
+//!
+//! # Keeping the PLL running
+//!
+//! Dropping an [`AudioPll`] powers the PLL, bandgap and refgen down. Systems
+//! that repeatedly create and drop audio drivers can instead call
+//! [`AudioPll::leak`] to keep the PLL locked, and later [`AudioPll::reclaim`]
+//! it without repeating VCO calibration (~300µs plus bandgap settling).
+//! The tradeoff is that the analog blocks keep drawing current while no
+//! audio driver is active.
+//!
+//! ```ignore
+//! let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
+//! // ... use audio drivers ...
+//! pll.leak();
+//!
+//! // later
+//! let pll = AudioPll::reclaim()
+//!     .unwrap_or_else(|| AudioPll::new(AudPllFreq::Mhz49_152, Default::default()));
+//! ```
+//!
+//! # Loop tuning
+//!
+//! The charge pump current and loop filter codes default to the values the
+//! SDK uses, which lock cleanly on the reference boards. Boards with a noisy
+//! HXT or AVDD may need a different loop bandwidth; [`AudPllConfig`] exposes
+//! the raw codes for that bring-up work:
+//!
+//! ```ignore
+//! let config = AudPllConfig::default().with_icp_sel(6);
+//! let pll = AudioPll::try_new(AudPllFreq::Mhz49_152, config)?;
+//! ```
+//!
+//! # Following an external clock
+//!
+//! A sink whose samples arrive on someone else's clock (Bluetooth audio, an
+//! I2S master) drifts against the local PLL by the two crystals' ppm
+//! difference. Instead of resampling, the PLL can be pulled towards the
+//! source by trimming the fractional SDM input (`SDIN`):
+//!
+//! ```ignore
+//! // Once a second: source MCLK measured against the local timebase.
+//! let measured = 12_288_130; // Hz, nominally 12.288 MHz
+//! pll.track_mclk(measured, 12_288_000)?;
+//! ```
+//!
+//! One `SDIN` step is `6 MHz / 2^20` (about 5.72 Hz, 0.116 ppm at
+//! 49.152 MHz). The loop stays locked across trims, since only the SDM
+//! fraction changes, but the VCO band is calibrated for the nominal
+//! frequency, so the trim is limited to [`MAX_SDIN_TRIM`] (about ±950 ppm),
+//! far beyond the ±100 ppm of two real crystals. [`AudioPll::track_mclk`]
+//! moves at most [`MAX_SDIN_TRIM_STEP`] per call so the output clock slews
+//! instead of jumping.
 
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use crate::pac;
 use crate::rcc;
@@ -60,6 +112,21 @@ impl AudPllFreq {
             Self::Mhz45_1584 => 45_158_400,
         }
     }
+
+    fn to_bits(self) -> u8 {
+        match self {
+            Self::Mhz49_152 => 1,
+            Self::Mhz45_1584 => 2,
+        }
+    }
+
+    fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            1 => Some(Self::Mhz49_152),
+            2 => Some(Self::Mhz45_1584),
+            _ => None,
+        }
+    }
 }
 
 /// Audio sample rate.
@@ -145,17 +212,142 @@ impl SampleRate {
     }
 }
 
+/// Analog loop parameters of the audio PLL.
+///
+/// These are raw register codes (charge pump current in `PLL_CFG0.ICP_SEL`,
+/// loop filter components in `PLL_CFG1`). The defaults match the SDK and
+/// should only be changed while tuning lock behaviour on a specific board.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AudPllConfig {
+    /// Charge pump current code, 0-15 (default: 8).
+    pub icp_sel: u8,
+    /// Loop filter R3 code, 0-15 (default: 3).
+    pub r3_sel: u8,
+    /// Loop filter RZ code, 0-15 (default: 1).
+    pub rz_sel: u8,
+    /// Loop filter C2 code, 0-7 (default: 3).
+    pub c2_sel: u8,
+    /// Loop filter CZ code, 0-7 (default: 6).
+    pub cz_sel: u8,
+}
+
+impl AudPllConfig {
+    const ICP_SEL_MAX: u8 = 0xF;
+    const R3_SEL_MAX: u8 = 0xF;
+    const RZ_SEL_MAX: u8 = 0xF;
+    const C2_SEL_MAX: u8 = 0x7;
+    const CZ_SEL_MAX: u8 = 0x7;
+
+    /// Set the charge pump current code, 0-15.
+    pub const fn with_icp_sel(mut self, icp_sel: u8) -> Self {
+        self.icp_sel = icp_sel;
+        self
+    }
+
+    /// Set the loop filter R3 code, 0-15.
+    pub const fn with_r3_sel(mut self, r3_sel: u8) -> Self {
+        self.r3_sel = r3_sel;
+        self
+    }
+
+    /// Set the loop filter RZ code, 0-15.
+    pub const fn with_rz_sel(mut self, rz_sel: u8) -> Self {
+        self.rz_sel = rz_sel;
+        self
+    }
+
+    /// Set the loop filter C2 code, 0-7.
+    pub const fn with_c2_sel(mut self, c2_sel: u8) -> Self {
+        self.c2_sel = c2_sel;
+        self
+    }
+
+    /// Set the loop filter CZ code, 0-7.
+    pub const fn with_cz_sel(mut self, cz_sel: u8) -> Self {
+        self.cz_sel = cz_sel;
+        self
+    }
+
+    /// Check that every code fits its register field.
+    pub fn validate(&self) -> Result<(), Error> {
+        let fields = [
+            (self.icp_sel, Self::ICP_SEL_MAX),
+            (self.r3_sel, Self::R3_SEL_MAX),
+            (self.rz_sel, Self::RZ_SEL_MAX),
+            (self.c2_sel, Self::C2_SEL_MAX),
+            (self.cz_sel, Self::CZ_SEL_MAX),
+        ];
+        if fields.iter().all(|&(value, max)| value <= max) {
+            Ok(())
+        } else {
+            Err(Error::InvalidConfig)
+        }
+    }
+}
+
+impl Default for AudPllConfig {
+    fn default() -> Self {
+        Self {
+            icp_sel: 8,
+            r3_sel: 3,
+            rz_sel: 1,
+            c2_sel: 3,
+            cz_sel: 6,
+        }
+    }
+}
+
+/// Audio PLL error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The PLL did not report lock within the timeout.
+    NotLocked,
+    /// An [`AudPllConfig`] code does not fit its register field.
+    InvalidConfig,
+    /// An `SDIN` trim beyond [`MAX_SDIN_TRIM`].
+    TrimOutOfRange,
+}
+
+/// Lock timeout used by [`AudioPll::new`] and [`AudioPll::try_new`].
+///
+/// Typical boards lock within the first 100 µs poll; the margin covers slow
+/// HXT startup on marginal boards.
+pub const LOCK_TIMEOUT_US: u32 = 2_000;
+
+/// Largest `SDIN` offset from nominal accepted by [`AudioPll::set_sdin_trim`],
+/// about ±950 ppm.
+pub const MAX_SDIN_TRIM: i32 = 8192;
+
+/// Largest `SDIN` change [`AudioPll::track_mclk`] applies per call (about
+/// 7.4 ppm).
+pub const MAX_SDIN_TRIM_STEP: i32 = 64;
+
+/// `SDIN` full scale: one FCW unit (6 MHz) is `2^20` SDIN steps.
+const SDIN_ONE: i64 = 1 << 20;
+
+/// SDM reference step for one FCW unit.
+const SDM_REF_HZ: i64 = 6_000_000;
+
 /// Singleton flag to prevent multiple AudioPll instances.
 static TAKEN: AtomicBool = AtomicBool::new(false);
 
+/// Frequency of a PLL left running by [`AudioPll::leak`] (0 = none).
+static PERSISTED: AtomicU8 = AtomicU8::new(0);
+
 /// Audio PLL driver.
 ///
 /// Manages the AUDCODEC PLL, bandgap, and reference generator.
 /// Only one instance can exist at a time (enforced by a runtime check).
 ///
-/// The PLL is initialized in [`new()`](Self::new) and shut down on [`Drop`].
+/// The PLL is initialized in [`new()`](Self::new) and shut down on [`Drop`],
+/// unless it is handed off with [`leak()`](Self::leak).
 pub struct AudioPll {
     freq: AudPllFreq,
+    /// `SDIN` offset from `freq.sdin()`, see [`set_sdin_trim`](Self::set_sdin_trim).
+    trim: i32,
 }
 
 fn delay_us(us: u32) {
@@ -174,17 +366,53 @@ impl AudioPll {
     /// 2. Bandgap + reference generator power-up (with ADC-required config)
     /// 3. PLL analog enable + VCO calibration
     /// 4. SDM frequency programming
-    /// 5. PLL lock check
+    /// 5. PLL lock check (see [`wait_locked`](Self::wait_locked))
     /// 6. Update RCC clocks cache
     ///
+    /// A PLL that fails to lock within [`LOCK_TIMEOUT_US`] is logged and left
+    /// running; use [`try_new`](Self::try_new) to get an error instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `AudioPll` instance already exists, or if `config` does
+    /// not pass [`AudPllConfig::validate`].
+    pub fn new(freq: AudPllFreq, config: AudPllConfig) -> Self {
+        if config.validate().is_err() {
+            panic!("AudioPll: invalid config {:?}", config);
+        }
+        let pll = Self::init(freq, &config);
+        if pll.wait_locked(LOCK_TIMEOUT_US).is_err() {
+            error!("AudioPll: not locked after {}us", LOCK_TIMEOUT_US);
+        }
+        pll
+    }
+
+    /// Like [`new`](Self::new), but returns [`Error::NotLocked`] (with the PLL
+    /// powered down again) if it does not lock within [`LOCK_TIMEOUT_US`], and
+    /// [`Error::InvalidConfig`] (without touching the hardware) for a bad
+    /// `config`.
+    ///
     /// # Panics
     ///
     /// Panics if an `AudioPll` instance already exists.
-    pub fn new(freq: AudPllFreq) -> Self {
+    pub fn try_new(freq: AudPllFreq, config: AudPllConfig) -> Result<Self, Error> {
+        config.validate()?;
+        let pll = Self::init(freq, &config);
+        // On error `pll` is dropped, which powers the PLL down.
+        pll.wait_locked(LOCK_TIMEOUT_US)?;
+        Ok(pll)
+    }
+
+    fn init(freq: AudPllFreq, config: &AudPllConfig) -> Self {
         if TAKEN.swap(true, Ordering::SeqCst) {
             panic!("AudioPll: already taken");
         }
 
+        // A leaked PLL is re-initialized from scratch; use `reclaim()` to reuse it.
+        if PERSISTED.swap(0, Ordering::SeqCst) != 0 {
+            Self::power_down();
+        }
+
         // Enable AUDCODEC clock
         rcc::enable::<crate::peripherals::AUDCODEC>();
 
@@ -216,17 +444,17 @@ impl AudioPll {
         codec.pll_cfg0().modify(|w| w.set_en_iary(true));
         codec.pll_cfg0().modify(|w| w.set_en_vco(true));
         codec.pll_cfg0().modify(|w| w.set_en_ana(true));
-        codec.pll_cfg0().modify(|w| w.set_icp_sel(8));
+        codec.pll_cfg0().modify(|w| w.set_icp_sel(config.icp_sel));
         codec.pll_cfg2().modify(|w| w.set_en_dig(true));
         codec.pll_cfg3().modify(|w| w.set_en_sdm(true));
         codec.pll_cfg4().modify(|w| w.set_en_clk_dig(true));
 
         // Loop filter configuration
         codec.pll_cfg1().modify(|w| {
-            w.set_r3_sel(3);
-            w.set_rz_sel(1);
-            w.set_c2_sel(3);
-            w.set_cz_sel(6);
+            w.set_r3_sel(config.r3_sel);
+            w.set_rz_sel(config.rz_sel);
+            w.set_c2_sel(config.c2_sel);
+            w.set_cz_sel(config.cz_sel);
             w.set_csd_rst(false);
             w.set_csd_en(false);
         });
@@ -315,19 +543,6 @@ impl AudioPll {
         codec.pll_cfg2().modify(|w| w.set_rstb(true));
         delay_us(50);
 
-        // ===== PLL lock check =====
-        codec.pll_cfg1().modify(|w| {
-            w.set_csd_en(true);
-            w.set_csd_rst(true);
-        });
-        delay_us(50);
-        codec.pll_cfg1().modify(|w| w.set_csd_rst(false));
-        delay_us(100);
-        let locked = !codec.pll_stat().read().unlock();
-        if locked {
-            codec.pll_cfg1().modify(|w| w.set_csd_en(false));
-        }
-
         // PLL_CFG5: chopping clocks for bandgap and refgen
         codec.pll_cfg5().write(|w| {
             w.set_divb_clk_chop_bg(2);
@@ -344,9 +559,84 @@ impl AudioPll {
         });
 
         // Update clocks cache
-        Self::update_clocks_cache(Some(freq));
+        Self::update_clocks_cache(Some(freq.freq()));
+
+        Self { freq, trim: 0 }
+    }
+
+    /// Release this handle but keep the PLL powered and locked.
+    ///
+    /// The singleton is released, so a later [`reclaim()`](Self::reclaim) can pick
+    /// the running PLL back up without recalibration. The bandgap, refgen and
+    /// PLL keep drawing current until it is reclaimed and dropped, or until
+    /// [`new()`](Self::new) re-initializes it.
+    pub fn leak(self) {
+        PERSISTED.store(self.freq.to_bits(), Ordering::SeqCst);
+        core::mem::forget(self);
+        TAKEN.store(false, Ordering::SeqCst);
+    }
+
+    /// Re-acquire a PLL previously left running with [`leak()`](Self::leak).
+    ///
+    /// Returns `None` if no leaked PLL is running or an `AudioPll` instance
+    /// already exists.
+    pub fn reclaim() -> Option<Self> {
+        if TAKEN.swap(true, Ordering::SeqCst) {
+            return None;
+        }
 
-        Self { freq }
+        match AudPllFreq::from_bits(PERSISTED.swap(0, Ordering::SeqCst)) {
+            Some(freq) => {
+                // A leaked PLL keeps any trim it was running with.
+                let sdin = pac::AUDCODEC.pll_cfg3().read().sdin();
+                Some(Self {
+                    freq,
+                    trim: sdin as i32 - freq.sdin() as i32,
+                })
+            }
+            None => {
+                TAKEN.store(false, Ordering::SeqCst);
+                None
+            }
+        }
+    }
+
+    /// Wait for the PLL to report lock, for at most `timeout_us`.
+    ///
+    /// Re-arms the cycle-slip detector and polls `PLL_STAT.UNLOCK` every
+    /// 10 µs after a 100 µs settling time, disabling the detector again once
+    /// locked.
+    pub fn wait_locked(&self, timeout_us: u32) -> Result<(), Error> {
+        let codec = pac::AUDCODEC;
+
+        codec.pll_cfg1().modify(|w| {
+            w.set_csd_en(true);
+            w.set_csd_rst(true);
+        });
+        delay_us(50);
+        codec.pll_cfg1().modify(|w| w.set_csd_rst(false));
+        delay_us(100);
+
+        let mut waited = 0;
+        loop {
+            if !codec.pll_stat().read().unlock() {
+                codec.pll_cfg1().modify(|w| w.set_csd_en(false));
+                return Ok(());
+            }
+            if waited >= timeout_us {
+                return Err(Error::NotLocked);
+            }
+            delay_us(10);
+            waited += 10;
+        }
+    }
+
+    /// Whether the PLL is currently locked.
+    ///
+    /// Runs one detector cycle (~150 µs, blocking), so it is meant for
+    /// periodic health checks rather than tight loops.
+    pub fn is_locked(&self) -> bool {
+        self.wait_locked(0).is_ok()
     }
 
     /// Get the configured PLL frequency.
@@ -354,6 +644,64 @@ impl AudioPll {
         self.freq
     }
 
+    /// Current `SDIN` offset from the nominal setting of [`freq`](Self::freq).
+    pub fn sdin_trim(&self) -> i32 {
+        self.trim
+    }
+
+    /// Output frequency including the current trim, in Hz (rounded down).
+    pub fn trimmed_freq_hz(&self) -> u32 {
+        let sdin = self.freq.sdin() as i64 + self.trim as i64;
+        let fcw = self.freq.fcw() as i64 + 3;
+        ((fcw * SDIN_ONE + sdin) * SDM_REF_HZ / SDIN_ONE) as u32
+    }
+
+    /// Offset the SDM fraction by `trim` steps from nominal.
+    ///
+    /// Takes effect at the next SDM update without relocking; see the
+    /// [module docs](self#following-an-external-clock) for step size and
+    /// range. Returns [`Error::TrimOutOfRange`] beyond [`MAX_SDIN_TRIM`].
+    pub fn set_sdin_trim(&mut self, trim: i32) -> Result<(), Error> {
+        if trim.unsigned_abs() > MAX_SDIN_TRIM as u32 {
+            return Err(Error::TrimOutOfRange);
+        }
+        let sdin = (self.freq.sdin() as i32 + trim) as u32;
+        let codec = pac::AUDCODEC;
+        codec.pll_cfg3().modify(|w| w.set_sdm_update(false));
+        codec.pll_cfg3().modify(|w| {
+            w.set_sdin(sdin);
+            w.set_sdm_update(true);
+        });
+        self.trim = trim;
+        Self::update_clocks_cache(Some(self.trimmed_freq_hz()));
+        Ok(())
+    }
+
+    /// Pull the PLL towards an external master clock.
+    ///
+    /// `measured_hz` is the source MCLK as measured against the local
+    /// timebase and `nominal_hz` what it should be (any multiple of the
+    /// sample rate, e.g. 12.288 MHz or 256·fs). The PLL trim that would match
+    /// the source's ppm error is computed and approached by at most
+    /// [`MAX_SDIN_TRIM_STEP`] per call, clamped to [`MAX_SDIN_TRIM`].
+    /// Call it at the measurement rate, typically every 0.5-2 s. Returns the
+    /// trim now applied, or [`Error::TrimOutOfRange`] for a zero `nominal_hz`.
+    pub fn track_mclk(&mut self, measured_hz: u32, nominal_hz: u32) -> Result<i32, Error> {
+        if nominal_hz == 0 {
+            return Err(Error::TrimOutOfRange);
+        }
+        // PLL offset (Hz) = f_pll * (measured - nominal) / nominal, in SDIN steps.
+        let error = measured_hz as i128 - nominal_hz as i128;
+        let target = self.freq.freq() as i128 * error * SDIN_ONE as i128
+            / (nominal_hz as i128 * SDM_REF_HZ as i128);
+        let target = target.clamp(-(MAX_SDIN_TRIM as i128), MAX_SDIN_TRIM as i128) as i32;
+        let step = (target - self.trim).clamp(-MAX_SDIN_TRIM_STEP, MAX_SDIN_TRIM_STEP);
+        if step != 0 {
+            self.set_sdin_trim(self.trim + step)?;
+        }
+        Ok(self.trim)
+    }
+
     /// Assert that a sample rate is compatible with this PLL frequency.
     ///
     /// # Panics
@@ -370,12 +718,11 @@ impl AudioPll {
         );
     }
 
-    fn update_clocks_cache(freq: Option<AudPllFreq>) {
+    fn update_clocks_cache(freq_hz: Option<u32>) {
         unsafe {
             let mut clocks = *rcc::get_freqs();
-            match freq {
-                Some(f) => {
-                    let hz = f.freq();
+            match freq_hz {
+                Some(hz) => {
                     clocks.clk_aud_pll = Some(Hertz(hz)).into();
                     clocks.clk_aud_pll_div16 = Some(Hertz(hz / 16)).into();
                 }
@@ -387,10 +734,9 @@ impl AudioPll {
             rcc::set_freqs(clocks);
         }
     }
-}
 
-impl Drop for AudioPll {
-    fn drop(&mut self) {
+    /// Power down the PLL, bandgap, refgen and HXT audio buffer.
+    fn power_down() {
         let codec = pac::AUDCODEC;
 
         // Disable PLL
@@ -412,6 +758,12 @@ impl Drop for AudioPll {
 
         // Update clocks cache
         Self::update_clocks_cache(None);
+    }
+}
+
+impl Drop for AudioPll {
+    fn drop(&mut self) {
+        Self::power_down();
 
         // Release singleton
         TAKEN.store(false, Ordering::SeqCst);
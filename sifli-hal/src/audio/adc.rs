@@ -12,6 +12,7 @@ use embassy_hal_internal::{into_ref, PeripheralRef};
 use super::codec;
 use super::{AdcConfig, ChannelMode, Error, RxCh0Dma};
 use crate::aud_pll::AudioPll;
+use crate::dma::ringbuffer::Error as RingBufferError;
 use crate::dma::{ChannelAndRequest, ReadableRingBuffer, TransferOptions};
 use crate::mode::{Async, Blocking, Mode};
 use crate::pac;
@@ -146,6 +147,7 @@ impl<'d> AudioAdc<'d, Async> {
         Self::init_hardware(&config);
 
         use crate::interrupt::typelevel::Interrupt as _;
+        crate::interrupt::typelevel::AUDPRC::set_priority(config.irq_priority);
         crate::interrupt::typelevel::AUDPRC::unpend();
         unsafe { crate::interrupt::typelevel::AUDPRC::enable() };
 
@@ -313,8 +315,10 @@ impl<'d, M: Mode> Drop for AudioAdc<'d, M> {
     fn drop(&mut self) {
         rx_ch0_disable();
 
+        // Keep AUDPRC running if the DAC half of a duplex pair still plays.
         audprc().cfg().modify(|w| {
             w.set_adc_path_en(false);
+            w.set_enable(w.dac_path_en());
         });
 
         codec::shutdown_adc();
@@ -341,6 +345,11 @@ impl<'a> AudioInputStream<'a> {
     ///
     /// Returns the remaining number of elements available for immediate reading.
     ///
+    /// [`Error::Overrun`] means this reader fell a full buffer behind and
+    /// samples were dropped; the stream has already resynchronized and the
+    /// next read continues with fresh data. [`Error::Dma`] means the DMA
+    /// position became inconsistent.
+    ///
     /// Note: DMA buffers reside in SRAM which is configured as non-cacheable
     /// via MPU, so no D-Cache maintenance is needed for ring buffer reads.
     pub async fn read(&mut self, buf: &mut [u32]) -> Result<usize, Error> {
@@ -348,7 +357,10 @@ impl<'a> AudioInputStream<'a> {
             .ring
             .read_exact(buf)
             .await
-            .map_err(|_| Error::Overrun)?;
+            .map_err(|e| match e {
+                RingBufferError::Overrun => Error::Overrun,
+                RingBufferError::DmaUnsynced => Error::Dma,
+            })?;
 
         Ok(remaining)
     }
@@ -193,6 +193,7 @@ impl<'d> AudioDac<'d, Async> {
         Self::init_hardware(&config);
 
         use crate::interrupt::typelevel::Interrupt as _;
+        crate::interrupt::typelevel::AUDPRC::set_priority(config.irq_priority);
         crate::interrupt::typelevel::AUDPRC::unpend();
         unsafe { crate::interrupt::typelevel::AUDPRC::enable() };
 
@@ -403,9 +404,10 @@ impl<'d, M: Mode> Drop for AudioDac<'d, M> {
     fn drop(&mut self) {
         tx_ch0_disable();
 
+        // Keep AUDPRC running if the ADC half of a duplex pair still captures.
         audprc().cfg().modify(|w| {
-            w.set_enable(false);
             w.set_dac_path_en(false);
+            w.set_enable(w.adc_path_en());
         });
 
         codec::shutdown_dac();
@@ -17,7 +17,7 @@
 //! ```ignore
 //! use sifli_hal::aud_pll::{AudioPll, AudPllFreq};
 //!
-//! let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+//! let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
 //! let mut dac = audio::AudioDac::new_blocking(
 //!     p.AUDPRC,
 //!     p.DMAC1_CH1,
@@ -36,7 +36,7 @@
 //!     AUDPRC => audio::InterruptHandler;
 //! });
 //!
-//! let pll = AudioPll::new(AudPllFreq::Mhz49_152);
+//! let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
 //! let mut adc = audio::AudioAdc::new(
 //!     p.AUDPRC,
 //!     p.DMAC1_CH2,
@@ -50,6 +50,25 @@
 //!     stream.read(&mut buf).await.unwrap();
 //! }
 //! ```
+//!
+//! # Full-duplex Example (capture + playback)
+//!
+//! ```ignore
+//! let pll = AudioPll::new(AudPllFreq::Mhz49_152, Default::default());
+//! let (mut dac, mut adc) = audio::new_duplex(
+//!     p.AUDPRC,
+//!     p.DMAC1_CH1,
+//!     p.DMAC1_CH2,
+//!     &pll,
+//!     Irqs,
+//!     audio::DacConfig::default(),
+//!     audio::AdcConfig::default(),
+//! );
+//! // `adc` and `dac` can be moved into separate tasks.
+//! ```
+//!
+//! The external I2S1 interface is not supported yet; full-duplex operation
+//! uses the internal AUDCODEC paths.
 
 pub(crate) mod codec;
 mod adc;
@@ -58,8 +77,10 @@ mod dac;
 pub use adc::*;
 pub use dac::*;
 
-use embassy_hal_internal::Peripheral;
+use embassy_hal_internal::{into_ref, Peripheral};
 
+use crate::aud_pll::AudioPll;
+use crate::mode::Async;
 use crate::{interrupt, peripherals};
 
 pub use crate::aud_pll::SampleRate;
@@ -87,6 +108,9 @@ pub struct DacConfig {
     pub channel_mode: ChannelMode,
     /// DAC path coarse volume, 0-15 (default: 6).
     pub volume: u8,
+    /// NVIC priority of the shared AUDPRC interrupt (default: P0). The TX DMA
+    /// channel's priority is set separately by the DMA driver.
+    pub irq_priority: crate::interrupt::Priority,
 }
 
 impl Default for DacConfig {
@@ -95,6 +119,7 @@ impl Default for DacConfig {
             sample_rate: SampleRate::Hz48000,
             channel_mode: ChannelMode::Stereo,
             volume: 6,
+            irq_priority: crate::interrupt::Priority::P0,
         }
     }
 }
@@ -110,6 +135,9 @@ pub struct AdcConfig {
     pub channel_mode: ChannelMode,
     /// ADC path coarse volume, 0-15 (default: 6, 0dB).
     pub volume: u8,
+    /// NVIC priority of the shared AUDPRC interrupt (default: P0). The RX DMA
+    /// channel's priority is set separately by the DMA driver.
+    pub irq_priority: crate::interrupt::Priority,
 }
 
 impl Default for AdcConfig {
@@ -118,6 +146,7 @@ impl Default for AdcConfig {
             sample_rate: SampleRate::Hz48000,
             channel_mode: ChannelMode::Stereo,
             volume: 6,
+            irq_priority: crate::interrupt::Priority::P0,
         }
     }
 }
@@ -132,6 +161,47 @@ pub enum Error {
     Overrun,
 }
 
+/// Create a full-duplex audio driver: an [`AudioDac`] writer and an
+/// [`AudioAdc`] reader sharing AUDPRC, the audio PLL and the interrupt.
+///
+/// Each half owns its own DMA channel and can be used from a separate task.
+/// Dropping one half shuts down only its own path; AUDPRC stays enabled
+/// until both are gone.
+///
+/// # Constraints
+///
+/// TX and RX share AUDPRC's audio clock selection, so `dac_config` and
+/// `adc_config` must use the same sample rate. Both paths use 16-bit samples
+/// in 32-bit FIFO entries (see [`ChannelMode`]); the channel mode may differ.
+///
+/// # Panics
+///
+/// Panics if the sample rates differ or are incompatible with `pll`.
+pub fn new_duplex<'d>(
+    peri: impl Peripheral<P = peripherals::AUDPRC> + 'd,
+    tx_dma: impl Peripheral<P = impl TxCh0Dma<peripherals::AUDPRC>> + 'd,
+    rx_dma: impl Peripheral<P = impl RxCh0Dma<peripherals::AUDPRC>> + 'd,
+    pll: &'d AudioPll,
+    irq: impl interrupt::typelevel::Binding<interrupt::typelevel::AUDPRC, InterruptHandler>
+        + Copy
+        + 'd,
+    dac_config: DacConfig,
+    adc_config: AdcConfig,
+) -> (AudioDac<'d, Async>, AudioAdc<'d, Async>) {
+    assert_eq!(
+        dac_config.sample_rate, adc_config.sample_rate,
+        "audio duplex: TX and RX sample rates must match"
+    );
+    into_ref!(peri);
+    // SAFETY: each half only touches its own AUDPRC path (TX_CH0/DAC path vs
+    // RX_CH0/ADC path) and enables the shared parts without resetting them.
+    let dac_peri = unsafe { peri.clone_unchecked() };
+
+    let dac = AudioDac::new(dac_peri, tx_dma, pll, irq, dac_config);
+    let adc = AudioAdc::new(peri, rx_dma, pll, irq, adc_config);
+    (dac, adc)
+}
+
 // Instance trait for AUDPRC (singleton, needed for DMA trait system)
 #[allow(private_interfaces)]
 pub(crate) trait SealedInstance: crate::rcc::RccEnableReset {}
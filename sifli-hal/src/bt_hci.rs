@@ -7,6 +7,28 @@
 //! For lower-level access, [`IpcHciTransport`] provides the raw bt-hci
 //! `Transport` trait.
 //!
+//! For a plain advertising beacon without a host stack, see [`Beacon`]; for
+//! the matching observer role, see [`Scanner`].
+//!
+//! # Commands outside a host stack
+//!
+//! [`Beacon`], [`Scanner`] and the configuration helpers on
+//! [`BleController`] (filter accept and resolving lists, data length) send
+//! their HCI commands themselves and read the event stream until the
+//! controller answers. A host stack such as `trouble` reads the same stream,
+//! so the two must not run at the same time: call the helpers before the
+//! host stack starts, and use `Beacon`/`Scanner` only without one.
+//!
+//! # LCPU logging
+//!
+//! The LCPU's internal log cannot be enabled or tuned from HCPU: neither the
+//! A3 ROM nor the Letter Series (A4/B4) ROM has a log-level word in the ROM
+//! config area or elsewhere in shared memory, and neither forwards log
+//! messages to HCPU. What HCPU can see is the HCI traffic: with the `defmt`
+//! or `log` feature every packet to and from the controller is logged at
+//! debug level under the `[hci]` prefix. Controller state that the ROM does
+//! publish is available through [`BleController::stats`].
+//!
 //! # Example
 //!
 //! ```no_run
@@ -26,18 +48,39 @@
 //! }
 //! ```
 
+use core::cell::Cell;
+use core::future::Future;
+
 use bt_hci::cmd;
+use bt_hci::cmd::le::{
+    LeAddDeviceToFilterAcceptList, LeAddDeviceToResolvingList, LeClearFilterAcceptList,
+    LeClearResolvingList, LeReadMaximumDataLength, LeRemoveDeviceFromFilterAcceptList,
+    LeSetAddrResolutionEnable, LeSetAdvData, LeSetAdvEnable, LeSetAdvParams, LeSetDataLength,
+    LeSetDefaultPhy, LeSetRandomAddr, LeSetScanEnable, LeSetScanParams,
+    LeWriteSuggestedDefaultDataLength,
+};
 use bt_hci::controller::{Controller, ControllerCmdAsync, ControllerCmdSync, ExternalController};
 use bt_hci::data;
+use bt_hci::event::le::LeEvent;
+use bt_hci::event::EventKind;
+use bt_hci::param::{
+    AddrKind, AdvChannelMap, AdvFilterPolicy, AdvKind, AllPhys, BdAddr, ConnHandle,
+    CoreSpecificationVersion, Duration, LeAdvEventKind, LeScanKind, PhyMask, ScanningFilterPolicy,
+};
 use bt_hci::transport::{Transport, WithIndicator};
 use bt_hci::{ControllerToHostPacket, FixedSizeValue, HostToControllerPacket, ReadHci, ReadHciError, WriteHci};
+use embassy_futures::select::{select, Either};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex as BlockingMutex;
 use embassy_sync::mutex::Mutex;
 use embedded_io::ReadExactError;
 
 use crate::dma::Channel;
 use crate::ipc::{self, Error as IpcError, IpcQueue, IpcQueueRx, IpcQueueTx};
-use crate::lcpu::{Lcpu, LcpuConfig, LcpuError};
+use crate::lcpu::{
+    ActConfig, AddressType, BleConfig, ControllerConfig, Lcpu, LcpuConfig, LcpuError,
+};
+use crate::syscfg::{self, ChipRevision};
 use crate::{interrupt, peripherals, Peripheral};
 
 #[cfg(any(feature = "defmt", feature = "log"))]
@@ -135,6 +178,8 @@ pub enum BleInitError {
     Ipc(IpcError),
     /// LCPU power-on error.
     Lcpu(LcpuError),
+    /// Post-boot HCI configuration (default PHY, random address) failed.
+    Hci(cmd::Error<Error>),
 }
 
 #[cfg(feature = "defmt")]
@@ -143,6 +188,7 @@ impl defmt::Format for BleInitError {
         match self {
             BleInitError::Ipc(e) => defmt::write!(f, "IPC error: {}", e),
             BleInitError::Lcpu(e) => defmt::write!(f, "LCPU error: {:?}", defmt::Debug2Format(e)),
+            BleInitError::Hci(e) => defmt::write!(f, "HCI error: {:?}", defmt::Debug2Format(e)),
         }
     }
 }
@@ -159,6 +205,12 @@ impl From<LcpuError> for BleInitError {
     }
 }
 
+impl From<cmd::Error<Error>> for BleInitError {
+    fn from(e: cmd::Error<Error>) -> Self {
+        Self::Hci(e)
+    }
+}
+
 /// bt-hci Transport error type.
 #[derive(Debug)]
 pub enum Error {
@@ -318,6 +370,39 @@ impl Transport for IpcHciTransport {
     }
 }
 
+/// Identification of the running BLE controller, see [`BleController::version`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControllerVersion {
+    /// Chip revision; selects ROM controller (Letter Series) vs RAM image (A3).
+    pub revision: ChipRevision,
+    /// HCI version reported by the controller.
+    pub hci_version: CoreSpecificationVersion,
+    /// Controller build identifier (HCI subversion).
+    pub hci_subversion: u16,
+    /// LMP/LL version reported by the controller.
+    pub lmp_version: CoreSpecificationVersion,
+    /// Bluetooth SIG company identifier.
+    pub company_identifier: u16,
+    /// Controller patch/firmware identifier (LMP subversion).
+    pub lmp_subversion: u16,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ControllerVersion {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ControllerVersion {{ revision: {}, hci: {:?}/0x{:04X}, lmp: {:?}/0x{:04X}, company: 0x{:04X} }}",
+            self.revision,
+            defmt::Debug2Format(&self.hci_version),
+            self.hci_subversion,
+            defmt::Debug2Format(&self.lmp_version),
+            self.lmp_subversion,
+            self.company_identifier
+        )
+    }
+}
+
 /// High-level BLE controller that owns the LCPU and HCI transport.
 ///
 /// Wraps [`Lcpu`] (for power management) and [`ExternalController`] (for HCI
@@ -345,13 +430,23 @@ impl Transport for IpcHciTransport {
 pub struct BleController<const SLOTS: usize = 4> {
     lcpu: Lcpu,
     inner: ExternalController<IpcHciTransport, SLOTS>,
+    /// Activity limits the controller was booted with.
+    act: ActConfig,
+    /// Own address type the controller was booted with.
+    own_addr_kind: AddrKind,
+    /// Last `LE_Data_Length_Change` event seen by [`read`](Controller::read).
+    data_len: BlockingMutex<CriticalSectionRawMutex, Cell<Option<DataLength>>>,
+    /// Configuration the controller was booted with, reused by
+    /// [`reset`](Self::reset).
+    config: LcpuConfig<'static>,
 }
 
 impl<const SLOTS: usize> BleController<SLOTS> {
     /// Initialize BLE and create a controller in one step.
     ///
     /// This combines IPC queue creation, LCPU BLE power-on (with scoped wake),
-    /// and controller construction.
+    /// and controller construction. `config` is kept for [`reset`](Self::reset),
+    /// so a firmware image it carries must be `'static`.
     pub async fn new(
         lcpu_peri: impl Peripheral<P = peripherals::LCPU> + 'static,
         mailbox: impl Peripheral<P = peripherals::MAILBOX1_CH1>,
@@ -360,7 +455,7 @@ impl<const SLOTS: usize> BleController<SLOTS> {
             interrupt::typelevel::MAILBOX2_CH1,
             ipc::InterruptHandler,
         >,
-        config: &LcpuConfig,
+        config: &LcpuConfig<'static>,
     ) -> Result<Self, BleInitError> {
         let mut ipc_driver = ipc::Ipc::new(mailbox, irq, ipc::Config::default());
         let queue = ipc_driver.open_queue(ipc::QueueConfig::qid0_hci())?;
@@ -368,16 +463,477 @@ impl<const SLOTS: usize> BleController<SLOTS> {
         let lcpu = Lcpu::new(lcpu_peri);
         lcpu.ble_power_on(config, dma_ch, &mut rx).await?;
         let transport = IpcHciTransport::from_parts(rx, tx);
-        Ok(Self {
+        let controller = Self {
             lcpu,
             inner: ExternalController::new(transport),
+            act: act_config(config),
+            own_addr_kind: own_addr_kind(&config.ble),
+            data_len: BlockingMutex::new(Cell::new(None)),
+            config: *config,
+        };
+        controller.apply_phy_config(&config.ble.controller).await?;
+        controller.apply_addr_config(&config.ble).await?;
+        Ok(controller)
+    }
+
+    /// Set the preferred PHYs from [`ControllerConfig::le_2m_phy`] and
+    /// [`ControllerConfig::le_coded_phy`] via HCI `LE_Set_Default_PHY`.
+    ///
+    /// With neither set the controller keeps its power-on default and no
+    /// command is sent.
+    async fn apply_phy_config(&self, config: &ControllerConfig) -> Result<(), cmd::Error<Error>> {
+        if !config.le_2m_phy && !config.le_coded_phy {
+            return Ok(());
+        }
+        let phys = PhyMask::new()
+            .set_le_1m_preferred(true)
+            .set_le_2m_preferred(config.le_2m_phy)
+            .set_le_coded_preferred(config.le_coded_phy);
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeSetDefaultPhy::new(AllPhys::new(), phys, phys)).await
+        })
+        .await
+    }
+
+    /// Set the random static address from [`BleConfig::bd_addr`] via HCI
+    /// `LE_Set_Random_Address`. A public address is already in NVDS.
+    async fn apply_addr_config(&self, config: &BleConfig) -> Result<(), cmd::Error<Error>> {
+        if config.addr_type != AddressType::RandomStatic {
+            return Ok(());
+        }
+        let addr = BdAddr::new(config.bd_addr);
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeSetRandomAddr::new(addr)).await
+        })
+        .await
+    }
+
+    /// Own address type to use in advertising, scanning and initiating
+    /// parameters: [`AddrKind::RANDOM`] when booted with
+    /// [`AddressType::RandomStatic`], otherwise [`AddrKind::PUBLIC`].
+    ///
+    /// Host stacks that manage the address themselves (e.g. `trouble`'s
+    /// `set_random_address`) must be given the same random static address.
+    pub fn own_addr_kind(&self) -> AddrKind {
+        self.own_addr_kind
+    }
+
+    /// Query the running controller's version information.
+    ///
+    /// The LCPU does not publish a version field in the shared ROM config
+    /// area, so this issues HCI `Read_Local_Version_Information`. The
+    /// subversion fields identify the ROM build and the installed patch set,
+    /// which is what to compare against the requested [`LcpuConfig`] when
+    /// diagnosing field issues.
+    pub async fn version(&self) -> Result<ControllerVersion, cmd::Error<Error>> {
+        let ret =
+            ControllerCmdSync::exec(&self.inner, &cmd::info::ReadLocalVersionInformation::new())
+                .await?;
+        Ok(ControllerVersion {
+            revision: syscfg::read_idr().revision(),
+            hci_version: ret.hci_version,
+            hci_subversion: ret.hci_subversion,
+            lmp_version: ret.lmp_version,
+            company_identifier: ret.company_identifier,
+            lmp_subversion: ret.lmp_subversion,
+        })
+    }
+
+    /// Read BLE scheduler state for power/latency tuning.
+    ///
+    /// See [`SchedStats`](crate::lcpu::SchedStats) for which values are
+    /// available on each chip revision.
+    pub fn stats(&self) -> crate::lcpu::SchedStats {
+        crate::lcpu::controller::sched_stats()
+    }
+
+    /// Enable or disable controller sleep without rebooting the LCPU.
+    ///
+    /// With sleep enabled the controller powers down between connection and
+    /// advertising events. That saves current on an idle link, but every
+    /// event then starts with a wakeup (crystal/RC settling), which adds
+    /// jitter to connection events and can cost the first event after a long
+    /// idle. Disable it again before latency-sensitive traffic.
+    ///
+    /// The controller boots with sleep disabled (see
+    /// [`stats`](Self::stats)): this HAL does not yet restore the MAC clock
+    /// and PTC trigger after a controller wakeup, so an active connection may
+    /// drop with a supervision timeout while sleep is enabled. Only enable it
+    /// when no connection is open.
+    ///
+    /// Returns [`LcpuError::SleepControlNotAcked`] if the setting did not
+    /// take effect.
+    pub fn set_sleep_enabled(&self, enabled: bool) -> Result<(), LcpuError> {
+        crate::lcpu::controller::set_ble_sleep(enabled)
+    }
+
+    /// Add a device to the filter accept list (whitelist).
+    ///
+    /// Fails with an HCI `MemoryCapacityExceeded` status once the
+    /// controller's list is full; see [`set_whitelist`](Self::set_whitelist)
+    /// for a checked bulk update.
+    ///
+    /// Reads the event stream until the command completes, see
+    /// [Commands outside a host stack](self#commands-outside-a-host-stack).
+    pub async fn add_to_whitelist(&self, kind: AddrKind, addr: BdAddr) -> Result<(), ListError> {
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeAddDeviceToFilterAcceptList::new(kind, addr)).await
         })
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a device from the filter accept list (whitelist).
+    pub async fn remove_from_whitelist(
+        &self,
+        kind: AddrKind,
+        addr: BdAddr,
+    ) -> Result<(), ListError> {
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeRemoveDeviceFromFilterAcceptList::new(kind, addr))
+                .await
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Replace the filter accept list (whitelist) with `entries`.
+    ///
+    /// The entry count is checked against [`ActConfig::ble_max_act`] before
+    /// anything is sent, so an oversized list fails up front instead of
+    /// leaving the controller with a partial list. An empty slice clears it.
+    ///
+    /// The list must not be in use by advertising, scanning or a pending
+    /// connection while it is changed; the controller rejects the update
+    /// with `CommandDisallowed` otherwise.
+    pub async fn set_whitelist(&self, entries: &[(AddrKind, BdAddr)]) -> Result<(), ListError> {
+        check_list_len(entries.len(), self.act.ble_max_act)?;
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeClearFilterAcceptList::new()).await?;
+            for &(kind, addr) in entries {
+                ControllerCmdSync::exec(self, &LeAddDeviceToFilterAcceptList::new(kind, addr))
+                    .await?;
+            }
+            Ok::<_, cmd::Error<Error>>(())
+        })
+        .await?;
+        debug!("bt_hci: whitelist set, {} entries", entries.len());
+        Ok(())
+    }
+
+    /// Replace the resolving list with `entries` and enable address
+    /// resolution, so bonded peers using resolvable private addresses are
+    /// recognized.
+    ///
+    /// The entry count is checked against [`ActConfig::ble_max_ral`]. Address
+    /// resolution is disabled while the list is rewritten and only turned
+    /// back on if `entries` is not empty. As with
+    /// [`set_whitelist`](Self::set_whitelist), no advertising, scanning or
+    /// connection setup may be running.
+    pub async fn set_resolving_list(
+        &self,
+        entries: &[ResolvingListEntry],
+    ) -> Result<(), ListError> {
+        check_list_len(entries.len(), self.act.ble_max_ral)?;
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeSetAddrResolutionEnable::new(false)).await?;
+            ControllerCmdSync::exec(self, &LeClearResolvingList::new()).await?;
+            for e in entries {
+                let add = LeAddDeviceToResolvingList::new(
+                    e.peer_addr_kind,
+                    e.peer_addr,
+                    e.peer_irk,
+                    e.local_irk,
+                );
+                ControllerCmdSync::exec(self, &add).await?;
+            }
+            if !entries.is_empty() {
+                ControllerCmdSync::exec(self, &LeSetAddrResolutionEnable::new(true)).await?;
+            }
+            Ok::<_, cmd::Error<Error>>(())
+        })
+        .await?;
+        debug!("bt_hci: resolving list set, {} entries", entries.len());
+        Ok(())
+    }
+
+    /// Set the data length the controller proposes for new connections, via
+    /// HCI `LE_Write_Suggested_Default_Data_Length`.
+    ///
+    /// `tx_octets` is the maximum LL payload per packet
+    /// ([`DataLength::MIN_OCTETS`]..=[`DataLength::MAX_OCTETS`]) and `tx_time`
+    /// the maximum air time in microseconds
+    /// ([`DataLength::MIN_TIME_US`]..=[`DataLength::MAX_TIME_US`]). For
+    /// throughput, use 251 octets and 2120 us (1M PHY) or 17040 us (if coded
+    /// PHY links are expected).
+    ///
+    /// [`ActConfig`] only sets descriptor counts (`ble_rx_desc`); the size of
+    /// each LL buffer is fixed by the ROM. The request is therefore checked
+    /// against what the controller reports through
+    /// `LE_Read_Maximum_Data_Length` and rejected with
+    /// [`DataLengthError::ExceedsController`] instead of being silently
+    /// clamped.
+    ///
+    /// The peer takes part in the negotiation, so the values in effect on a
+    /// link are only known once the controller reports them; see
+    /// [`data_length`](Self::data_length). The ATT MTU is negotiated by the
+    /// host stack (L2CAP/ATT), not the controller; set the host's preferred
+    /// MTU there, and to avoid L2CAP fragmentation keep it at most
+    /// `tx_octets - 4`.
+    ///
+    /// Call this before a host stack takes over the controller, see
+    /// [Commands outside a host stack](self#commands-outside-a-host-stack).
+    pub async fn set_preferred_data_length(
+        &self,
+        tx_octets: u16,
+        tx_time: u16,
+    ) -> Result<(), DataLengthError> {
+        check_data_length(tx_octets, tx_time)?;
+        with_event_pump(self, async {
+            let max = ControllerCmdSync::exec(self, &LeReadMaximumDataLength::new()).await?;
+            if tx_octets > max.supported_max_tx_octets || tx_time > max.supported_max_tx_time {
+                return Err(DataLengthError::ExceedsController {
+                    max_octets: max.supported_max_tx_octets,
+                    max_time_us: max.supported_max_tx_time,
+                });
+            }
+            let cmd = LeWriteSuggestedDefaultDataLength::new(tx_octets, tx_time);
+            ControllerCmdSync::exec(self, &cmd).await?;
+            Ok(())
+        })
+        .await?;
+        debug!(
+            "bt_hci: preferred data length {} octets, {} us",
+            tx_octets, tx_time
+        );
+        Ok(())
+    }
+
+    /// Request a new data length on an open connection, via HCI
+    /// `LE_Set_Data_Length`.
+    ///
+    /// Takes the same ranges as
+    /// [`set_preferred_data_length`](Self::set_preferred_data_length). The
+    /// command only starts the LL procedure; the outcome arrives later as an
+    /// `LE_Data_Length_Change` event (see [`data_length`](Self::data_length)).
+    pub async fn set_data_length(
+        &self,
+        handle: ConnHandle,
+        tx_octets: u16,
+        tx_time: u16,
+    ) -> Result<(), DataLengthError> {
+        check_data_length(tx_octets, tx_time)?;
+        with_event_pump(self, async {
+            ControllerCmdSync::exec(self, &LeSetDataLength::new(handle, tx_octets, tx_time)).await
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// The most recent data length negotiated on any connection.
+    ///
+    /// Updated whenever an `LE_Data_Length_Change` event passes through
+    /// [`read`](Controller::read), whether the host stack or one of the
+    /// helpers here is driving the event stream. `None` until the first
+    /// change; a connection that keeps the default 27 octets / 328 us never
+    /// reports one. Check [`DataLength::handle`] when more than one link is
+    /// open.
+    pub fn data_length(&self) -> Option<DataLength> {
+        self.data_len.lock(|d| d.get())
+    }
+
+    /// Reboot the controller with the configuration it was created with,
+    /// e.g. to recover from a stuck controller.
+    ///
+    /// The HCI queue is closed and reopened empty, so no event from before
+    /// the reset is read afterwards. Any host stack state (connections,
+    /// advertising) is lost.
+    pub async fn reset(
+        &mut self,
+        dma_ch: impl Peripheral<P = impl Channel>,
+    ) -> Result<(), BleInitError> {
+        let config = self.config;
+        let cfg = ipc::QueueConfig::qid0_hci();
+        ipc::close_queue(cfg.qid);
+        self.lcpu.ble_power_off()?;
+
+        // The transport's handles index the same queue and stay valid.
+        let (mut rx, _tx) = ipc::open_queue(cfg)?.split();
+        self.lcpu.ble_power_on(&config, dma_ch, &mut rx).await?;
+        self.data_len.lock(|d| d.set(None));
+        self.apply_phy_config(&config.ble.controller).await?;
+        self.apply_addr_config(&config.ble).await?;
+        Ok(())
     }
 
     /// Shut down BLE and power off LCPU.
+    ///
+    /// The HCI queue is closed before the LCPU is halted, so pending IPC
+    /// reads fail instead of blocking forever. Afterwards the ROM config area
+    /// is cleared and the BLE clocks released; [`new`](Self::new) can boot
+    /// the controller again.
     pub fn shutdown(self) {
         let Self { lcpu, .. } = self;
-        let _ = lcpu.power_off();
+        ipc::close_queue(ipc::QueueConfig::qid0_hci().qid);
+        if let Err(e) = lcpu.ble_power_off() {
+            warn!("BLE shutdown: {:?}", e);
+        }
+    }
+}
+
+/// Activity limits the controller boots with for `config`.
+fn act_config(config: &LcpuConfig<'_>) -> ActConfig {
+    config.boot.rom.act_config.unwrap_or(ActConfig::DEFAULT)
+}
+
+fn own_addr_kind(config: &BleConfig) -> AddrKind {
+    match config.addr_type {
+        AddressType::Public => AddrKind::PUBLIC,
+        AddressType::RandomStatic => AddrKind::RANDOM,
+    }
+}
+
+fn check_list_len(len: usize, max: u8) -> Result<(), ListError> {
+    if len > max as usize {
+        return Err(ListError::TooManyEntries { len, max });
+    }
+    Ok(())
+}
+
+fn check_data_length(tx_octets: u16, tx_time: u16) -> Result<(), DataLengthError> {
+    if !(DataLength::MIN_OCTETS..=DataLength::MAX_OCTETS).contains(&tx_octets) {
+        return Err(DataLengthError::InvalidOctets(tx_octets));
+    }
+    if !(DataLength::MIN_TIME_US..=DataLength::MAX_TIME_US).contains(&tx_time) {
+        return Err(DataLengthError::InvalidTime(tx_time));
+    }
+    Ok(())
+}
+
+/// Data length in effect on a connection, from an `LE_Data_Length_Change`
+/// event. See [`BleController::data_length`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataLength {
+    /// Connection the values apply to.
+    pub handle: ConnHandle,
+    /// Maximum LL payload the local controller will send, in octets.
+    pub max_tx_octets: u16,
+    /// Maximum air time of a transmitted packet, in microseconds.
+    pub max_tx_time: u16,
+    /// Maximum LL payload the local controller expects to receive.
+    pub max_rx_octets: u16,
+    /// Maximum air time of a received packet, in microseconds.
+    pub max_rx_time: u16,
+}
+
+impl DataLength {
+    /// Smallest LL payload (the pre-4.2 default).
+    pub const MIN_OCTETS: u16 = 27;
+    /// Largest LL payload (Data Length Extension).
+    pub const MAX_OCTETS: u16 = 251;
+    /// Smallest packet time, in microseconds.
+    pub const MIN_TIME_US: u16 = 328;
+    /// Largest packet time, in microseconds (251 octets on coded PHY S=8).
+    pub const MAX_TIME_US: u16 = 17040;
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DataLength {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "conn {}: tx {}/{}us rx {}/{}us",
+            self.handle.raw(),
+            self.max_tx_octets,
+            self.max_tx_time,
+            self.max_rx_octets,
+            self.max_rx_time
+        )
+    }
+}
+
+/// Error returned by [`BleController::set_preferred_data_length`] and
+/// [`BleController::set_data_length`].
+#[derive(Debug)]
+pub enum DataLengthError {
+    /// Octet count outside
+    /// [`DataLength::MIN_OCTETS`]..=[`DataLength::MAX_OCTETS`].
+    InvalidOctets(u16),
+    /// Time outside
+    /// [`DataLength::MIN_TIME_US`]..=[`DataLength::MAX_TIME_US`].
+    InvalidTime(u16),
+    /// More than the controller's buffers support, as reported by
+    /// `LE_Read_Maximum_Data_Length`.
+    ExceedsController { max_octets: u16, max_time_us: u16 },
+    /// The controller rejected a command or the transport failed.
+    Hci(cmd::Error<Error>),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DataLengthError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            DataLengthError::InvalidOctets(n) => defmt::write!(f, "invalid tx octets {}", n),
+            DataLengthError::InvalidTime(t) => defmt::write!(f, "invalid tx time {}us", t),
+            DataLengthError::ExceedsController {
+                max_octets,
+                max_time_us,
+            } => defmt::write!(
+                f,
+                "controller supports at most {} octets, {}us",
+                max_octets,
+                max_time_us
+            ),
+            DataLengthError::Hci(e) => defmt::write!(f, "HCI error: {:?}", defmt::Debug2Format(e)),
+        }
+    }
+}
+
+impl From<cmd::Error<Error>> for DataLengthError {
+    fn from(e: cmd::Error<Error>) -> Self {
+        Self::Hci(e)
+    }
+}
+
+/// Resolving list entry for [`BleController::set_resolving_list`].
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvingListEntry {
+    /// Kind of the peer's identity address.
+    pub peer_addr_kind: AddrKind,
+    /// Peer identity address (public or static random).
+    pub peer_addr: BdAddr,
+    /// Peer identity resolving key, as exchanged during bonding.
+    pub peer_irk: [u8; 16],
+    /// Local identity resolving key; all zeros to use the identity address.
+    pub local_irk: [u8; 16],
+}
+
+/// Error returned by the whitelist and resolving list methods of
+/// [`BleController`].
+#[derive(Debug)]
+pub enum ListError {
+    /// More entries than the controller was configured for in
+    /// [`ActConfig`].
+    TooManyEntries { len: usize, max: u8 },
+    /// The controller rejected a command or the transport failed.
+    Hci(cmd::Error<Error>),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ListError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            ListError::TooManyEntries { len, max } => {
+                defmt::write!(f, "{} list entries, controller supports {}", len, max)
+            }
+            ListError::Hci(e) => defmt::write!(f, "HCI error: {:?}", defmt::Debug2Format(e)),
+        }
+    }
+}
+
+impl From<cmd::Error<Error>> for ListError {
+    fn from(e: cmd::Error<Error>) -> Self {
+        Self::Hci(e)
     }
 }
 
@@ -402,7 +958,26 @@ impl<const SLOTS: usize> Controller for BleController<SLOTS> {
         &self,
         buf: &'a mut [u8],
     ) -> Result<ControllerToHostPacket<'a>, Self::Error> {
-        self.inner.read(buf).await
+        let pkt = self.inner.read(buf).await?;
+        if let ControllerToHostPacket::Event(event) = &pkt {
+            if event.kind == EventKind::Le {
+                if let Ok(LeEvent::LeDataLengthChange(e)) = LeEvent::from_packet(event) {
+                    debug!(
+                        "bt_hci: data length tx {}/{}us rx {}/{}us",
+                        e.max_tx_octets, e.max_tx_time, e.max_rx_octets, e.max_rx_time
+                    );
+                    let len = DataLength {
+                        handle: e.handle,
+                        max_tx_octets: e.max_tx_octets,
+                        max_tx_time: e.max_tx_time,
+                        max_rx_octets: e.max_rx_octets,
+                        max_rx_time: e.max_rx_time,
+                    };
+                    self.data_len.lock(|d| d.set(Some(len)));
+                }
+            }
+        }
+        Ok(pkt)
     }
 }
 
@@ -424,3 +999,414 @@ where
         ControllerCmdAsync::exec(&self.inner, cmd).await
     }
 }
+
+// ============================================================================
+// Beacon
+// ============================================================================
+
+/// Maximum legacy advertising payload length.
+pub const MAX_ADV_DATA_LEN: usize = 31;
+
+/// Error returned by [`Beacon`].
+#[derive(Debug)]
+pub enum BeaconError {
+    /// `adv_data` is longer than [`MAX_ADV_DATA_LEN`].
+    DataTooLong,
+    /// Interval outside [`Beacon::MIN_INTERVAL_MS`]..=[`Beacon::MAX_INTERVAL_MS`].
+    InvalidInterval,
+    /// The controller rejected a command or the transport failed.
+    Hci(cmd::Error<Error>),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for BeaconError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            BeaconError::DataTooLong => defmt::write!(f, "advertising data too long"),
+            BeaconError::InvalidInterval => defmt::write!(f, "advertising interval out of range"),
+            BeaconError::Hci(e) => defmt::write!(f, "HCI error: {:?}", defmt::Debug2Format(e)),
+        }
+    }
+}
+
+impl From<cmd::Error<Error>> for BeaconError {
+    fn from(e: cmd::Error<Error>) -> Self {
+        Self::Hci(e)
+    }
+}
+
+/// Non-connectable advertising without a host stack.
+///
+/// Sets the advertising parameters and data and enables legacy
+/// `ADV_NONCONN_IND` advertising from the address configured with
+/// [`LcpuConfig::bd_addr`] or [`LcpuConfig::random_static_addr`]. The
+/// payload is sent as-is, so it must already be encoded AD structures
+/// (flags, manufacturer data, ...).
+///
+/// `Beacon` drives the HCI event stream itself while its commands run. Do not
+/// use it together with a host stack such as `trouble` on the same controller.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(controller: sifli_hal::bt_hci::BleController) {
+/// use sifli_hal::bt_hci::Beacon;
+///
+/// // Flags + manufacturer specific data
+/// let adv = [0x02, 0x01, 0x06, 0x05, 0xFF, 0xFF, 0xFF, 0x01, 0x02];
+/// let beacon = Beacon::start(&controller, &adv, 500).await.unwrap();
+/// // ...
+/// beacon.stop().await.unwrap();
+/// # }
+/// ```
+pub struct Beacon<'c, const SLOTS: usize = 4> {
+    controller: &'c BleController<SLOTS>,
+}
+
+impl<'c, const SLOTS: usize> Beacon<'c, SLOTS> {
+    /// Shortest advertising interval for non-connectable advertising.
+    pub const MIN_INTERVAL_MS: u32 = 20;
+    /// Longest legacy advertising interval (0x4000 * 0.625 ms).
+    pub const MAX_INTERVAL_MS: u32 = 10_240;
+
+    /// Start advertising `adv_data` every `interval_ms` milliseconds.
+    ///
+    /// The interval is rounded down to the 0.625 ms HCI unit.
+    pub async fn start(
+        controller: &'c BleController<SLOTS>,
+        adv_data: &[u8],
+        interval_ms: u32,
+    ) -> Result<Self, BeaconError> {
+        if adv_data.len() > MAX_ADV_DATA_LEN {
+            return Err(BeaconError::DataTooLong);
+        }
+        if !(Self::MIN_INTERVAL_MS..=Self::MAX_INTERVAL_MS).contains(&interval_ms) {
+            return Err(BeaconError::InvalidInterval);
+        }
+
+        let interval = Duration::from_u16((interval_ms * 1000 / 625) as u16);
+        let params = LeSetAdvParams::new(
+            interval,
+            interval,
+            AdvKind::AdvNonconnInd,
+            controller.own_addr_kind,
+            AddrKind::PUBLIC,
+            BdAddr::new([0; 6]),
+            AdvChannelMap::ALL,
+            AdvFilterPolicy::default(),
+        );
+        let mut data = [0u8; MAX_ADV_DATA_LEN];
+        data[..adv_data.len()].copy_from_slice(adv_data);
+
+        with_event_pump(controller, async {
+            ControllerCmdSync::exec(controller, &params).await?;
+            ControllerCmdSync::exec(controller, &LeSetAdvData::new(adv_data.len() as u8, data))
+                .await?;
+            ControllerCmdSync::exec(controller, &LeSetAdvEnable::new(true)).await
+        })
+        .await?;
+
+        debug!("beacon: advertising {} bytes every {} ms", adv_data.len(), interval_ms);
+        Ok(Self { controller })
+    }
+
+    /// Stop advertising.
+    pub async fn stop(self) -> Result<(), BeaconError> {
+        let controller = self.controller;
+        with_event_pump(controller, async {
+            ControllerCmdSync::exec(controller, &LeSetAdvEnable::new(false)).await
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Scanner
+// ============================================================================
+
+/// Scan parameters for [`Scanner::start`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ScanParams {
+    /// Send scan requests to get scan responses (default: passive).
+    pub active: bool,
+    /// Time between the start of two scan windows, in ms (default: 100).
+    pub interval_ms: u32,
+    /// Listening time per interval, in ms; at most `interval_ms` (default: 50).
+    pub window_ms: u32,
+    /// Let the controller report each advertiser only once per scan
+    /// (default: false).
+    pub filter_duplicates: bool,
+}
+
+impl Default for ScanParams {
+    fn default() -> Self {
+        Self {
+            active: false,
+            interval_ms: 100,
+            window_ms: 50,
+            filter_duplicates: false,
+        }
+    }
+}
+
+/// Error returned by [`Scanner`].
+#[derive(Debug)]
+pub enum ScanError {
+    /// Interval or window outside
+    /// [`Scanner::MIN_INTERVAL_MS`]..=[`Scanner::MAX_INTERVAL_MS`], or the
+    /// window is longer than the interval.
+    InvalidParams,
+    /// The controller rejected a command or the transport failed.
+    Hci(cmd::Error<Error>),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ScanError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            ScanError::InvalidParams => defmt::write!(f, "scan interval/window out of range"),
+            ScanError::Hci(e) => defmt::write!(f, "HCI error: {:?}", defmt::Debug2Format(e)),
+        }
+    }
+}
+
+impl From<cmd::Error<Error>> for ScanError {
+    fn from(e: cmd::Error<Error>) -> Self {
+        Self::Hci(e)
+    }
+}
+
+impl From<Error> for ScanError {
+    fn from(e: Error) -> Self {
+        Self::Hci(cmd::Error::Io(e))
+    }
+}
+
+/// One received advertising report.
+#[derive(Debug, Clone, Copy)]
+pub struct AdvReport {
+    /// PDU type (connectable, scannable, scan response, ...).
+    pub event_kind: LeAdvEventKind,
+    /// Public or random advertiser address.
+    pub addr_kind: AddrKind,
+    /// Advertiser address.
+    pub addr: BdAddr,
+    /// Received signal strength in dBm (127 = not available).
+    pub rssi: i8,
+    data: [u8; MAX_ADV_DATA_LEN],
+    len: u8,
+}
+
+impl AdvReport {
+    /// Advertising (or scan response) data, as encoded AD structures.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+}
+
+/// Passive or active LE scanning without a host stack.
+///
+/// Advertising reports are returned one at a time by [`next`](Self::next).
+/// A single HCI event can carry several reports; they are parked in a queue
+/// of `N` entries and handed out before the next event is read. Reports that
+/// do not fit are dropped and counted in [`dropped`](Self::dropped), so a
+/// slow consumer sees that it lost data instead of losing it silently.
+///
+/// Between `next` calls nothing reads the HCI transport, so events wait in
+/// the IPC queue and then in the controller, which discards reports once its
+/// own buffers are full. A legacy advertising PDU takes up to 376 µs on air,
+/// which caps the report rate at roughly 2,600 per second at a 100 % duty
+/// cycle; busy environments typically produce a few hundred per second. Use
+/// [`ScanParams::filter_duplicates`] or a lower duty cycle when only new
+/// devices matter.
+///
+/// `Scanner` reads the HCI event stream for as long as a scan runs, so it
+/// cannot share the controller with a host stack (see the
+/// [module docs](self#commands-outside-a-host-stack)).
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(controller: sifli_hal::bt_hci::BleController) {
+/// use sifli_hal::bt_hci::{ScanParams, Scanner};
+///
+/// let mut scanner: Scanner<'_, 4, 8> =
+///     Scanner::start(&controller, ScanParams::default()).await.unwrap();
+/// loop {
+///     let report = scanner.next().await.unwrap();
+///     // report.addr, report.rssi, report.data()
+/// }
+/// # }
+/// ```
+pub struct Scanner<'c, const SLOTS: usize = 4, const N: usize = 8> {
+    controller: &'c BleController<SLOTS>,
+    queue: [Option<AdvReport>; N],
+    head: usize,
+    len: usize,
+    dropped: u32,
+}
+
+impl<'c, const SLOTS: usize, const N: usize> Scanner<'c, SLOTS, N> {
+    /// Shortest scan interval and window (0x0004 * 0.625 ms).
+    pub const MIN_INTERVAL_MS: u32 = 3;
+    /// Longest scan interval and window (0x4000 * 0.625 ms).
+    pub const MAX_INTERVAL_MS: u32 = 10_240;
+
+    /// Configure and enable scanning.
+    ///
+    /// Interval and window are rounded down to the 0.625 ms HCI unit. Scans
+    /// from the address configured in [`LcpuConfig`], see
+    /// [`BleController::own_addr_kind`].
+    pub async fn start(
+        controller: &'c BleController<SLOTS>,
+        params: ScanParams,
+    ) -> Result<Self, ScanError> {
+        let range = Self::MIN_INTERVAL_MS..=Self::MAX_INTERVAL_MS;
+        if !range.contains(&params.interval_ms)
+            || !range.contains(&params.window_ms)
+            || params.window_ms > params.interval_ms
+        {
+            return Err(ScanError::InvalidParams);
+        }
+
+        let scan_params = LeSetScanParams::new(
+            if params.active {
+                LeScanKind::Active
+            } else {
+                LeScanKind::Passive
+            },
+            Duration::from_u16((params.interval_ms * 1000 / 625) as u16),
+            Duration::from_u16((params.window_ms * 1000 / 625) as u16),
+            controller.own_addr_kind,
+            ScanningFilterPolicy::BasicUnfiltered,
+        );
+
+        with_event_pump(controller, async {
+            ControllerCmdSync::exec(controller, &scan_params).await?;
+            ControllerCmdSync::exec(
+                controller,
+                &LeSetScanEnable::new(true, params.filter_duplicates),
+            )
+            .await
+        })
+        .await?;
+
+        debug!(
+            "scanner: {} scan, {}/{} ms",
+            if params.active { "active" } else { "passive" },
+            params.window_ms,
+            params.interval_ms
+        );
+        Ok(Self {
+            controller,
+            queue: [None; N],
+            head: 0,
+            len: 0,
+            dropped: 0,
+        })
+    }
+
+    /// Wait for the next advertising report.
+    pub async fn next(&mut self) -> Result<AdvReport, ScanError> {
+        let mut buf = [0u8; 260];
+        loop {
+            if let Some(report) = self.pop() {
+                return Ok(report);
+            }
+
+            let ControllerToHostPacket::Event(event) = self.controller.read(&mut buf).await? else {
+                continue;
+            };
+            if event.kind != EventKind::Le {
+                continue;
+            }
+            let Ok(LeEvent::LeAdvertisingReport(adv)) = LeEvent::from_packet(&event) else {
+                continue;
+            };
+            for report in adv.reports.iter() {
+                let Ok(report) = report else {
+                    warn!("scanner: malformed advertising report");
+                    break;
+                };
+                let len = report.data.len().min(MAX_ADV_DATA_LEN);
+                let mut data = [0u8; MAX_ADV_DATA_LEN];
+                data[..len].copy_from_slice(&report.data[..len]);
+                self.push(AdvReport {
+                    event_kind: report.event_kind,
+                    addr_kind: report.addr_kind,
+                    addr: report.addr,
+                    rssi: report.rssi,
+                    data,
+                    len: len as u8,
+                });
+            }
+        }
+    }
+
+    /// Reports dropped because the queue was full, since start.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Disable scanning.
+    pub async fn stop(self) -> Result<(), ScanError> {
+        let controller = self.controller;
+        with_event_pump(controller, async {
+            ControllerCmdSync::exec(controller, &LeSetScanEnable::new(false, false)).await
+        })
+        .await?;
+        if self.dropped != 0 {
+            debug!("scanner: {} reports dropped", self.dropped);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, report: AdvReport) {
+        if self.len == N {
+            self.dropped = self.dropped.wrapping_add(1);
+            return;
+        }
+        self.queue[(self.head + self.len) % N] = Some(report);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<AdvReport> {
+        if self.len == 0 {
+            return None;
+        }
+        let report = self.queue[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        report
+    }
+}
+
+/// Run `f` while reading HCI events, so command completions reach
+/// [`ExternalController`]'s slots when no host stack is reading.
+///
+/// A failed read ends the wait with that error instead of retrying: a closed
+/// or broken queue fails again immediately and would starve `f`.
+async fn with_event_pump<const SLOTS: usize, F, T, E>(
+    controller: &BleController<SLOTS>,
+    f: F,
+) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: From<cmd::Error<Error>>,
+{
+    let pump = async {
+        let mut buf = [0u8; 260];
+        loop {
+            if let Err(e) = controller.read(&mut buf).await {
+                warn!("bt_hci: HCI read error: {:?}", e);
+                return e;
+            }
+        }
+    };
+    match select(f, pump).await {
+        Either::First(r) => r,
+        Either::Second(e) => Err(cmd::Error::Io(e).into()),
+    }
+}
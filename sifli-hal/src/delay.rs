@@ -0,0 +1,235 @@
+//! Blocking cycle-counting delay.
+//!
+//! [`Delay`] implements the `embedded-hal` 1.0 [`DelayNs`] and 0.2
+//! `DelayUs`/`DelayMs` traits without needing `embassy-time`, so blocking
+//! panel or sensor init sequences can run before (or without) an executor.
+//!
+//! Delays are busy loops calibrated from the current HCLK. The calibration is
+//! refreshed whenever [`rcc::clock_generation`] changes, e.g. after
+//! [`rcc::reconfigure_sysclk`](crate::rcc::reconfigure_sysclk).
+//!
+//! `cortex_m::asm::delay` only promises "at least" the requested cycles on
+//! the cores it was tuned for; on the Cortex-M33 the loop can retire faster.
+//! Each clock change therefore also times a short `asm::delay` against the
+//! DWT cycle counter and scales every loop count
+//! ([`cortex_m_blocking_delay_us`](crate::cortex_m_blocking_delay_us)
+//! included) by the measured speed, so a 200 µs wait lasts at least 200 µs
+//! at any HCLK.
+//!
+//! ```rust,ignore
+//! use embedded_hal_1::delay::DelayNs;
+//!
+//! let mut delay = sifli_hal::Delay::new();
+//! reset.set_low();
+//! delay.delay_ms(10);
+//! reset.set_high();
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use embedded_hal_1::delay::DelayNs;
+
+use crate::rcc;
+
+/// Real cycles taken per cycle requested from `asm::delay`, in Q8 (256 = exact).
+static LOOP_SCALE_Q8: AtomicU32 = AtomicU32::new(256);
+
+/// Cycles timed by [`calibrate_loop`].
+const CALIBRATION_CYCLES: u32 = 4096;
+
+/// Time `asm::delay` against the DWT cycle counter and store its speed.
+///
+/// Called from `rcc::set_freqs`, i.e. at init and after every clock change.
+/// Keeps the previous scale if the cycle counter is not available.
+pub(crate) fn calibrate_loop() {
+    #[cfg(target_arch = "arm")]
+    {
+        use cortex_m::peripheral::DWT;
+
+        /// `DEMCR.TRCENA`
+        const TRCENA: u32 = 1 << 24;
+
+        if !DWT::has_cycle_counter() {
+            return;
+        }
+        // An interrupt inside the timed loop would inflate the count and make
+        // every later delay too short.
+        let elapsed = critical_section::with(|_| {
+            // SAFETY: only the trace enable and the cycle counter are touched,
+            // and both are left in the state they were found.
+            let mut cp = unsafe { cortex_m::Peripherals::steal() };
+            let trace_was_enabled = cp.DCB.demcr.read() & TRCENA != 0;
+            cp.DCB.enable_trace();
+            let was_enabled = DWT::cycle_counter_enabled();
+            cp.DWT.enable_cycle_counter();
+
+            let start = DWT::cycle_count();
+            cortex_m::asm::delay(CALIBRATION_CYCLES);
+            let elapsed = DWT::cycle_count().wrapping_sub(start);
+
+            if !was_enabled {
+                cp.DWT.disable_cycle_counter();
+            }
+            if !trace_was_enabled {
+                cp.DCB.disable_trace();
+            }
+            elapsed
+        });
+        if elapsed != 0 {
+            let scale = (elapsed as u64 * 256 / CALIBRATION_CYCLES as u64).max(1) as u32;
+            LOOP_SCALE_Q8.store(scale, Ordering::Relaxed);
+        }
+    }
+}
+
+/// `asm::delay` argument that waits at least `cycles` real cycles at the
+/// measured loop speed `scale_q8`.
+pub(crate) fn loop_count(cycles: u64, scale_q8: u32) -> u64 {
+    (cycles * 256).div_ceil(scale_q8.max(1) as u64)
+}
+
+/// `asm::delay` argument for `us` microseconds at `hclk_hz`.
+pub(crate) fn loop_count_us(hclk_hz: u32, us: u32, scale_q8: u32) -> u64 {
+    loop_count((hclk_hz as u64 * us as u64).div_ceil(1_000_000), scale_q8)
+}
+
+/// `asm::delay` argument for `us` microseconds at `hclk_hz` and the loop
+/// speed measured by [`calibrate_loop`]. Used by both [`Delay::delay_us`]
+/// and [`cortex_m_blocking_delay_us`](crate::cortex_m_blocking_delay_us).
+pub(crate) fn loops_for_us(hclk_hz: u32, us: u32) -> u64 {
+    loop_count_us(hclk_hz, us, LOOP_SCALE_Q8.load(Ordering::Relaxed))
+}
+
+/// Run `asm::delay` for `loops` iterations.
+pub(crate) fn delay_loops(mut loops: u64) {
+    // `asm::delay` takes a u32; split long waits.
+    while loops > 0 {
+        let chunk = loops.min(u32::MAX as u64);
+        cortex_m::asm::delay(chunk as u32);
+        loops -= chunk;
+    }
+}
+
+/// Run `asm::delay` for at least `cycles` real cycles.
+pub(crate) fn delay_cycles(cycles: u64) {
+    delay_loops(loop_count(cycles, LOOP_SCALE_Q8.load(Ordering::Relaxed)));
+}
+
+/// Blocking delay provider backed by `cortex_m::asm::delay`.
+#[derive(Debug, Clone, Copy)]
+pub struct Delay {
+    /// HCLK in Hz at the last calibration.
+    hclk_hz: u32,
+    /// [`rcc::clock_generation`] at the last calibration.
+    generation: u32,
+}
+
+impl Delay {
+    /// Create a delay calibrated to the current HCLK.
+    ///
+    /// # Panics
+    ///
+    /// Panics if HCLK cannot be read (before [`init`](crate::init)).
+    pub fn new() -> Self {
+        let mut delay = Self {
+            hclk_hz: 0,
+            generation: 0,
+        };
+        delay.calibrate();
+        delay
+    }
+
+    fn calibrate(&mut self) {
+        self.generation = rcc::clock_generation();
+        self.hclk_hz = unwrap!(rcc::get_hclk_freq(), "delay: HCLK unavailable").0;
+    }
+
+    fn refresh(&mut self) {
+        if self.generation != rcc::clock_generation() {
+            self.calibrate();
+        }
+    }
+}
+
+impl Default for Delay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.refresh();
+        let cycles = (self.hclk_hz as u64 * ns as u64).div_ceil(1_000_000_000);
+        delay_cycles(cycles);
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.refresh();
+        delay_loops(loops_for_us(self.hclk_hz, us));
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.refresh();
+        let cycles = self.hclk_hz as u64 * ms as u64 / 1_000;
+        delay_cycles(cycles);
+    }
+}
+
+macro_rules! impl_eh02_delay {
+    ($($t:ty),*) => {
+        $(
+            impl embedded_hal_02::blocking::delay::DelayUs<$t> for Delay {
+                fn delay_us(&mut self, us: $t) {
+                    DelayNs::delay_us(self, us as u32);
+                }
+            }
+
+            impl embedded_hal_02::blocking::delay::DelayMs<$t> for Delay {
+                fn delay_ms(&mut self, ms: $t) {
+                    DelayNs::delay_ms(self, ms as u32);
+                }
+            }
+        )*
+    };
+}
+
+impl_eh02_delay!(u8, u16, u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loop_count_scales_with_frequency() {
+        let at_48m = loop_count_us(48_000_000, 200, 256);
+        let at_240m = loop_count_us(240_000_000, 200, 256);
+        assert_eq!(at_48m, 9_600);
+        assert_eq!(at_240m, 5 * at_48m);
+    }
+
+    #[test]
+    fn loop_count_compensates_fast_loop() {
+        // A loop retiring twice as fast needs twice the iterations.
+        assert_eq!(loop_count_us(240_000_000, 200, 128), 96_000);
+        // Never rounds a partial microsecond's worth of cycles down to zero.
+        assert_eq!(loop_count_us(1_000_000, 1, 256), 1);
+        assert_eq!(loop_count(1, 512), 1);
+    }
+
+    #[test]
+    fn delay_paths_use_measured_scale() {
+        // The only test touching LOOP_SCALE_Q8, so parallel tests don't race.
+        let delay = Delay {
+            hclk_hz: 48_000_000,
+            generation: 0,
+        };
+        LOOP_SCALE_Q8.store(256, Ordering::Relaxed);
+        assert_eq!(loops_for_us(delay.hclk_hz, 200), 9_600);
+        // A calibration that measured a loop twice as fast doubles the count
+        // used by `Delay::delay_us` and `cortex_m_blocking_delay_us`.
+        LOOP_SCALE_Q8.store(128, Ordering::Relaxed);
+        assert_eq!(loops_for_us(delay.hclk_hz, 200), 19_200);
+        LOOP_SCALE_Q8.store(256, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,98 @@
+//! Debug helpers for inspecting peripheral state.
+//!
+//! [`dump_peripheral_state`] logs the registers that explain most hangs in
+//! this HAL (LCDC busy waits, stuck DMA transfers, ADC conversions that never
+//! complete) in one call. It only reads registers and takes no locks, so it
+//! can be called from a panic handler:
+//!
+//! ```rust,ignore
+//! #[defmt::panic_handler]
+//! fn panic() -> ! {
+//!     sifli_hal::diag::dump_peripheral_state();
+//!     cortex_m::asm::udf()
+//! }
+//! ```
+//!
+//! Values of peripherals whose clock is gated (see `HPSYS_RCC.ENR1/ENR2` in
+//! the dump) read as zero and should be ignored.
+
+use crate::_generated::DMAC1_CHANNEL_COUNT;
+use crate::pac::{DMAC1, GPADC, HPSYS_RCC, LCDC1};
+use crate::rcc;
+
+/// Log RCC, DMAC1, LCDC1 and GPADC status registers via defmt.
+pub fn dump_peripheral_state() {
+    dump_rcc();
+    dump_dma();
+    dump_lcdc();
+    dump_adc();
+}
+
+fn dump_rcc() {
+    info!(
+        "diag rcc: CSR={:08x} CFGR={:08x} ENR1={:08x} ENR2={:08x}",
+        HPSYS_RCC.csr().read().0,
+        HPSYS_RCC.cfgr().read().0,
+        HPSYS_RCC.enr1().read().0,
+        HPSYS_RCC.enr2().read().0,
+    );
+    info!(
+        "diag rcc: DLL1CR={:08x} DLL2CR={:08x} USBCR={:08x}",
+        HPSYS_RCC.dllcr(0).read().0,
+        HPSYS_RCC.dllcr(1).read().0,
+        HPSYS_RCC.usbcr().read().0,
+    );
+    // Hardware readers: the cached values may be what is wrong.
+    info!(
+        "diag rcc: sysclk={:?} hclk={:?} pclk={:?} pclk2={:?} (generation {})",
+        rcc::read_clk_sys_freq(),
+        rcc::read_hclk_freq(),
+        rcc::read_pclk_freq(),
+        rcc::read_pclk2_freq(),
+        rcc::clock_generation(),
+    );
+}
+
+fn dump_dma() {
+    // DMAC2 lives in LPSYS and may be asleep; reading it here could fault.
+    info!("diag dmac1: ISR={:08x}", DMAC1.isr().read().0);
+    for ch in 0..DMAC1_CHANNEL_COUNT {
+        let ccr = DMAC1.ccr(ch).read();
+        if !ccr.en() && DMAC1.cndtr(ch).read().0 == 0 {
+            continue;
+        }
+        info!(
+            "diag dmac1 ch{}: CCR={:08x} CNDTR={:08x} CPAR={:08x} CM0AR={:08x}",
+            ch,
+            ccr.0,
+            DMAC1.cndtr(ch).read().0,
+            DMAC1.cpar(ch).read().0,
+            DMAC1.cm0ar(ch).read().0,
+        );
+    }
+}
+
+fn dump_lcdc() {
+    info!(
+        "diag lcdc1: STATUS={:08x} IRQ={:08x} SETTING={:08x} LCD_SINGLE={:08x}",
+        LCDC1.status().read().0,
+        LCDC1.irq().read().0,
+        LCDC1.setting().read().0,
+        LCDC1.lcd_single().read().0,
+    );
+    info!(
+        "diag lcdc1: LCD_CONF={:08x} LCD_IF_CONF={:08x} SPI_IF_CONF={:08x}",
+        LCDC1.lcd_conf().read().0,
+        LCDC1.lcd_if_conf().read().0,
+        LCDC1.spi_if_conf().read().0,
+    );
+}
+
+fn dump_adc() {
+    info!(
+        "diag gpadc: IRQ={:08x} CTRL={:08x} CTRL2={:08x}",
+        GPADC.gpadc_irq().read().0,
+        GPADC.ctrl_reg().read().0,
+        GPADC.ctrl_reg2().read().0,
+    );
+}
@@ -12,6 +12,7 @@ use embassy_sync::waitqueue::AtomicWaker;
 
 use super::ringbuffer::{DmaCtrl, Error, ReadableDmaRingBuffer, WritableDmaRingBuffer};
 use super::word::{Word, WordSize};
+use super::Error as TransferError;
 use super::{AnyChannel, Channel, Request, STATE};
 use crate::{interrupt, pac, peripherals};
 
@@ -98,12 +99,15 @@ impl From<WordSize> for pac::dmac::vals::Size {
 pub(crate) struct ChannelState {
     waker: AtomicWaker,
     complete_count: AtomicUsize,
+    /// CNDTR programmed by the last `configure`, to derive progress.
+    ndtr: AtomicUsize,
 }
 
 impl ChannelState {
     pub(crate) const NEW: Self = Self {
         waker: AtomicWaker::new(),
         complete_count: AtomicUsize::new(0),
+        ndtr: AtomicUsize::new(0),
     };
 }
 
@@ -156,6 +160,22 @@ impl AnyChannel {
         state.waker.wake();
     }
 
+    /// Service a DMAC2 channel from the task waiting on it.
+    ///
+    /// DMAC2 has no HCPU interrupt, so nothing else clears its flags or
+    /// disables a finished channel. Run the interrupt handler by hand and ask
+    /// to be polled again while the transfer is still running. Must be called
+    /// after the waker is registered. No-op for DMAC1 channels.
+    fn poll_irq(&self, waker: &Waker) {
+        if !self.is_dmac2() {
+            return;
+        }
+        critical_section::with(|_| unsafe { self.on_irq() });
+        if self.is_running() {
+            waker.wake_by_ref();
+        }
+    }
+
     unsafe fn configure(
         &self,
         request: Request,
@@ -169,6 +189,14 @@ impl AnyChannel {
         peri_size: WordSize,
         options: TransferOptions,
     ) {
+        // `Request` enumerates the DMAC1 (HPSYS) request lines. DMAC2's CSELR
+        // selects LPSYS peripherals with overlapping numbers, so a peripheral
+        // transfer on a DMAC2 channel would be paced by the wrong device.
+        assert!(
+            mem2mem || !self.is_dmac2(),
+            "dma: peripheral requests are only routed to DMAC1 channels"
+        );
+
         // "Preceding reads and writes cannot be moved past subsequent writes."
         fence(Ordering::SeqCst);
 
@@ -201,6 +229,7 @@ impl AnyChannel {
         };
 
         assert!(ndtr > 0 && ndtr <= 0xFFFF);
+        state.ndtr.store(ndtr, Ordering::Relaxed);
 
         // In M2M mode CPAR is also a memory address, apply remap for flash addresses
         let peri_addr = if mem2mem {
@@ -302,6 +331,31 @@ impl AnyChannel {
         r.cndtr(info.num).read().ndt()
     }
 
+    /// Memory-side address the channel will access next, for debugging a
+    /// stalled transfer (e.g. "stuck at offset X of Y").
+    ///
+    /// The DMAC has no live address register, so this is derived from the
+    /// programmed start address (CM0AR) plus the progress implied by CNDTR.
+    /// It is a snapshot and may advance between reads. Addresses are system
+    /// bus addresses, as programmed into the controller. Without memory
+    /// increment the start address is returned.
+    pub fn current_address(&self) -> u32 {
+        let info = self.info();
+        let r = info.dma;
+        let ccr = r.ccr(info.num).read();
+        let start = r.cm0ar(info.num).read().0;
+        if !ccr.minc() {
+            return start;
+        }
+
+        let total = STATE[self.state_index()].ndtr.load(Ordering::Relaxed) as u32;
+        let done = total.saturating_sub(r.cndtr(info.num).read().ndt() as u32);
+        // CNDTR counts transfers; the memory address advances by MSIZE per
+        // transfer, whatever the peripheral-side width.
+        let unit = 1u32 << ccr.msize().to_bits();
+        start + done * unit
+    }
+
     fn disable_circular_mode(&self) {
         let info = self.info();
         let r = info.dma;
@@ -323,6 +377,31 @@ impl AnyChannel {
 }
 
 /// DMA transfer.
+///
+/// This is the shared async primitive drivers should use to await DMA
+/// completion: `.await` it directly, or call [`poll_complete`](Self::poll_complete)
+/// from inside a driver's own `poll_fn` to combine it with peripheral status.
+/// Completion wakes through the per-channel waker in `STATE`. In-tree users
+/// are [`Adc::read_continuous`](crate::adc::Adc::read_continuous),
+/// [`Lcdc::send_pixel_data_dma`](crate::lcdc::Lcdc::send_pixel_data_dma) and
+/// [`fill`](crate::dma::fill()); the LCDC layer fetch and the audio rings have
+/// their own bus masters or ring-buffer wakers.
+///
+/// Dropping a `Transfer` before it completes aborts the channel and waits
+/// for it to stop, so cancelling the future is safe.
+///
+/// # Flow control
+///
+/// Peripheral transfers ([`new_read`](Self::new_read), [`new_write`](Self::new_write)
+/// and friends) bind `request` to the channel through `CSELR`, so the
+/// peripheral paces the channel: one peripheral-sized element moves per
+/// request it raises (e.g. each GPADC conversion or each I2S FIFO slot).
+/// Drivers take the request from their DMA trait via `dma.request()`.
+/// Memory-to-memory transfers ([`new_transfer`](Self::new_transfer)) set
+/// `MEM2MEM` instead and run as fast as the bus allows.
+///
+/// `Request` only lists DMAC1 request lines; starting a peripheral transfer
+/// on a DMAC2 channel panics.
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct Transfer<'a> {
     channel: PeripheralRef<'a, AnyChannel>,
@@ -634,6 +713,17 @@ impl<'a> Transfer<'a> {
         options: TransferOptions,
     ) -> Self {
         assert!(mem_len > 0 && mem_len <= 0xFFFF);
+        // A register pointer cast to the wrong word type (e.g. a 16-bit FIFO
+        // read through `*mut u32` at a half-word offset) silently shifts
+        // every sample; the DMAC needs both addresses aligned to their size.
+        assert!(
+            peri_addr as usize % peri_size.bytes() == 0,
+            "dma: peripheral address not aligned to its word size"
+        );
+        assert!(
+            mem_addr as usize % mem_size.bytes() == 0,
+            "dma: buffer not aligned to its word size"
+        );
 
         channel.configure(
             request, dir, peri_addr, mem_addr, mem_len, incr, false, mem_size, peri_size, options,
@@ -707,6 +797,13 @@ impl<'a> Transfer<'a> {
         self.channel.get_remaining_transfers()
     }
 
+    /// Memory-side address the transfer will access next.
+    ///
+    /// See [`AnyChannel::current_address`].
+    pub fn current_address(&self) -> u32 {
+        self.channel.current_address()
+    }
+
     /// Blocking wait until the transfer finishes.
     ///
     /// Polls the TCIF (Transfer Complete Interrupt Flag) in ISR, which is set
@@ -739,12 +836,94 @@ impl<'a> Transfer<'a> {
 
         core::mem::forget(self);
     }
+
+    /// Blocking wait with a timeout, for code running without an executor.
+    ///
+    /// Polls TCIF like [`blocking_wait`](Self::blocking_wait), every 10 µs
+    /// of busy-wait delay, and also accepts a channel already disabled by the
+    /// interrupt handler. If the transfer is not complete after `timeout_us`,
+    /// it is aborted and [`Error::Timeout`](super::Error::Timeout) returned.
+    /// Either way the channel is left stopped with its flags cleared.
+    pub fn blocking_wait_timeout_us(self, timeout_us: u32) -> Result<(), TransferError> {
+        let info = self.channel.info();
+        let mut waited_us = 0;
+        while !info.dma.isr().read().tcif(info.num) && self.channel.is_running() {
+            if waited_us >= timeout_us {
+                // Dropping `self` aborts the transfer.
+                return Err(TransferError::Timeout);
+            }
+            crate::cortex_m_blocking_delay_us(10);
+            waited_us += 10;
+        }
+        // Dropping a finished transfer disables the channel and clears TCIF.
+        drop(self);
+        Ok(())
+    }
+
+    /// Wait for completion for at most `timeout`.
+    ///
+    /// On timeout the transfer is aborted (as when it is dropped) and
+    /// [`Error::Timeout`](super::Error::Timeout) returned, so the channel can
+    /// be reused right away. Use this instead of wrapping the transfer in a
+    /// driver-specific timeout loop:
+    ///
+    /// ```rust,ignore
+    /// let transfer = unsafe { Transfer::new_read(ch, req, peri, &mut buf, opts) };
+    /// transfer.with_timeout(Duration::from_millis(10)).await?;
+    /// ```
+    #[cfg(feature = "time")]
+    pub async fn with_timeout(
+        mut self,
+        timeout: embassy_time::Duration,
+    ) -> Result<(), TransferError> {
+        embassy_time::with_timeout(timeout, &mut self)
+            .await
+            .map_err(|_| TransferError::Timeout)
+    }
+
+    /// Poll for transfer completion, registering `cx`'s waker on this channel.
+    ///
+    /// Requires `complete_transfer_ir` in [`TransferOptions`] (the default), since
+    /// the channel interrupt is what wakes the task and disables the channel.
+    ///
+    /// DMAC2 channels have no HCPU interrupt: their flags are serviced from
+    /// here instead, and the task is re-polled until the transfer finishes.
+    /// This keeps the executor busy for the length of the transfer, needs
+    /// LPSYS awake, and assumes the LCPU is not enabling those IRQs itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// poll_fn(|cx| {
+    ///     if peripheral_error() {
+    ///         return Poll::Ready(Err(Error::Bus));
+    ///     }
+    ///     transfer.poll_complete(cx).map(Ok)
+    /// })
+    /// .await
+    /// ```
+    pub fn poll_complete(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let state: &ChannelState = &STATE[self.channel.state_index()];
+
+        state.waker.register(cx.waker());
+        self.channel.poll_irq(cx.waker());
+
+        if self.is_running() {
+            Poll::Pending
+        } else {
+            // "Subsequent reads and writes cannot be moved ahead of preceding reads."
+            fence(Ordering::SeqCst);
+            Poll::Ready(())
+        }
+    }
 }
 
 impl<'a> Drop for Transfer<'a> {
     fn drop(&mut self) {
+        // Abort: no-op if the transfer already completed.
         self.request_stop();
         while self.is_running() {}
+        self.channel.clear_irqs();
 
         // "Subsequent reads and writes cannot be moved ahead of preceding reads."
         fence(Ordering::SeqCst);
@@ -755,15 +934,106 @@ impl<'a> Unpin for Transfer<'a> {}
 impl<'a> Future for Transfer<'a> {
     type Output = ();
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let state: &ChannelState = &STATE[self.channel.state_index()];
+        self.poll_complete(cx)
+    }
+}
 
-        state.waker.register(cx.waker());
+/// DMA transfer that owns a `'static` buffer and hands it back on completion.
+///
+/// [`Transfer::new_read`] and [`Transfer::new_write`] borrow the buffer for
+/// the transfer's lifetime `'a`, and dropping the `Transfer` aborts the
+/// channel, so the borrow checker already keeps the buffer alive while the
+/// DMAC uses it. That guarantee relies on the destructor running: leaking a
+/// `Transfer` with `mem::forget` leaves the DMAC writing to a buffer the
+/// caller may reuse. Taking the buffer by value closes that gap, since a
+/// `'static` buffer moved in here can't be touched by anything else until
+/// [`wait`](Self::wait) returns it. Use this for buffers from `StaticCell`
+/// or `static mut` that are handed to DMA repeatedly.
+///
+/// ```rust,ignore
+/// static BUF: StaticCell<[u8; 256]> = StaticCell::new();
+/// let buf = BUF.init([0; 256]);
+///
+/// let transfer = unsafe {
+///     OwnedTransfer::new_read(
+///         p.DMAC1_CH1,
+///         Request::USART1_RX,
+///         pac::USART1.dr().as_ptr() as *mut u8,
+///         buf,
+///         TransferOptions::default(),
+///     )
+/// };
+/// let buf = transfer.wait().await;
+/// ```
+#[must_use = "dropping an `OwnedTransfer` aborts it and drops the buffer"]
+pub struct OwnedTransfer<'a, B> {
+    transfer: Transfer<'a>,
+    buf: B,
+}
 
-        if self.is_running() {
-            Poll::Pending
-        } else {
-            Poll::Ready(())
-        }
+impl<'a, W: Word> OwnedTransfer<'a, &'static mut [W]> {
+    /// Start a peripheral-to-memory transfer into `buf`.
+    ///
+    /// # Safety
+    ///
+    /// `peri_addr` must be a register `request` paces, as for
+    /// [`Transfer::new_read`].
+    pub unsafe fn new_read(
+        channel: impl Peripheral<P = impl Channel> + 'a,
+        request: Request,
+        peri_addr: *mut W,
+        buf: &'static mut [W],
+        options: TransferOptions,
+    ) -> Self {
+        let ptr: *mut [W] = &mut *buf;
+        let transfer = Transfer::new_read_raw(channel, request, peri_addr, ptr, options);
+        Self { transfer, buf }
+    }
+}
+
+impl<'a, W: Word> OwnedTransfer<'a, &'static [W]> {
+    /// Start a memory-to-peripheral transfer from `buf`.
+    ///
+    /// # Safety
+    ///
+    /// `peri_addr` must be a register `request` paces, as for
+    /// [`Transfer::new_write`].
+    pub unsafe fn new_write(
+        channel: impl Peripheral<P = impl Channel> + 'a,
+        request: Request,
+        buf: &'static [W],
+        peri_addr: *mut W,
+        options: TransferOptions,
+    ) -> Self {
+        let transfer = Transfer::new_write_raw(channel, request, buf, peri_addr, options);
+        Self { transfer, buf }
+    }
+}
+
+impl<'a, B> OwnedTransfer<'a, B> {
+    /// Wait for the transfer to complete and return the buffer.
+    pub async fn wait(self) -> B {
+        let Self { transfer, buf } = self;
+        transfer.await;
+        buf
+    }
+
+    /// Abort the transfer, wait for the channel to stop and return the buffer.
+    pub fn stop(self) -> B {
+        let Self { transfer, buf } = self;
+        // `Transfer::drop` aborts and waits for the channel.
+        drop(transfer);
+        buf
+    }
+
+    /// Whether the channel is still running.
+    pub fn is_running(&mut self) -> bool {
+        self.transfer.is_running()
+    }
+
+    /// Remaining transfers, in peripheral-sized units.
+    pub fn get_remaining_transfers(&self) -> u16 {
+        self.transfer.get_remaining_transfers()
     }
 }
 // ==============================
@@ -782,6 +1052,7 @@ impl<'a> DmaCtrl for DmaCtrlImpl<'a> {
 
     fn set_waker(&mut self, waker: &Waker) {
         STATE[self.0.state_index()].waker.register(waker);
+        self.0.poll_irq(waker);
     }
 }
 
@@ -865,16 +1136,21 @@ impl<'a, W: Word> ReadableRingBuffer<'a, W> {
     }
 
     /// Clear all data in the ring buffer.
+    ///
+    /// Unread samples are dropped and the read position jumps to the DMA's
+    /// current position. The transfer keeps running, so this is how to
+    /// resynchronize after an [`Error::Overrun`].
     pub fn clear(&mut self) {
         self.ringbuf
-            .reset(&mut DmaCtrlImpl(self.channel.reborrow()));
+            .clear(&mut DmaCtrlImpl(self.channel.reborrow()));
     }
 
     /// Read elements from the ring buffer
     /// Return a tuple of the length read and the length remaining in the buffer
     /// If not all of the elements were read, then there will be some elements in the buffer remaining
     /// The length remaining is the capacity, ring_buf.len(), less the elements remaining after the read
-    /// Error is returned if the portion to be read was overwritten by the DMA controller.
+    /// [`Error::Overrun`] is returned if the portion to be read was overwritten by the DMA
+    /// controller (samples were lost); [`Error::DmaUnsynced`] signals an inconsistent DMA state.
     pub fn read(&mut self, buf: &mut [W]) -> Result<(usize, usize), Error> {
         self.ringbuf
             .read(&mut DmaCtrlImpl(self.channel.reborrow()), buf)
@@ -1143,3 +1419,182 @@ impl<'a, W: Word> Drop for WritableRingBuffer<'a, W> {
         fence(Ordering::SeqCst);
     }
 }
+
+/// Half of a [`DoubleBuffer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Half {
+    /// `buffer[..len / 2]`
+    First,
+    /// `buffer[len / 2..]`
+    Second,
+}
+
+/// Circular peripheral-to-memory DMA split into two halves.
+///
+/// The half-transfer and transfer-complete interrupts mark each half as
+/// filled; [`wait`](Self::wait) returns it while the DMA fills the other.
+/// This is the classic ping-pong scheme for streaming input (audio, ADC).
+///
+/// Compared to [`ReadableRingBuffer`], data is handed out in fixed blocks of
+/// `len / 2` elements: there is no copy and no length bookkeeping, but the
+/// latency is a full half buffer and the consumer must finish with a half
+/// before the DMA wraps back onto it. Missing that deadline returns
+/// [`Error::Overrun`]. Use the ring buffer when reads of arbitrary size or
+/// lower latency matter more than zero-copy.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut dma_buf = [0u32; 512];
+/// let mut db = unsafe {
+///     DoubleBuffer::new(
+///         p.DMAC1_CH1,
+///         Request::AUDPRC_RX_CH0,
+///         rx_fifo_addr,
+///         &mut dma_buf,
+///         TransferOptions::default(),
+///     )
+/// };
+/// db.start();
+/// loop {
+///     let (_half, samples) = db.wait().await?;
+///     process(samples);
+/// }
+/// ```
+pub struct DoubleBuffer<'a, W: Word> {
+    channel: PeripheralRef<'a, AnyChannel>,
+    buffer: *mut W,
+    len: usize,
+    /// Full buffer laps completed, from the channel's complete count.
+    laps: usize,
+    /// Halves handed out by `wait`.
+    consumed: usize,
+    _phantom: core::marker::PhantomData<&'a mut [W]>,
+}
+
+impl<'a, W: Word> DoubleBuffer<'a, W> {
+    /// Create a double buffer over `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer.len()` is odd or zero.
+    pub unsafe fn new(
+        channel: impl Peripheral<P = impl Channel> + 'a,
+        request: Request,
+        peri_addr: *mut W,
+        buffer: &'a mut [W],
+        mut options: TransferOptions,
+    ) -> Self {
+        assert!(
+            !buffer.is_empty() && buffer.len() % 2 == 0,
+            "dma: double buffer length must be even"
+        );
+        into_ref!(channel);
+        let channel: PeripheralRef<'a, AnyChannel> = channel.map_into();
+
+        options.half_transfer_ir = true;
+        options.complete_transfer_ir = true;
+        options.circular = true;
+
+        channel.configure(
+            request,
+            Dir::PeripheralToMemory,
+            peri_addr as *mut u32,
+            buffer.as_mut_ptr() as *mut u32,
+            buffer.len(),
+            Increment::Memory,
+            false,
+            W::size(),
+            W::size(),
+            options,
+        );
+
+        Self {
+            channel,
+            buffer: buffer.as_mut_ptr(),
+            len: buffer.len(),
+            laps: 0,
+            consumed: 0,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Start the transfer.
+    pub fn start(&mut self) {
+        self.channel.start();
+    }
+
+    /// Halves filled so far.
+    fn filled(&mut self) -> usize {
+        // Count laps before reading the position: a wrap in between then
+        // under-counts (picked up on the next wake) instead of over-counting.
+        self.laps += DmaCtrlImpl(self.channel.reborrow()).reset_complete_count();
+        let pos = self.len - self.channel.get_remaining_transfers() as usize;
+        2 * self.laps + (pos >= self.len / 2) as usize
+    }
+
+    /// Wait for the next half to be filled and return it.
+    ///
+    /// Returns [`Error::Overrun`] if the DMA has already started refilling
+    /// it; the next call then continues with the most recently filled half.
+    pub async fn wait(&mut self) -> Result<(Half, &[W]), Error> {
+        let filled = poll_fn(|cx| {
+            DmaCtrlImpl(self.channel.reborrow()).set_waker(cx.waker());
+            let filled = self.filled();
+            if filled > self.consumed {
+                Poll::Ready(filled)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        if filled - self.consumed > 1 {
+            self.consumed = filled - 1;
+            return Err(Error::Overrun);
+        }
+
+        let half = if self.consumed % 2 == 0 {
+            Half::First
+        } else {
+            Half::Second
+        };
+        self.consumed += 1;
+
+        let half_len = self.len / 2;
+        let start = match half {
+            Half::First => self.buffer,
+            Half::Second => self.buffer.wrapping_add(half_len),
+        };
+        // "Subsequent reads and writes cannot be moved ahead of preceding reads."
+        fence(Ordering::SeqCst);
+        // SAFETY: the DMA is filling the other half; the CPU cache may still
+        // hold the previous lap's data for this one.
+        unsafe {
+            let mut scb = cortex_m::Peripherals::steal().SCB;
+            scb.invalidate_dcache_by_address(start as usize, half_len * W::size().bytes());
+            Ok((half, core::slice::from_raw_parts(start, half_len)))
+        }
+    }
+
+    /// Request the DMA to stop. See [`ReadableRingBuffer::request_stop`].
+    pub fn request_stop(&mut self) {
+        self.channel.request_stop()
+    }
+
+    /// Return whether DMA is still running.
+    pub fn is_running(&mut self) -> bool {
+        self.channel.is_running()
+    }
+}
+
+impl<'a, W: Word> Drop for DoubleBuffer<'a, W> {
+    fn drop(&mut self) {
+        self.request_stop();
+        while self.is_running() {}
+
+        // "Subsequent reads and writes cannot be moved ahead of preceding reads."
+        fence(Ordering::SeqCst);
+    }
+}
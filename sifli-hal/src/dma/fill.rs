@@ -0,0 +1,98 @@
+//! DMA-assisted memory fill (memset with a repeating 32-bit pattern).
+
+use embassy_hal_internal::Peripheral;
+
+use super::{Channel, Increment, Transfer, TransferOptions};
+
+/// D-cache line size in bytes.
+const CACHE_LINE: usize = 32;
+
+/// Fill `dst` with a repeating 32-bit `pattern` using a memory-to-memory DMA
+/// transfer, e.g. to clear a framebuffer while the CPU prepares the next frame.
+///
+/// Byte `i` of `dst` receives `pattern.to_le_bytes()[i % 4]`, regardless of the
+/// buffer's alignment. For an RGB565 clear, pass the colour twice:
+/// `(c as u32) << 16 | c as u32`.
+///
+/// The DMA only writes whole D-cache lines: the unaligned head and tail bytes,
+/// the first aligned word and every word sharing a cache line with memory
+/// outside that range are written by the CPU. The DMA lines are cleaned and
+/// invalidated before the transfer starts, so neither a stale line nor the
+/// eviction of a dirty one can hide the DMA's writes; `dst` stays borrowed
+/// until the transfer completes, so the CPU cannot pull them back in early.
+/// The first word is the fixed DMA source for the rest, so `pattern` does not
+/// need to outlive the call.
+///
+/// Returns `None` if `dst` is too short to need DMA (no whole cache line
+/// after the first aligned word); in that case it has already been filled
+/// when this returns.
+///
+/// # Panics
+///
+/// Panics if the aligned body exceeds 65536 words (256 KiB).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let fb: &mut [u8] = framebuffer.as_mut();
+/// if let Some(transfer) = dma::fill(p.DMAC1_CH3, fb, 0xFFFF_FFFF, Default::default()) {
+///     build_overlay(&mut overlay);
+///     transfer.await;
+/// }
+/// ```
+pub fn fill<'a>(
+    channel: impl Peripheral<P = impl Channel> + 'a,
+    dst: &'a mut [u8],
+    pattern: u32,
+    options: TransferOptions,
+) -> Option<Transfer<'a>> {
+    let bytes = pattern.to_le_bytes();
+
+    // SAFETY: u32 has no invalid bit patterns.
+    let (head, body, tail) = unsafe { dst.align_to_mut::<u32>() };
+
+    for (i, b) in head.iter_mut().enumerate() {
+        *b = bytes[i % 4];
+    }
+    // Keep the byte phase continuous across the aligned body and the tail.
+    let word = pattern.rotate_right(8 * (head.len() as u32 % 4));
+    let tail_phase = head.len() + body.len() * 4;
+    for (i, b) in tail.iter_mut().enumerate() {
+        *b = bytes[(tail_phase + i) % 4];
+    }
+
+    let start = body.as_ptr() as usize;
+    let end = start + body.len() * 4;
+    let dma_start = (start / CACHE_LINE + 1) * CACHE_LINE;
+    let dma_end = end / CACHE_LINE * CACHE_LINE;
+    if dma_start >= dma_end {
+        body.fill(word);
+        return None;
+    }
+
+    let (lead, rest) = body.split_at_mut((dma_start - start) / 4);
+    let (rest, trail) = rest.split_at_mut((dma_end - dma_start) / 4);
+    lead.fill(word);
+    trail.fill(word);
+    let src = lead.as_ptr();
+
+    unsafe {
+        let mut scb = cortex_m::Peripherals::steal().SCB;
+        // The DMA reads the seed word from memory, not from the CPU cache.
+        scb.clean_dcache_by_address(src as usize, 4);
+        scb.clean_invalidate_dcache_by_address(dma_start, dma_end - dma_start);
+    }
+
+    // SAFETY: `src` and `rest` are disjoint words of `dst`, which is
+    // borrowed for `'a`.
+    Some(unsafe {
+        Transfer::new_transfer_raw(
+            channel,
+            src,
+            rest.as_mut_ptr(),
+            rest.len(),
+            Increment::Memory,
+            options,
+        )
+    })
+}
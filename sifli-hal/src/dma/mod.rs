@@ -13,12 +13,26 @@ pub use dma::*;
 mod util;
 pub(crate) use util::*;
 
+mod fill;
+pub use fill::fill;
+
+pub mod pool;
+pub use pool::{allocate, AllocatedChannel};
 pub(crate) mod ringbuffer;
 pub mod word;
 
 pub use crate::_generated::Request;
 pub use crate::_generated::DMAC2_ID_FLAG;
 
+/// DMA transfer error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The transfer did not complete in time. The channel has been stopped
+    /// and can be reused.
+    Timeout,
+}
+
 pub(crate) trait SealedChannel {
     fn id(&self) -> u8;
 }
@@ -51,7 +65,7 @@ impl_peripheral!(AnyChannel);
 impl AnyChannel {
     /// Get the DMA controller and channel number for this channel.
     fn info(&self) -> ChannelInfo {
-        if self.id & DMAC2_ID_FLAG != 0 {
+        if self.is_dmac2() {
             // DMAC2 channel (0x80-0x87 -> channel 0-7)
             ChannelInfo {
                 dma: crate::pac::DMAC2,
@@ -66,13 +80,22 @@ impl AnyChannel {
         }
     }
 
+    /// Whether this channel belongs to DMAC2 (LPSYS).
+    ///
+    /// DMAC2 interrupts are routed to the LCPU only, so HCPU never runs
+    /// `on_irq` for these channels and has to service them by polling.
+    #[inline]
+    pub(crate) fn is_dmac2(&self) -> bool {
+        self.id & DMAC2_ID_FLAG != 0
+    }
+
     /// Convert channel ID to STATE array index.
     ///
     /// - DMAC1 channels (0x00-0x07) -> indices 0-7
     /// - DMAC2 channels (0x80-0x87) -> indices 8-15
     #[inline]
     pub(crate) fn state_index(&self) -> usize {
-        if self.id & DMAC2_ID_FLAG != 0 {
+        if self.is_dmac2() {
             crate::_generated::DMAC1_CHANNEL_COUNT + (self.id & 0x7F) as usize
         } else {
             self.id as usize
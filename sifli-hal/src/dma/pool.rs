@@ -0,0 +1,99 @@
+//! Runtime DMA channel allocator.
+//!
+//! Channels are donated to the pool by consuming their singleton, so a pooled
+//! channel can never also be obtained through `degrade()` or the singleton
+//! itself. [`allocate`] then hands out any free pooled channel, and the
+//! returned [`AllocatedChannel`] gives it back on drop.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use sifli_hal::dma;
+//!
+//! dma::pool::donate(p.DMAC1_CH6);
+//! dma::pool::donate(p.DMAC1_CH7);
+//!
+//! let mut ch = dma::pool::allocate().unwrap();
+//! let transfer = unsafe {
+//!     dma::Transfer::new_transfer(ch.channel(), &src, &mut dst, Default::default())
+//! };
+//! transfer.await;
+//! // `ch` returns to the pool when dropped
+//! ```
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::{AnyChannel, Channel, DMAC2_ID_FLAG};
+use crate::_generated::{CHANNEL_COUNT, DMAC1_CHANNEL_COUNT};
+
+const _: () = ::core::assert!(CHANNEL_COUNT <= 32, "DMA pool bitmask holds at most 32 channels");
+
+/// Channels owned by the pool (bit = `AnyChannel::state_index()`).
+static DONATED: AtomicU32 = AtomicU32::new(0);
+/// Pooled channels that are currently free.
+static FREE: AtomicU32 = AtomicU32::new(0);
+
+/// Give a channel to the pool. The singleton is consumed, so the channel
+/// stays pool-managed for the rest of the program.
+pub fn donate(channel: impl Channel) {
+    let bit = 1u32 << channel.degrade().state_index();
+    let prev = DONATED.fetch_or(bit, Ordering::AcqRel);
+    // Singletons can only be donated once; a set bit means the pool is corrupt.
+    assert!(prev & bit == 0, "DMA channel donated twice");
+    FREE.fetch_or(bit, Ordering::Release);
+}
+
+/// Allocate any free pooled channel, or `None` if all are in use.
+pub fn allocate() -> Option<AllocatedChannel> {
+    let mut free = FREE.load(Ordering::Acquire);
+    loop {
+        if free == 0 {
+            return None;
+        }
+        let index = free.trailing_zeros() as usize;
+        let bit = 1u32 << index;
+        match FREE.compare_exchange_weak(free, free & !bit, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => {
+                return Some(AllocatedChannel {
+                    channel: AnyChannel {
+                        id: id_from_state_index(index),
+                    },
+                })
+            }
+            Err(current) => free = current,
+        }
+    }
+}
+
+/// Number of pooled channels currently free.
+pub fn free_count() -> u32 {
+    FREE.load(Ordering::Acquire).count_ones()
+}
+
+fn id_from_state_index(index: usize) -> u8 {
+    if index < DMAC1_CHANNEL_COUNT {
+        index as u8
+    } else {
+        DMAC2_ID_FLAG | (index - DMAC1_CHANNEL_COUNT) as u8
+    }
+}
+
+/// A channel borrowed from the pool. Returned to the pool on drop.
+pub struct AllocatedChannel {
+    channel: AnyChannel,
+}
+
+impl AllocatedChannel {
+    /// Borrow the underlying channel for use with [`Transfer`](super::Transfer)
+    /// or ring buffer constructors.
+    pub fn channel(&mut self) -> &mut AnyChannel {
+        &mut self.channel
+    }
+}
+
+impl Drop for AllocatedChannel {
+    fn drop(&mut self) {
+        let bit = 1u32 << self.channel.state_index();
+        FREE.fetch_or(bit, Ordering::Release);
+    }
+}
@@ -19,9 +19,12 @@ pub trait DmaCtrl {
     fn set_waker(&mut self, waker: &Waker);
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error {
+    /// The reader fell a full buffer behind the DMA (or the DMA caught up
+    /// with the writer): data was lost, but the transfer itself is healthy.
+    /// Call `clear()` to resynchronize and keep going.
     Overrun,
     /// the newly read DMA positions don't make sense compared to the previous
     /// ones. This can usually only occur due to wrong Driver implementation, if
@@ -105,6 +108,16 @@ impl<'a, W: Word> ReadableDmaRingBuffer<'a, W> {
         self.read_index = self.write_index;
     }
 
+    /// Drop all unread samples and resynchronize the read position with the
+    /// DMA, e.g. after an [`Error::Overrun`].
+    ///
+    /// The transfer keeps running; the next read returns only samples that
+    /// arrive after this call.
+    pub fn clear(&mut self, dma: &mut impl DmaCtrl) {
+        self.write_index.dma_sync(self.cap(), dma);
+        self.read_index = self.write_index;
+    }
+
     /// Get the full ringbuffer capacity.
     pub const fn cap(&self) -> usize {
         self.dma_buf.len()
@@ -131,11 +144,16 @@ impl<'a, W: Word> ReadableDmaRingBuffer<'a, W> {
     /// Return a tuple of the length read and the length remaining in the buffer
     /// If not all of the elements were read, then there will be some elements in the buffer remaining
     /// The length remaining is the capacity, ring_buf.len(), less the elements remaining after the read
-    /// Error is returned if the portion to be read was overwritten by the DMA controller,
-    /// in which case the rinbuffer will automatically reset itself.
+    ///
+    /// [`Error::Overrun`] is returned if the portion to be read was overwritten by the DMA
+    /// controller (the reader lagged more than a full buffer); the ringbuffer then
+    /// [`clear`](Self::clear)s itself so the next read resumes with fresh data.
+    /// [`Error::DmaUnsynced`] means the DMA position itself is inconsistent; the
+    /// ringbuffer is reset.
     pub fn read(&mut self, dma: &mut impl DmaCtrl, buf: &mut [W]) -> Result<(usize, usize), Error> {
-        self.read_raw(dma, buf).inspect_err(|_e| {
-            self.reset(dma);
+        self.read_raw(dma, buf).inspect_err(|e| match e {
+            Error::Overrun => self.clear(dma),
+            Error::DmaUnsynced => self.reset(dma),
         })
     }
 
@@ -10,6 +10,7 @@ use super::*;
 enum ReaderTransition {
     Write(usize),
     Reset,
+    Clear,
     ReadUpTo(usize),
 }
 
@@ -28,6 +29,7 @@ impl ReferenceStateMachine for ReaderSM {
             (1..50_usize).prop_map(ReaderTransition::Write),
             (1..50_usize).prop_map(ReaderTransition::ReadUpTo),
             strategy::Just(ReaderTransition::Reset),
+            strategy::Just(ReaderTransition::Clear),
         ]
         .boxed()
     }
@@ -35,7 +37,9 @@ impl ReferenceStateMachine for ReaderSM {
     fn apply(status: Self::State, transition: &Self::Transition) -> Self::State {
         match (status, transition) {
             (_, ReaderTransition::Reset) => Status::Available(0),
+            (_, ReaderTransition::Clear) => Status::Available(0),
             (Status::Available(x), ReaderTransition::Write(y)) => {
+                // Overrun as soon as the reader lags more than a full buffer.
                 if x + y > CAP {
                     Status::Failed
                 } else {
@@ -98,6 +102,10 @@ impl StateMachineTest for ReaderTest {
             ReaderTransition::Reset => {
                 sut.consumer.reset(&mut sut.producer);
             }
+            ReaderTransition::Clear => {
+                sut.consumer.clear(&mut sut.producer);
+                assert_eq!(sut.consumer.len(&mut sut.producer), Ok(0));
+            }
             ReaderTransition::ReadUpTo(x) => {
                 let status = sut.status;
                 let ReaderSut {
@@ -113,7 +121,7 @@ impl StateMachineTest for ReaderTest {
 
                         assert_eq!(res.unwrap().0, readable);
                     }
-                    Status::Failed => assert!(res.is_err()),
+                    Status::Failed => assert_eq!(res, Err(Error::Overrun)),
                 }
             }
         }
@@ -29,6 +29,10 @@ trait SealedWord {}
 /// DMA word trait.
 ///
 /// This is implemented for u8, u16, u32, etc.
+///
+/// Drivers whose data register has a fixed FIFO width take buffers of that
+/// width (e.g. [`Adc::read_continuous`](crate::adc::Adc::read_continuous)
+/// takes `&mut [u16]`), so a mismatched element type fails to compile.
 #[allow(private_bounds)]
 pub trait Word: SealedWord + Default + Copy + 'static {
     /// Word size
@@ -46,6 +46,33 @@ impl Bank1Calibration {
             vol2,
         }
     }
+
+    /// Whether factory calibration was written to this bank.
+    ///
+    /// The bank carries no checksum or overall valid bit, and an unprogrammed
+    /// fuse reads as all zeros, which would decode to plausible-looking zero
+    /// trims. Like the SDK, this treats a row as programmed when its HPSYS
+    /// LDO code is non-zero, since every factory-tested part gets one; true
+    /// if either the primary or the `vol2` row is. Individual blocks have
+    /// their own flags on top ([`Bank1PrimaryHigh::vref_flag`],
+    /// [`Bank1PrimaryHigh::tmxcap_flag`], [`Bank1PrimaryLow::edr_cal_done`]).
+    pub fn is_programmed(&self) -> bool {
+        self.primary.is_programmed() || self.vol2.is_programmed()
+    }
+}
+
+impl Bank1Primary {
+    /// Whether the primary row is programmed (non-zero HPSYS LDO code).
+    pub fn is_programmed(&self) -> bool {
+        self.low.hpsys_ldo_vout() != 0
+    }
+}
+
+impl Bank1Vol2 {
+    /// Whether the `vol2` row is programmed (non-zero HPSYS LDO code).
+    pub fn is_programmed(&self) -> bool {
+        self.hpsys_ldo_vout() != 0
+    }
 }
 
 fn u128_from_le_words(words: &[u32]) -> u128 {
@@ -1,15 +1,41 @@
 //! EFUSE (eFuse controller)
-
+//!
+//! [`Efuse::new`] reads the controller by polling. [`Efuse::new_async`]
+//! waits for the EFUSEC done interrupt instead, so the task yields while a
+//! bank is read:
+//!
+//! ```rust,ignore
+//! bind_interrupts!(struct Irqs {
+//!     EFUSEC => efuse::InterruptHandler;
+//! });
+//!
+//! let mut efuse = Efuse::new_async(p.EFUSEC, Irqs).await?;
+//! let bank2 = efuse.read_bank(2).await?;
+//! ```
+
+use core::future::poll_fn;
 use core::marker::PhantomData;
+use core::sync::atomic::{compiler_fence, AtomicBool, Ordering};
+use core::task::Poll;
 
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::Peripheral;
+use embassy_sync::waitqueue::AtomicWaker;
 
+use crate::interrupt::typelevel::Binding;
+use crate::interrupt::InterruptExt;
+use crate::mode::{Async, Blocking, Mode};
 use crate::pac::{EFUSEC, PMUC};
-use crate::{blocking_delay_us, peripherals, rcc};
+use crate::{blocking_delay_us, interrupt, peripherals, rcc};
 
 mod bank1;
 pub use bank1::{Bank1Calibration, Bank1Primary, Bank1PrimaryHigh, Bank1PrimaryLow, Bank1Vol2};
 
+#[cfg(any(test, feature = "efuse-sim"))]
+mod sim;
+#[cfg(any(test, feature = "efuse-sim"))]
+pub use sim::SimEfuse;
+
 /// EFUSE error.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -27,8 +53,14 @@ pub enum Error {
 }
 
 /// Unique ID (UID) read from EFUSE.
+///
+/// The 16 bytes are bank0 words 0..=3, each stored little-endian, in the
+/// order [`bytes`](Self::bytes) returns them. The canonical string is those
+/// bytes as 32 lowercase hex digits, byte 0 first (this is what `Display`
+/// and `defmt::Format` print), and [`as_u128`](Self::as_u128) is the same
+/// digits read as one big-endian number. For bank0 words
+/// `[0x1122_3344, ..]` the string starts with `44332211`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Uid {
     bytes: [u8; 16],
 }
@@ -50,6 +82,11 @@ impl Uid {
         ]
     }
 
+    /// Get UID as a 128-bit integer whose hex form is the canonical string.
+    pub fn as_u128(&self) -> u128 {
+        u128::from_be_bytes(self.bytes)
+    }
+
     fn from_bank0_words(bank0_words: &[u32; 8]) -> Self {
         let mut bytes = [0u8; 16];
         for (i, word) in bank0_words.iter().take(4).enumerate() {
@@ -59,34 +96,154 @@ impl Uid {
     }
 }
 
+impl core::fmt::Display for Uid {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for b in self.bytes {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Uid {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u128:032x}", self.as_u128())
+    }
+}
+
+static WAKER: AtomicWaker = AtomicWaker::new();
+static IRQ_DONE: AtomicBool = AtomicBool::new(false);
+
+/// Upper bound for one bank read in async mode. A read takes tens of
+/// microseconds; this only catches a controller that never signals DONE.
+#[cfg(feature = "time")]
+const READ_TIMEOUT: embassy_time::Duration = embassy_time::Duration::from_millis(10);
+
 /// EFUSE driver.
-pub struct Efuse<'d> {
+pub struct Efuse<'d, M: Mode = Blocking> {
     bank0_words: [u32; 8],
     bank1_words: [u32; 8],
     uid: Uid,
     bank1_calibration: Bank1Calibration,
-    _phantom: PhantomData<&'d peripherals::EFUSEC>,
+    /// Contents served by [`blocking_read_bank`](Self::blocking_read_bank)
+    /// for a driver built with [`new_simulated`](Efuse::new_simulated).
+    #[cfg(any(test, feature = "efuse-sim"))]
+    sim_banks: Option<[[u32; 8]; 4]>,
+    _phantom: PhantomData<(&'d peripherals::EFUSEC, M)>,
 }
 
-impl<'d> Efuse<'d> {
+impl<'d> Efuse<'d, Blocking> {
     /// Create a new EFUSE driver, initialize the controller timing register, and cache the UID
     /// and bank0/bank1 raw contents in memory.
     pub fn new(_efusec: impl Peripheral<P = peripherals::EFUSEC> + 'd) -> Result<Self, Error> {
         rcc::enable_and_reset::<peripherals::EFUSEC>();
-        init_timr()?;
+        let pclk_hz = rcc::peripheral_clock::<peripherals::EFUSEC>().map(|f| f.0);
+        Self::load(&mut Hardware, pclk_hz)
+    }
+
+    /// Create a driver backed by a [`SimEfuse`] instead of the controller.
+    ///
+    /// Runs the same timing setup and bank reads as [`Efuse::new`], with
+    /// `pclk_hz` standing in for the RCC clock tree, so code built on
+    /// [`Efuse`] can be tested on the host.
+    #[cfg(any(test, feature = "efuse-sim"))]
+    pub fn new_simulated(sim: &mut SimEfuse, pclk_hz: u32) -> Result<Self, Error> {
+        let mut efuse = Self::load(sim, Some(pclk_hz))?;
+        efuse.sim_banks = Some(sim.banks());
+        Ok(efuse)
+    }
+
+    fn load(backend: &mut impl Backend, pclk_hz: Option<u32>) -> Result<Self, Error> {
+        let (thrck, thpck, tckhp) = timr_values(pclk_hz)?;
+        backend.set_timings(thrck, thpck, tckhp);
+
+        let bank0_words = backend.read_bank(0)?;
+        let bank1_words = backend.read_bank(1)?;
+        Ok(Self::from_banks(bank0_words, bank1_words))
+    }
+}
+
+/// EFUSEC interrupt handler, for [`Efuse::new_async`].
+pub struct InterruptHandler;
 
-        let bank0_words = read_bank_words(0)?;
-        let bank1_words = read_bank_words(1)?;
+impl interrupt::typelevel::Handler<interrupt::typelevel::EFUSEC> for InterruptHandler {
+    unsafe fn on_interrupt() {
+        if EFUSEC.sr().read().done() {
+            // DONE is cleared by the reader; mask it so the IRQ does not refire.
+            EFUSEC.cr().modify(|w| w.set_ie(false));
+            IRQ_DONE.store(true, Ordering::SeqCst);
+        }
+        WAKER.wake();
+    }
+}
+
+impl<'d> Efuse<'d, Async> {
+    /// Create a new EFUSE driver like [`Efuse::new`], waiting for the bank0
+    /// and bank1 reads on the EFUSEC interrupt instead of polling.
+    pub async fn new_async(
+        _efusec: impl Peripheral<P = peripherals::EFUSEC> + 'd,
+        _irq: impl Binding<interrupt::typelevel::EFUSEC, InterruptHandler>,
+    ) -> Result<Self, Error> {
+        rcc::enable_and_reset::<peripherals::EFUSEC>();
+        let pclk_hz = rcc::peripheral_clock::<peripherals::EFUSEC>().map(|f| f.0);
+        let (thrck, thpck, tckhp) = timr_values(pclk_hz)?;
+        Hardware.set_timings(thrck, thpck, tckhp);
+
+        let irq = crate::interrupt::EFUSEC;
+        irq.unpend();
+        unsafe { irq.enable() };
+
+        let bank0_words = read_bank_words_async(0).await?;
+        let bank1_words = read_bank_words_async(1).await?;
+        Ok(Self::from_banks(bank0_words, bank1_words))
+    }
+
+    /// Read the 8 words of `bank` (0..=3) from the controller, yielding
+    /// until the EFUSEC done interrupt.
+    ///
+    /// With the `time` feature, fails with [`Error::Timeout`] if the read
+    /// does not complete. If the future is dropped mid-read, the supply
+    /// boost applied for the read is undone.
+    pub async fn read_bank(&mut self, bank: u8) -> Result<[u32; 8], Error> {
+        read_bank_words_async(bank).await
+    }
+}
+
+impl<'d, M: Mode> Efuse<'d, M> {
+    fn from_banks(bank0_words: [u32; 8], bank1_words: [u32; 8]) -> Self {
         let uid = Uid::from_bank0_words(&bank0_words);
         let bank1_calibration = Bank1Calibration::decode(&bank1_words);
+        if !bank1_calibration.is_programmed() {
+            warn!("efuse: bank1 calibration is not programmed");
+        }
 
-        Ok(Self {
+        Self {
             bank0_words,
             bank1_words,
             uid,
             bank1_calibration,
+            #[cfg(any(test, feature = "efuse-sim"))]
+            sim_banks: None,
             _phantom: PhantomData,
-        })
+        }
+    }
+
+    /// Read the 8 words of `bank` (0..=3) from the controller by polling.
+    ///
+    /// Unlike the cached [`bank0_words`](Self::bank0_words) and
+    /// [`bank1_words`](Self::bank1_words), this always goes to the
+    /// hardware; it is also the way to reach banks 2 and 3. A driver from
+    /// [`new_simulated`](Efuse::new_simulated) serves the simulated banks.
+    pub fn blocking_read_bank(&mut self, bank: u8) -> Result<[u32; 8], Error> {
+        #[cfg(any(test, feature = "efuse-sim"))]
+        if let Some(banks) = &self.sim_banks {
+            return match banks.get(bank as usize) {
+                Some(words) => Ok(*words),
+                None => Err(Error::InvalidBank { bank }),
+            };
+        }
+        read_bank_words(bank)
     }
 
     /// Get cached UID.
@@ -105,13 +262,52 @@ impl<'d> Efuse<'d> {
     }
 
     /// Get cached bank1 factory calibration values.
+    ///
+    /// On an unprovisioned chip these are all zero; check
+    /// [`Bank1Calibration::is_programmed`] or use
+    /// [`programmed_calibration`](Self::programmed_calibration) before
+    /// applying them.
     pub fn calibration(&self) -> &Bank1Calibration {
         &self.bank1_calibration
     }
+
+    /// Bank1 calibration values, or `None` if the bank is not programmed.
+    pub fn programmed_calibration(&self) -> Option<&Bank1Calibration> {
+        self.bank1_calibration
+            .is_programmed()
+            .then_some(&self.bank1_calibration)
+    }
+}
+
+/// Access to the bank contents and the timing register.
+///
+/// [`Hardware`] drives EFUSEC; [`SimEfuse`] serves canned words for host tests.
+pub(crate) trait Backend {
+    /// Program the TIMR fields.
+    fn set_timings(&mut self, thrck: u8, thpck: u8, tckhp: u16);
+    /// Read the 8 words of `bank`.
+    fn read_bank(&mut self, bank: u8) -> Result<[u32; 8], Error>;
 }
 
-fn init_timr() -> Result<(), Error> {
-    let pclk_hz = rcc::get_pclk_freq().ok_or(Error::PclkUnknown)?.0;
+/// The EFUSEC controller.
+struct Hardware;
+
+impl Backend for Hardware {
+    fn set_timings(&mut self, thrck: u8, thpck: u8, tckhp: u16) {
+        EFUSEC.timr().write(|w| {
+            w.set_thrck(thrck);
+            w.set_thpck(thpck);
+            w.set_tckhp(tckhp);
+        });
+    }
+
+    fn read_bank(&mut self, bank: u8) -> Result<[u32; 8], Error> {
+        read_bank_words(bank)
+    }
+}
+
+fn timr_values(pclk_hz: Option<u32>) -> Result<(u8, u8, u16), Error> {
+    let pclk_hz = pclk_hz.ok_or(Error::PclkUnknown)?;
 
     // CSDK: EFUSE_PCLK_LIMIT = 120000000
     #[cfg(not(feature = "unchecked-overclocking"))]
@@ -119,14 +315,22 @@ fn init_timr() -> Result<(), Error> {
         return Err(Error::PclkTooFast { pclk_hz });
     }
 
-    let (thrck, thpck, tckhp) = compute_timings(pclk_hz)?;
-    EFUSEC.timr().write(|w| {
-        w.set_thrck(thrck);
-        w.set_thpck(thpck);
-        w.set_tckhp(tckhp);
-    });
+    compute_timings(pclk_hz)
+}
 
-    Ok(())
+/// Raise HPSYS_VOUT for a read and return the original setting.
+///
+/// CSDK (SF32LB52X): temporarily raise HPSYS_VOUT before reading efuse.
+fn boost_vout() -> u8 {
+    let org_vout = PMUC.hpsys_vout().read().vout();
+    let value = (org_vout as u32 + 3).clamp(0xe, 0xf);
+    PMUC.hpsys_vout().modify(|w| w.set_vout(value as u8));
+    blocking_delay_us(20);
+    org_vout
+}
+
+fn restore_vout(org_vout: u8) {
+    PMUC.hpsys_vout().modify(|w| w.set_vout(org_vout));
 }
 
 fn read_bank_words(bank: u8) -> Result<[u32; 8], Error> {
@@ -134,14 +338,7 @@ fn read_bank_words(bank: u8) -> Result<[u32; 8], Error> {
         return Err(Error::InvalidBank { bank });
     }
 
-    // CSDK (SF32LB52X): temporarily raise HPSYS_VOUT before reading efuse.
-    let org_vout = PMUC.hpsys_vout().read();
-    let mut boosted_vout = org_vout;
-    let mut value = boosted_vout.vout() as u32 + 3;
-    value = value.clamp(0xe, 0xf);
-    boosted_vout.set_vout(value as u8);
-    PMUC.hpsys_vout().write_value(boosted_vout);
-    blocking_delay_us(20);
+    let org_vout = boost_vout();
 
     // Select bank and start READ.
     EFUSEC.cr().write(|w| {
@@ -162,18 +359,65 @@ fn read_bank_words(bank: u8) -> Result<[u32; 8], Error> {
     EFUSEC.sr().write(|w| w.set_done(true));
 
     if ready >= timeout {
-        PMUC.hpsys_vout().write_value(org_vout);
+        restore_vout(org_vout);
         return Err(Error::Timeout { bank });
     }
 
     let words = read_bank_data_words(bank);
 
     // Restore HPSYS_VOUT.
-    PMUC.hpsys_vout().write_value(org_vout);
+    restore_vout(org_vout);
 
     Ok(words)
 }
 
+async fn read_bank_words_async(bank: u8) -> Result<[u32; 8], Error> {
+    if bank >= 4 {
+        return Err(Error::InvalidBank { bank });
+    }
+
+    let org_vout = boost_vout();
+    // Undo the boost and stop the read if the future is dropped.
+    let on_drop = OnDrop::new(move || {
+        EFUSEC.cr().modify(|w| w.set_ie(false));
+        EFUSEC.sr().write(|w| w.set_done(true));
+        restore_vout(org_vout);
+    });
+
+    IRQ_DONE.store(false, Ordering::SeqCst);
+    EFUSEC.cr().write(|w| {
+        w.set_banksel(bank);
+        w.set_mode(false);
+        w.set_ie(true);
+    });
+    compiler_fence(Ordering::SeqCst);
+    EFUSEC.cr().modify(|w| w.set_en(true));
+
+    let done = poll_fn(|cx| {
+        WAKER.register(cx.waker());
+        if IRQ_DONE.swap(false, Ordering::SeqCst) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    });
+    #[cfg(feature = "time")]
+    let result = embassy_time::with_timeout(READ_TIMEOUT, done)
+        .await
+        .map_err(|_| Error::Timeout { bank });
+    #[cfg(not(feature = "time"))]
+    let result = {
+        done.await;
+        Ok(())
+    };
+
+    // Read the data before the cleanup masks the IRQ, clears DONE and
+    // restores HPSYS_VOUT.
+    let words = result.map(|()| read_bank_data_words(bank));
+    drop(on_drop);
+    words
+}
+
 fn read_bank_data_words(bank: u8) -> [u32; 8] {
     match bank {
         0 => [
@@ -225,21 +469,11 @@ fn compute_timings(pclk_hz: u32) -> Result<(u8, u8, u16), Error> {
 
     // EFUSE_RD_TIM_NS = 500
     let rd_thrck = (500u64 * pclk_hz as u64) / 1_000_000_000u64 + 1;
-    if rd_thrck > 0x7f {
-        return Err(Error::TimingOutOfRange {
-            field: "thrck",
-            value: rd_thrck as u32,
-        });
-    }
+    let rd_thrck = check_timing("thrck", rd_thrck, 0x7f)?;
 
     // EFUSE_PGM_THPCK_NS = 20
     let pgm_thpck = (20u64 * pclk_hz as u64) / 1_000_000_000u64 + 1;
-    if pgm_thpck > 0x07 {
-        return Err(Error::TimingOutOfRange {
-            field: "thpck",
-            value: pgm_thpck as u32,
-        });
-    }
+    let pgm_thpck = check_timing("thpck", pgm_thpck, 0x07)?;
 
     // EFUSE_PGM_TCKHP_US = 10
     let mut pgm_tckhp = ((10u64 * pclk_hz as u64) + 500_000) / 1_000_000u64;
@@ -249,15 +483,36 @@ fn compute_timings(pclk_hz: u32) -> Result<(u8, u8, u16), Error> {
     } else if pgm_tckhp_ns < 9_000 {
         pgm_tckhp += 1;
     }
-    if pgm_tckhp > 0x07ff {
-        return Err(Error::TimingOutOfRange {
-            field: "tckhp",
-            value: pgm_tckhp as u32,
-        });
-    }
+    let pgm_tckhp = check_timing("tckhp", pgm_tckhp, 0x07ff)?;
 
     Ok((rd_thrck as u8, pgm_thpck as u8, pgm_tckhp as u16))
 }
 
+/// Range-check a TIMR field.
+///
+/// With `unchecked-overclocking`, out-of-range values are saturated to the
+/// register maximum (timing is then shorter than the CSDK requires) so the
+/// controller still attempts the operation.
+fn check_timing(field: &'static str, value: u64, max: u64) -> Result<u64, Error> {
+    if value <= max {
+        return Ok(value);
+    }
+
+    #[cfg(feature = "unchecked-overclocking")]
+    {
+        warn!(
+            "efuse: {} = {} exceeds register max {}, saturating (unchecked-overclocking)",
+            field, value, max
+        );
+        Ok(max)
+    }
+
+    #[cfg(not(feature = "unchecked-overclocking"))]
+    Err(Error::TimingOutOfRange {
+        field,
+        value: value as u32,
+    })
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,81 @@
+use super::{Backend, Error};
+
+/// Simulated EFUSE contents for host-side tests.
+///
+/// Serves fixed bank words through the same path [`Efuse::new`](super::Efuse::new)
+/// uses on hardware and records the timing written to TIMR:
+///
+/// ```rust,ignore
+/// let mut sim = SimEfuse::new().with_bank(1, bank1_words);
+/// let efuse = Efuse::new_simulated(&mut sim, 120_000_000)?;
+/// apply_calibration(efuse.calibration());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SimEfuse {
+    banks: [[u32; 8]; 4],
+    timings: Option<(u8, u8, u16)>,
+    timeout_bank: Option<u8>,
+    reads: u32,
+}
+
+impl SimEfuse {
+    /// All four banks blank (all-zero words, as on an unprogrammed part).
+    pub const fn new() -> Self {
+        Self {
+            banks: [[0; 8]; 4],
+            timings: None,
+            timeout_bank: None,
+            reads: 0,
+        }
+    }
+
+    /// Set the words returned for `bank` (0..=3).
+    pub const fn with_bank(mut self, bank: u8, words: [u32; 8]) -> Self {
+        self.banks[bank as usize] = words;
+        self
+    }
+
+    /// Make reads of `bank` fail with [`Error::Timeout`].
+    pub const fn with_timeout(mut self, bank: u8) -> Self {
+        self.timeout_bank = Some(bank);
+        self
+    }
+
+    /// `(thrck, thpck, tckhp)` last written to TIMR, if any.
+    pub fn timings(&self) -> Option<(u8, u8, u16)> {
+        self.timings
+    }
+
+    /// Number of successful bank reads so far.
+    pub fn reads(&self) -> u32 {
+        self.reads
+    }
+
+    /// Words of all four banks.
+    pub(super) fn banks(&self) -> [[u32; 8]; 4] {
+        self.banks
+    }
+}
+
+impl Default for SimEfuse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for SimEfuse {
+    fn set_timings(&mut self, thrck: u8, thpck: u8, tckhp: u16) {
+        self.timings = Some((thrck, thpck, tckhp));
+    }
+
+    fn read_bank(&mut self, bank: u8) -> Result<[u32; 8], Error> {
+        if bank >= 4 {
+            return Err(Error::InvalidBank { bank });
+        }
+        if self.timeout_bank == Some(bank) {
+            return Err(Error::Timeout { bank });
+        }
+        self.reads += 1;
+        Ok(self.banks[bank as usize])
+    }
+}
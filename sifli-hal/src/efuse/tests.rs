@@ -1,5 +1,6 @@
 use super::bank1::Bank1Calibration;
 use super::Uid;
+use super::{compute_timings, Efuse, Error, SimEfuse};
 
 fn set_bits(words: &mut [u32; 8], pos: u16, bits: u8, value: u32) {
     for i in 0..bits {
@@ -84,3 +85,106 @@ fn uid_words_le_roundtrip() {
         [0x1122_3344, 0x5566_7788, 0x99aa_bbcc, 0xddee_ff00]
     );
 }
+
+#[test]
+fn uid_string_matches_u128() {
+    let uid = Uid::from_bank0_words(&[
+        0x1122_3344,
+        0x5566_7788,
+        0x99aa_bbcc,
+        0xddee_ff00,
+        0,
+        0,
+        0,
+        0,
+    ]);
+    let s = format!("{}", uid);
+    assert_eq!(s, "4433221188776655ccbbaa9900ffeedd");
+    assert_eq!(format!("{:032x}", uid.as_u128()), s);
+}
+
+#[test]
+fn timings_at_sdk_pclk() {
+    // 120 MHz: thrck = 61, thpck = 3, tckhp = 1200
+    assert_eq!(compute_timings(120_000_000), Ok((61, 3, 1200)));
+}
+
+#[cfg(not(feature = "unchecked-overclocking"))]
+#[test]
+fn timings_out_of_range_is_error() {
+    assert_eq!(
+        compute_timings(400_000_000),
+        Err(Error::TimingOutOfRange {
+            field: "thrck",
+            value: 201,
+        })
+    );
+}
+
+#[cfg(feature = "unchecked-overclocking")]
+#[test]
+fn timings_out_of_range_saturates() {
+    assert_eq!(compute_timings(400_000_000), Ok((0x7f, 0x07, 0x07ff)));
+}
+
+#[test]
+fn simulated_load_caches_banks() {
+    let mut bank1 = [0u32; 8];
+    set_bits(&mut bank1, 61, 5, 0b10101);
+    let mut sim = SimEfuse::new()
+        .with_bank(0, [0x1122_3344, 0x5566_7788, 0x99aa_bbcc, 0xddee_ff00, 0, 0, 0, 0])
+        .with_bank(1, bank1);
+
+    let efuse = Efuse::new_simulated(&mut sim, 120_000_000).unwrap();
+    assert_eq!(sim.timings(), Some((61, 3, 1200)));
+    assert_eq!(sim.reads(), 2);
+    assert_eq!(efuse.uid().words_le()[0], 0x1122_3344);
+    assert_eq!(efuse.bank1_words(), &bank1);
+    assert_eq!(efuse.calibration().primary.low.volt2_100mv(), 0b10101);
+}
+
+#[test]
+fn blank_bank1_is_not_programmed() {
+    let mut sim = SimEfuse::new();
+    let efuse = Efuse::new_simulated(&mut sim, 48_000_000).unwrap();
+    assert!(!efuse.calibration().is_programmed());
+    assert!(efuse.programmed_calibration().is_none());
+
+    let mut bank1 = [0u32; 8];
+    set_bits(&mut bank1, 164, 4, 0xb); // HPSYS_LDO_VOUT_Vol2
+    let mut sim = SimEfuse::new().with_bank(1, bank1);
+    let efuse = Efuse::new_simulated(&mut sim, 48_000_000).unwrap();
+    assert!(!efuse.calibration().primary.is_programmed());
+    assert!(efuse.programmed_calibration().is_some());
+}
+
+#[test]
+fn simulated_read_timeout_is_reported() {
+    let mut sim = SimEfuse::new().with_timeout(1);
+    assert!(matches!(
+        Efuse::new_simulated(&mut sim, 48_000_000),
+        Err(Error::Timeout { bank: 1 })
+    ));
+}
+
+#[cfg(not(feature = "unchecked-overclocking"))]
+#[test]
+fn simulated_pclk_too_fast_skips_timr() {
+    let mut sim = SimEfuse::new();
+    assert!(matches!(
+        Efuse::new_simulated(&mut sim, 144_000_000),
+        Err(Error::PclkTooFast { pclk_hz: 144_000_000 })
+    ));
+    assert_eq!(sim.timings(), None);
+    assert_eq!(sim.reads(), 0);
+}
+
+#[test]
+fn simulated_read_bank_serves_all_banks() {
+    let bank2 = [2, 0, 0, 0, 0, 0, 0, 0x8000_0000];
+    let mut sim = SimEfuse::new().with_bank(2, bank2);
+    let mut efuse = Efuse::new_simulated(&mut sim, 48_000_000).unwrap();
+    assert_eq!(efuse.blocking_read_bank(2), Ok(bank2));
+    assert_eq!(efuse.blocking_read_bank(0), Ok([0; 8]));
+    assert_eq!(efuse.blocking_read_bank(4), Err(Error::InvalidBank { bank: 4 }));
+}
@@ -0,0 +1,267 @@
+//! Bit-banged SPI over plain GPIOs.
+//!
+//! Fallback for devices wired to pins that no hardware SPI/LCDC instance can
+//! reach. Timing is generated with [`blocking_delay_us`](crate::blocking_delay_us),
+//! so the clock is coarse and the CPU is busy for the whole transfer.
+//!
+//! # Achievable frequency
+//!
+//! Each SCK half-period is `half_period_us` microseconds plus GPIO/loop
+//! overhead, so `f_sck <= 1 / (2 * half_period_us)`:
+//!
+//! | `half_period_us` | max SCK   |
+//! |------------------|-----------|
+//! | 1                | ~500 kHz  |
+//! | 5                | ~100 kHz  |
+//! | 50               | ~10 kHz   |
+//!
+//! With `half_period_us = 0` no delay is inserted and SCK runs as fast as the
+//! CPU can toggle the pin (roughly 1-3 MHz depending on `hclk`). Check the
+//! device's setup/hold requirements before using 0.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use sifli_hal::gpio::bitbang::{Config, SpiBus};
+//! use embedded_hal_1::spi::SpiBus as _;
+//!
+//! let mut spi = SpiBus::new(p.PA10, p.PA11, p.PA12, Config::default());
+//! let mut buf = [0x9F, 0, 0, 0];
+//! spi.transfer_in_place(&mut buf).unwrap();
+//! ```
+
+use core::convert::Infallible;
+
+use embedded_hal_1::spi::{Mode, Phase, Polarity, MODE_0};
+
+use super::{Input, Level, Output, Pin, Pull};
+use crate::Peripheral;
+
+/// Bit transmission order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BitOrder {
+    /// Most significant bit first.
+    MsbFirst,
+    /// Least significant bit first.
+    LsbFirst,
+}
+
+/// Bit-banged SPI configuration.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Config {
+    /// SPI mode (clock polarity and phase).
+    pub mode: Mode,
+    /// Bit order.
+    pub bit_order: BitOrder,
+    /// SCK half-period in microseconds. See the [module docs](self) for the
+    /// resulting frequency.
+    pub half_period_us: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mode: MODE_0,
+            bit_order: BitOrder::MsbFirst,
+            half_period_us: 1,
+        }
+    }
+}
+
+impl Config {
+    pub const fn new() -> Self {
+        Self {
+            mode: MODE_0,
+            bit_order: BitOrder::MsbFirst,
+            half_period_us: 1,
+        }
+    }
+
+    pub const fn with_mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub const fn with_bit_order(mut self, bit_order: BitOrder) -> Self {
+        self.bit_order = bit_order;
+        self
+    }
+
+    pub const fn with_half_period_us(mut self, half_period_us: u32) -> Self {
+        self.half_period_us = half_period_us;
+        self
+    }
+}
+
+/// Bit-banged SPI bus implementing [`embedded_hal_1::spi::SpiBus`].
+///
+/// Chip select is not handled here; wrap the bus in an
+/// `embedded_hal_bus`/`embassy_embedded_hal` `SpiDevice` for that.
+pub struct SpiBus<'d> {
+    sck: Output<'d>,
+    mosi: Option<Output<'d>>,
+    miso: Option<Input<'d>>,
+    config: Config,
+}
+
+impl<'d> SpiBus<'d> {
+    /// Create a full-duplex bit-banged SPI bus.
+    pub fn new(
+        sck: impl Peripheral<P = impl Pin> + 'd,
+        mosi: impl Peripheral<P = impl Pin> + 'd,
+        miso: impl Peripheral<P = impl Pin> + 'd,
+        config: Config,
+    ) -> Self {
+        Self {
+            sck: Output::new(sck, Self::idle_level(&config)),
+            mosi: Some(Output::new(mosi, Level::Low)),
+            miso: Some(Input::new(miso, Pull::None)),
+            config,
+        }
+    }
+
+    /// Create a transmit-only bit-banged SPI bus. Reads return `0x00`.
+    pub fn new_txonly(
+        sck: impl Peripheral<P = impl Pin> + 'd,
+        mosi: impl Peripheral<P = impl Pin> + 'd,
+        config: Config,
+    ) -> Self {
+        Self {
+            sck: Output::new(sck, Self::idle_level(&config)),
+            mosi: Some(Output::new(mosi, Level::Low)),
+            miso: None,
+            config,
+        }
+    }
+
+    /// Create a receive-only bit-banged SPI bus. Nothing is driven on MOSI.
+    pub fn new_rxonly(
+        sck: impl Peripheral<P = impl Pin> + 'd,
+        miso: impl Peripheral<P = impl Pin> + 'd,
+        config: Config,
+    ) -> Self {
+        Self {
+            sck: Output::new(sck, Self::idle_level(&config)),
+            mosi: None,
+            miso: Some(Input::new(miso, Pull::None)),
+            config,
+        }
+    }
+
+    /// Change the bus configuration. SCK is moved to the new idle level.
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+        self.sck.set_level(Self::idle_level(&config));
+    }
+
+    fn idle_level(config: &Config) -> Level {
+        match config.mode.polarity {
+            Polarity::IdleLow => Level::Low,
+            Polarity::IdleHigh => Level::High,
+        }
+    }
+
+    #[inline]
+    fn delay(&self) {
+        if self.config.half_period_us > 0 {
+            crate::blocking_delay_us(self.config.half_period_us);
+        }
+    }
+
+    #[inline]
+    fn write_bit(&mut self, bit: bool) {
+        if let Some(mosi) = self.mosi.as_mut() {
+            mosi.set_level(bit.into());
+        }
+    }
+
+    #[inline]
+    fn read_bit(&self) -> bool {
+        self.miso.as_ref().is_some_and(|miso| miso.is_high())
+    }
+
+    /// Shift one byte out and one byte in.
+    fn transfer_byte(&mut self, out: u8) -> u8 {
+        let idle = Self::idle_level(&self.config);
+        let mut input = 0u8;
+
+        for i in 0..8 {
+            let shift = match self.config.bit_order {
+                BitOrder::MsbFirst => 7 - i,
+                BitOrder::LsbFirst => i,
+            };
+            let out_bit = (out >> shift) & 1 != 0;
+
+            let in_bit = match self.config.mode.phase {
+                // Data valid before the leading edge, sampled on it.
+                Phase::CaptureOnFirstTransition => {
+                    self.write_bit(out_bit);
+                    self.delay();
+                    self.sck.set_level(!idle);
+                    let bit = self.read_bit();
+                    self.delay();
+                    self.sck.set_level(idle);
+                    bit
+                }
+                // Data changes on the leading edge, sampled on the trailing edge.
+                Phase::CaptureOnSecondTransition => {
+                    self.sck.set_level(!idle);
+                    self.write_bit(out_bit);
+                    self.delay();
+                    self.sck.set_level(idle);
+                    let bit = self.read_bit();
+                    self.delay();
+                    bit
+                }
+            };
+
+            input |= (in_bit as u8) << shift;
+        }
+
+        input
+    }
+}
+
+impl<'d> embedded_hal_1::spi::ErrorType for SpiBus<'d> {
+    type Error = Infallible;
+}
+
+impl<'d> embedded_hal_1::spi::SpiBus<u8> for SpiBus<'d> {
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words.iter_mut() {
+            *word = self.transfer_byte(0x00);
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for &word in words {
+            self.transfer_byte(word);
+        }
+        Ok(())
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+        let len = read.len().max(write.len());
+        for i in 0..len {
+            let received = self.transfer_byte(write.get(i).copied().unwrap_or(0x00));
+            if let Some(word) = read.get_mut(i) {
+                *word = received;
+            }
+        }
+        Ok(())
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        for word in words.iter_mut() {
+            *word = self.transfer_byte(*word);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Debounced push button.
+//!
+//! [`Button`] wraps an interrupt-driven [`Input`] and filters contact bounce in
+//! software: after an edge the level must stay put for the debounce window
+//! before a press or release is reported. Edges inside the window are ignored.
+//!
+//! ```rust,ignore
+//! use sifli_hal::gpio::{button::Button, Level};
+//!
+//! // Button to GND, internal pull-up, 20 ms debounce.
+//! let mut button = Button::new(p.PA34, Level::Low, 20);
+//! loop {
+//!     button.wait_press().await;
+//!     info!("pressed");
+//!     button.wait_release().await;
+//! }
+//! ```
+
+use embassy_time::{Duration, Timer};
+
+use super::{Input, Level, Pin, Pull};
+use crate::Peripheral;
+
+/// Push button with software debounce.
+pub struct Button<'d> {
+    input: Input<'d>,
+    active: Level,
+    debounce: Duration,
+}
+
+impl<'d> Button<'d> {
+    /// Create a button on `pin` that reads `active` while pressed.
+    ///
+    /// The pull is chosen to hold the pin at the released level: pull-up for
+    /// an active-low button (switch to GND), pull-down for active-high.
+    pub fn new(pin: impl Peripheral<P = impl Pin> + 'd, active: Level, debounce_ms: u32) -> Self {
+        let pull = match active {
+            Level::Low => Pull::Up,
+            Level::High => Pull::Down,
+        };
+        Self {
+            input: Input::new(pin, pull),
+            active,
+            debounce: Duration::from_millis(debounce_ms as u64),
+        }
+    }
+
+    /// Whether the button currently reads as pressed (not debounced).
+    #[inline]
+    pub fn is_pressed(&self) -> bool {
+        self.input.get_level() == self.active
+    }
+
+    /// Wait for a debounced press.
+    ///
+    /// If the button is already held, this first waits for it to be released,
+    /// so every call reports a new press.
+    pub async fn wait_press(&mut self) {
+        self.wait_stable(!self.active).await;
+        self.wait_stable(self.active).await;
+    }
+
+    /// Wait for a debounced release.
+    ///
+    /// If the button is already released, this first waits for a press.
+    pub async fn wait_release(&mut self) {
+        self.wait_stable(self.active).await;
+        self.wait_stable(!self.active).await;
+    }
+
+    /// Wait until the pin has read `level` for the whole debounce window.
+    async fn wait_stable(&mut self, level: Level) {
+        loop {
+            match level {
+                Level::High => self.input.wait_for_high().await,
+                Level::Low => self.input.wait_for_low().await,
+            }
+            Timer::after(self.debounce).await;
+            if self.input.get_level() == level {
+                return;
+            }
+        }
+    }
+}
@@ -402,6 +402,16 @@ impl HpsysPin {
         }
     }
 
+    /// Current function select value of the pad.
+    pub fn fsel(&self) -> u8 {
+        match self.pin {
+            0..=38 => self.pinmux().pad_pa0_38(self.pin as _).read().fsel(),
+            39..=42 => self.pinmux().pad_pa39_42((self.pin - 39) as _).read().fsel(),
+            43..=44 => self.pinmux().pad_pa43_44((self.pin - 43) as _).read().fsel(),
+            _ => unreachable!(),
+        }
+    }
+
     pub fn set_ie(&mut self, ie: bool) {
         match self.pin {
             0..=38 => {
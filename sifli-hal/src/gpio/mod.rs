@@ -17,6 +17,9 @@ use crate::{interrupt, peripherals, Peripheral};
 #[cfg(feature = "sf32lb52x")]
 pub(crate) const PA_PIN_COUNT: usize = 44;
 
+pub mod bitbang;
+#[cfg(feature = "time")]
+pub mod button;
 pub mod hpsys;
 
 static PA_WAKERS: [AtomicWaker; PA_PIN_COUNT] = [const { AtomicWaker::new() }; PA_PIN_COUNT];
@@ -688,6 +691,66 @@ impl<'d> Drop for Flex<'d> {
     }
 }
 
+/// Pad function select (FSEL) value, as listed in the pinmux table of the
+/// datasheet (e.g. `Func(4)` is the I2C/UART function on most PA pins).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Func(pub u8);
+
+impl Func {
+    /// Plain GPIO.
+    pub const GPIO: Func = Func(0);
+    /// Analog (ADC) input; digital input and pulls should be disabled.
+    pub const ANALOG: Func = Func(0b1111);
+}
+
+/// The requested function is not available on the pin.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidFunction {
+    pub pin: u8,
+    pub func: Func,
+}
+
+/// Whether `func` is a valid selection for `pin`.
+pub fn is_valid_function(pin: &impl Pin, func: Func) -> bool {
+    let n = pin.pin();
+    if func == Func::ANALOG {
+        return n <= 38;
+    }
+    let masks = &crate::_generated::PIN_FSEL_MASKS;
+    func.0 < 16 && masks.get(n as usize).is_some_and(|m| m & (1 << func.0) != 0)
+}
+
+/// Select the pad function of `pin`.
+///
+/// Pull, drive strength and input enable are left unchanged. Drivers set the
+/// function of the pins they are given themselves; this is for pins used by
+/// code outside the HAL, or analog inputs:
+///
+/// ```rust,ignore
+/// use sifli_hal::gpio::{self, Func};
+///
+/// gpio::set_function(&mut p.PA28, Func::ANALOG)?;
+/// ```
+pub fn set_function(pin: &mut impl Pin, func: Func) -> Result<(), InvalidFunction> {
+    if !is_valid_function(pin, func) {
+        return Err(InvalidFunction {
+            pin: pin.pin(),
+            func,
+        });
+    }
+    let mut hp = HpsysPin::new(pin.pin_bank());
+    // SAFETY: checked against the pinmux table above.
+    unsafe { hp.set_fsel_unchecked(func.0) };
+    Ok(())
+}
+
+/// Read back the pad function currently selected for `pin`.
+pub fn function(pin: &impl Pin) -> Func {
+    Func(HpsysPin::new(pin.pin_bank()).fsel())
+}
+
 pub struct AfType {
     pub(crate) pull: Pull,
 }
@@ -989,6 +1052,11 @@ impl<'d> embedded_hal_1::digital::StatefulOutputPin for Output<'d> {
     fn is_set_low(&mut self) -> Result<bool, Self::Error> {
         Ok((*self).is_set_low())
     }
+
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        (*self).toggle();
+        Ok(())
+    }
 }
 
 impl<'d> embedded_hal_1::digital::ErrorType for OutputOpenDrain<'d> {
@@ -1015,6 +1083,11 @@ impl<'d> embedded_hal_1::digital::StatefulOutputPin for OutputOpenDrain<'d> {
     fn is_set_low(&mut self) -> Result<bool, Self::Error> {
         Ok((*self).is_set_low())
     }
+
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        (*self).toggle();
+        Ok(())
+    }
 }
 
 impl<'d> embedded_hal_1::digital::InputPin for OutputOpenDrain<'d> {
@@ -1061,6 +1134,29 @@ impl<'d> embedded_hal_1::digital::StatefulOutputPin for Flex<'d> {
     fn is_set_low(&mut self) -> Result<bool, Self::Error> {
         Ok((*self).is_set_low())
     }
+
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        (*self).toggle();
+        Ok(())
+    }
+}
+
+// Generic drivers (display reset lines, bit-banged buses, ...) take pins by
+// these traits; fail the build if a pin type loses one.
+#[allow(dead_code)]
+fn assert_embedded_hal_impls() {
+    use embedded_hal_1::digital::{InputPin, OutputPin, StatefulOutputPin};
+    use embedded_hal_async::digital::Wait;
+
+    fn input<P: InputPin + Wait>() {}
+    fn output<P: OutputPin + StatefulOutputPin>() {}
+
+    input::<Input<'static>>();
+    input::<Flex<'static>>();
+    input::<OutputOpenDrain<'static>>();
+    output::<Output<'static>>();
+    output::<OutputOpenDrain<'static>>();
+    output::<Flex<'static>>();
 }
 
 impl<'d> embedded_hal_async::digital::Wait for Flex<'d> {
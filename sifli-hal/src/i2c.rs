@@ -40,6 +40,7 @@ use embassy_time::{Duration, Instant};
 use crate::gpio::{AfType, Pull, SealedPin};
 use crate::interrupt::typelevel::Interrupt as _;
 use crate::mode::{Async, Blocking, Mode};
+use crate::ownership::Claim;
 use crate::pac::i2c::I2c as Regs;
 use crate::time::Hertz;
 use crate::{interrupt, rcc, Peripheral};
@@ -130,6 +131,7 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
 /// I2C driver
 pub struct I2c<'d, T: Instance, M: Mode> {
     _peri: PeripheralRef<'d, T>,
+    _claim: Claim,
     _phantom: PhantomData<M>,
 }
 
@@ -166,6 +168,7 @@ impl<'d, T: Instance> I2c<'d, T, Blocking> {
 
         Self {
             _peri: peri,
+            _claim: Claim::new::<T>(),
             _phantom: PhantomData,
         }
     }
@@ -208,6 +211,7 @@ impl<'d, T: Instance> I2c<'d, T, Async> {
 
         Self {
             _peri: peri,
+            _claim: Claim::new::<T>(),
             _phantom: PhantomData,
         }
     }
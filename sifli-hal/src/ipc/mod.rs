@@ -312,61 +312,87 @@ impl<'d> Ipc<'d> {
 
     /// Open an IPC Queue (minimal subset of SDK init+open).
     pub fn open_queue(&mut self, cfg: QueueConfig) -> Result<IpcQueue, Error> {
-        if cfg.qid as usize >= HW_QUEUE_NUM {
-            return Err(Error::InvalidQid);
-        }
-        if cfg.tx_buf_size <= mem::size_of::<CircularBuf>() {
-            return Err(Error::BufferTooSmall);
-        }
+        open_queue(cfg)
+    }
+}
+
+/// Open a queue without an [`Ipc`] handle (MAILBOX1 must already be enabled).
+pub(crate) fn open_queue(cfg: QueueConfig) -> Result<IpcQueue, Error> {
+    if cfg.qid as usize >= HW_QUEUE_NUM {
+        return Err(Error::InvalidQid);
+    }
+    if cfg.tx_buf_size <= mem::size_of::<CircularBuf>() {
+        return Err(Error::BufferTooSmall);
+    }
 
-        let st = &QUEUES[cfg.qid as usize];
+    let st = &QUEUES[cfg.qid as usize];
 
-        critical_section::with(|_| {
-            if st.active.swap(true, Ordering::AcqRel) {
-                return Err(Error::AlreadyOpen);
-            }
+    critical_section::with(|_| {
+        if st.active.swap(true, Ordering::AcqRel) {
+            return Err(Error::AlreadyOpen);
+        }
 
-            st.rx_buf.store(cfg.rx_buf_addr, Ordering::Release);
-            st.tx_buf.store(cfg.tx_buf_addr, Ordering::Release);
-
-            // Sender initializes TX ring buffer and remaps rd_buffer_ptr to peer-visible alias.
-            if cfg.tx_buf_addr != 0 {
-                unsafe {
-                    let cb = cfg.tx_buf_addr as *mut CircularBuf;
-                    let pool_wr = (cfg.tx_buf_addr as *mut u8).add(mem::size_of::<CircularBuf>());
-                    cb.wr_init(
-                        pool_wr,
-                        (cfg.tx_buf_size - mem::size_of::<CircularBuf>()) as i16,
-                    );
-
-                    let pool_rd =
-                        (cfg.tx_buf_addr_alias as *mut u8).add(mem::size_of::<CircularBuf>());
-                    cb.rd_init(pool_rd);
-                }
+        st.rx_buf.store(cfg.rx_buf_addr, Ordering::Release);
+        st.tx_buf.store(cfg.tx_buf_addr, Ordering::Release);
+
+        // Sender initializes TX ring buffer and remaps rd_buffer_ptr to peer-visible alias.
+        if cfg.tx_buf_addr != 0 {
+            unsafe {
+                let cb = cfg.tx_buf_addr as *mut CircularBuf;
+                let pool_wr = (cfg.tx_buf_addr as *mut u8).add(mem::size_of::<CircularBuf>());
+                cb.wr_init(
+                    pool_wr,
+                    (cfg.tx_buf_size - mem::size_of::<CircularBuf>()) as i16,
+                );
+
+                let pool_rd =
+                    (cfg.tx_buf_addr_alias as *mut u8).add(mem::size_of::<CircularBuf>());
+                cb.rd_init(pool_rd);
             }
+        }
 
-            // SDK behavior: don't read RX ring buffer on open (peer may not be initialized yet).
-            st.rx_len.store(0, Ordering::Release);
-
-            // Unmask: per SDK semantics, unmask qid on TX mailbox; also unmask RX side to reduce missed interrupts.
-            let qid_mask = 1u16 << cfg.qid;
-            // Directly operate MAILBOX1 registers
-            crate::pac::MAILBOX1
-                .ier(0)
-                .modify(|w| w.0 |= qid_mask as u32);
-            {
-                let mb2 = crate::pac::MAILBOX2;
-                mb2.ier(0).modify(|w| w.0 |= qid_mask as u32);
-                mb2.icr(0)
-                    .write_value(crate::pac::mailbox::regs::Ixr(qid_mask as u32));
-            }
+        // SDK behavior: don't read RX ring buffer on open (peer may not be initialized yet).
+        st.rx_len.store(0, Ordering::Release);
 
-            Ok(IpcQueue {
-                rx: IpcQueueRx { qid: cfg.qid },
-                tx: IpcQueueTx { qid: cfg.qid },
-            })
+        // Unmask: per SDK semantics, unmask qid on TX mailbox; also unmask RX side to reduce missed interrupts.
+        let qid_mask = 1u16 << cfg.qid;
+        // Directly operate MAILBOX1 registers
+        crate::pac::MAILBOX1
+            .ier(0)
+            .modify(|w| w.0 |= qid_mask as u32);
+        {
+            let mb2 = crate::pac::MAILBOX2;
+            mb2.ier(0).modify(|w| w.0 |= qid_mask as u32);
+            mb2.icr(0)
+                .write_value(crate::pac::mailbox::regs::Ixr(qid_mask as u32));
+        }
+
+        Ok(IpcQueue {
+            rx: IpcQueueRx { qid: cfg.qid },
+            tx: IpcQueueTx { qid: cfg.qid },
         })
-    }
+    })
+}
+
+/// Close a queue: mask its mailbox interrupts and fail pending and future
+/// reads/writes with [`Error::NotOpen`].
+///
+/// Used when the peer is halted, so no task keeps waiting on a dead LCPU.
+/// Existing handles stay valid and work again once the queue is reopened.
+pub(crate) fn close_queue(qid: u8) {
+    let st = &QUEUES[qid as usize];
+    critical_section::with(|_| {
+        st.active.store(false, Ordering::Release);
+        st.rx_len.store(0, Ordering::Release);
+
+        let qid_mask = 1u32 << qid;
+        crate::pac::MAILBOX1.ier(0).modify(|w| w.0 &= !qid_mask);
+        crate::pac::MAILBOX2.ier(0).modify(|w| w.0 &= !qid_mask);
+        crate::pac::MAILBOX2
+            .icr(0)
+            .write_value(crate::pac::mailbox::regs::Ixr(qid_mask));
+    });
+    st.rx_waker.wake();
 }
 
 /// Opened IPC Queue handle.
@@ -511,19 +537,20 @@ impl IpcQueueRx {
 
         poll_fn(|cx| {
             if st.rx_len.load(Ordering::Acquire) > 0 {
-                return Poll::Ready(());
+                return Poll::Ready(Ok(()));
             }
             st.rx_waker.register(cx.waker());
             fence(Ordering::SeqCst);
-            if st.rx_len.load(Ordering::Acquire) > 0 {
-                Poll::Ready(())
+            if !st.active.load(Ordering::Acquire) {
+                // Closed while waiting (see `close_queue`).
+                Poll::Ready(Err(Error::NotOpen))
+            } else if st.rx_len.load(Ordering::Acquire) > 0 {
+                Poll::Ready(Ok(()))
             } else {
                 Poll::Pending
             }
         })
-        .await;
-
-        Ok(())
+        .await
     }
 
     /// Async read (reads at least 1 byte unless an error occurs).
@@ -1,22 +1,28 @@
+use core::cell::Cell;
 use core::future::poll_fn;
 use core::marker::PhantomData;
-use core::sync::atomic::{compiler_fence, Ordering};
+use core::sync::atomic::{compiler_fence, fence, AtomicBool, Ordering};
 use core::task::Poll;
 
 use display_driver::bus::ErrorType;
 use display_driver::{DisplayBus, DisplayError};
 
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::{into_ref, Peripheral};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::blocking_mutex::Mutex;
 use embassy_sync::waitqueue::AtomicWaker;
-use embassy_time::{Duration, Timer};
+use embassy_time::{with_timeout, Duration, Instant, Timer};
 
+use crate::dma::{Channel as DmaChannel, Transfer, TransferOptions};
 use crate::gpio::{AfType, Pull};
 use crate::interrupt::typelevel::{Binding, Interrupt};
+use crate::ownership::Claim;
 use crate::pac::lcdc::vals;
 use crate::rcc::enable_and_reset;
 use crate::time::Hertz;
 use crate::to_system_bus_addr;
-use crate::utils::blocking_wait_timeout_ms;
+use crate::utils::blocking_wait_timeout;
 use crate::{interrupt, peripherals};
 
 pub use vals::LcdIntfSel as LcdInterfaceSelector;
@@ -25,8 +31,20 @@ pub use vals::{
     SpiClkInit as SpiClkPhase, SpiClkPol, SpiLcdFormat, SpiLineMode, SpiRdMode, TargetLcd,
 };
 
+#[cfg(feature = "embedded-graphics")]
+mod display;
+#[cfg(feature = "embedded-graphics")]
+pub use display::LcdcDisplay;
+
+#[cfg(feature = "lcdc-presets")]
+pub mod presets;
+
 static WAKER: AtomicWaker = AtomicWaker::new();
 
+/// Frame queued by [`Lcdc::submit`], started from the EOF interrupt.
+static QUEUE: Mutex<CriticalSectionRawMutex, Cell<Option<Layer0Setup>>> =
+    Mutex::new(Cell::new(None));
+
 // ============================================================================
 // Interface Abstraction (Sealed Trait Pattern)
 // ============================================================================
@@ -190,6 +208,70 @@ impl InputColorFormat {
             InputColorFormat::L8 => 1,
         }
     }
+
+    /// Required alignment (in bytes) of the layer source address for this format.
+    pub fn alignment(&self) -> usize {
+        match self {
+            InputColorFormat::Rgb565 => 2,
+            InputColorFormat::Argb8888 => 4,
+            _ => 1,
+        }
+    }
+}
+
+/// 256-entry RGB565 colour lookup table for 8-bit indexed framebuffers.
+///
+/// The SF32LB52x LCDC layer has no L8/CLUT mode, and the DMAC cannot do
+/// table lookups, so indexed pixels are expanded by the CPU into an RGB565
+/// buffer before the transfer (see [`Lcdc::send_indexed_pixel_data`]).
+/// Keeping the UI in an indexed buffer halves its RAM compared to RGB565
+/// (240x240: 57.6 KiB instead of 115.2 KiB), at the cost of one table lookup
+/// per pixel on every update and a scratch buffer the size of the updated
+/// area. Changing the palette itself is free: it is only read during
+/// expansion, so there is nothing to upload to the hardware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette(pub [u16; 256]);
+
+impl Palette {
+    /// Linear grayscale ramp (index 0 = black, 255 = white).
+    pub const fn grayscale() -> Self {
+        let mut lut = [0u16; 256];
+        let mut i = 0;
+        while i < 256 {
+            lut[i] = rgb565(i as u8, i as u8, i as u8);
+            i += 1;
+        }
+        Self(lut)
+    }
+
+    /// Build a palette from 8-bit-per-channel RGB entries.
+    pub const fn from_rgb888(colors: &[[u8; 3]; 256]) -> Self {
+        let mut lut = [0u16; 256];
+        let mut i = 0;
+        while i < 256 {
+            lut[i] = rgb565(colors[i][0], colors[i][1], colors[i][2]);
+            i += 1;
+        }
+        Self(lut)
+    }
+
+    /// Expand `indices` into little-endian RGB565 pixels in `out`.
+    ///
+    /// `out` must be at least `2 * indices.len()` bytes; extra bytes are
+    /// left untouched.
+    pub fn expand(&self, indices: &[u8], out: &mut [u8]) {
+        assert!(
+            out.len() >= indices.len() * 2,
+            "lcdc: expand output too small"
+        );
+        for (&index, px) in indices.iter().zip(out.chunks_exact_mut(2)) {
+            px.copy_from_slice(&self.0[index as usize].to_le_bytes());
+        }
+    }
+}
+
+const fn rgb565(r: u8, g: u8, b: u8) -> u16 {
+    ((r as u16 >> 3) << 11) | ((g as u16 >> 2) << 5) | (b as u16 >> 3)
 }
 
 // ============================================================================
@@ -221,8 +303,17 @@ pub struct SpiConfig {
     pub write_frequency: FrequencyConfig,
     /// SPI read frequency
     pub read_frequency: FrequencyConfig,
+    /// Idle clock cycles between the command and the data phase, up to
+    /// [`MAX_DUMMY_CYCLES`]. Used by [`Lcdc::send_cmd`]; panels that need a
+    /// different count for some commands (typically reads) can override it
+    /// per command. A wrong value shifts the data by that many bits, which
+    /// shows up as a garbled first byte.
+    pub dummy_cycles: u8,
 }
 
+/// Largest value of the 2-bit `SPI_IF_CONF.DUMMY_CYCLE` field.
+pub const MAX_DUMMY_CYCLES: u8 = 3;
+
 impl Default for SpiConfig {
     fn default() -> Self {
         Self {
@@ -233,6 +324,7 @@ impl Default for SpiConfig {
             vsyn_polarity: Polarity::ActiveLow,
             write_frequency: FrequencyConfig::Freq(Hertz::mhz(10)), // 10 MHz
             read_frequency: FrequencyConfig::Freq(Hertz::mhz(2)),   // 2 MHz
+            dummy_cycles: 0,
         }
     }
 }
@@ -247,6 +339,18 @@ pub struct Config<I: LcdInterface> {
     pub out_color_format: OutputColorFormat,
     /// Input layer format (format of the framebuffer in memory)
     pub in_color_format: InputColorFormat,
+    /// Reverse the byte order of each pixel on its way to the panel
+    /// (`LCD_CONF.ENDIAN_RV`).
+    ///
+    /// Layers are always read as little-endian pixels, whatever the
+    /// `in_color_format`; the swap is applied after format conversion, to
+    /// the output pixel. With `Rgb565` output it exchanges the two bytes of
+    /// each pixel, so a native little-endian framebuffer (e.g.
+    /// `Framebuffer<Rgb565, RawU16, LittleEndian, ..>`) can drive a panel
+    /// that expects the high byte first without swapping in software. With
+    /// `Rgb888` output the three bytes are reversed, which swaps red and
+    /// blue. `Rgb332` output has one byte per pixel and is unaffected.
+    pub byte_swap: bool,
     /// LCD reset interval in microseconds
     pub reset_lcd_interval_us: u32,
 
@@ -260,6 +364,21 @@ pub struct Config<I: LcdInterface> {
     /// you can disable this option.
     pub dcache_clean: bool,
 
+    /// How many times a pixel transfer is re-armed after a DPI underrun before
+    /// [`Error::DpiUnderflow`] is returned. `0` disables recovery.
+    ///
+    /// Layer overflow is never retried, since it indicates a bus configuration problem.
+    pub max_underrun_retries: u8,
+
+    /// Timeout for single (command/parameter) register accesses.
+    pub single_timeout: Duration,
+    /// Timeout for a pixel transfer to finish (End-Of-Frame), and for the
+    /// interface to go idle before the next one is programmed.
+    ///
+    /// A full frame takes roughly `width * height * bits_per_pixel /
+    /// (lanes * spi_clock)`; raise this for large panels at low clocks.
+    pub frame_timeout: Duration,
+
     /// Interface specific settings (e.g., SpiConfig)
     pub interface_config: I::Config,
 }
@@ -271,9 +390,13 @@ impl Default for Config<Spi> {
             height: 240,
             out_color_format: OutputColorFormat::Rgb565,
             in_color_format: InputColorFormat::Rgb565,
+            byte_swap: false,
             reset_lcd_interval_us: 20,
             interface_config: SpiConfig::default(),
             dcache_clean: true,
+            max_underrun_retries: 0,
+            single_timeout: Duration::from_millis(100),
+            frame_timeout: Duration::from_secs(1),
         }
     }
 }
@@ -307,24 +430,72 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
 
         // Check for End of Frame (EOF)
         if irq_status.eof_raw_stat() {
-            // Mask EOF Interrupt (Disable it)
-            // This acts as the "signal" that the transfer is complete.
-            regs.setting().modify(|w| w.set_eof_mask(false));
+            let failed = irq_status.dpi_udr_raw_stat() || irq_status.icb_of_raw_stat();
+            let next = if failed {
+                None
+            } else {
+                QUEUE.lock(|q| q.take())
+            };
+            match next {
+                // Chain the frame queued by `Lcdc::submit`; EOF stays unmasked.
+                Some(setup) => {
+                    write_layer0(regs, &setup);
+                    regs.irq().write(|w| w.set_eof_stat(true));
+                    compiler_fence(Ordering::SeqCst);
+                    regs.command().write(|w| w.set_start(true));
+                }
+                // Mask EOF Interrupt (Disable it)
+                // This acts as the "signal" that the transfer is complete.
+                None => regs.setting().modify(|w| w.set_eof_mask(false)),
+            }
         }
 
         WAKER.wake();
     }
 }
 
+/// One step of a panel init sequence, see [`Lcdc::send_sequence`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LcdOp {
+    /// Send a one-byte command followed by its parameters (may be empty).
+    Cmd(u8, &'static [u8]),
+    /// Wait before the next step, e.g. after `SLPOUT` or a software reset.
+    DelayMs(u32),
+}
+
+/// QSPI panel opcode for a register write with single-line parameters.
+///
+/// See [`Lcdc::qspi_write_cmd`].
+pub const QSPI_OP_WRITE_CMD: u8 = 0x02;
+
+/// QSPI panel opcode for a memory write with quad-line pixel data.
+///
+/// See [`Lcdc::qspi_write_pixels`].
+pub const QSPI_OP_WRITE_PIXELS: u8 = 0x32;
+
 /// LCDC Driver implementation for SF32LB52x
 pub struct Lcdc<'d, T: Instance, I: LcdInterface> {
     _peri: crate::PeripheralRef<'d, T>,
     config: Config<I>,
+    underrun_retries: u32,
+    /// Ping-pong framebuffers registered with [`Lcdc::set_framebuffers`].
+    framebuffers: Option<[&'d mut [u8]; 2]>,
+    /// Index of the framebuffer last handed to the hardware.
+    front: usize,
+    /// A [`Lcdc::present`] or [`Lcdc::submit`] transfer has been started and
+    /// not yet awaited.
+    in_flight: bool,
+    _claim: Claim,
     _phantom: PhantomData<I>,
 }
 
 impl<'d, T: Instance> Lcdc<'d, T, Spi> {
     /// Create a new LCDC QSPI driver instance
+    ///
+    /// # Panics
+    ///
+    /// Panics if another `Lcdc` driver for this instance exists (e.g. one
+    /// built from a stolen peripheral).
     pub fn new_qspi(
         peri: impl Peripheral<P = T> + 'd,
         _irq: impl Binding<T::Interrupt, InterruptHandler<T>>,
@@ -338,6 +509,9 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         config: Config<Spi>,
     ) -> Self {
         into_ref!(peri);
+        if T::taken().swap(true, Ordering::SeqCst) {
+            panic!("Lcdc: already taken");
+        }
         init_pin!(spi_te, AfType::new(Pull::None));
         init_pin!(spi_cs, AfType::new(Pull::None));
         init_pin!(spi_clk, AfType::new(Pull::None));
@@ -359,6 +533,11 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         let mut slf = Self {
             _peri: peri,
             config,
+            underrun_retries: 0,
+            framebuffers: None,
+            front: 0,
+            in_flight: false,
+            _claim: Claim::new::<T>(),
             _phantom: PhantomData,
         };
         slf.init();
@@ -385,21 +564,22 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
     }
 
     pub fn set_spi_frequency(&mut self, freq: FrequencyConfig) {
-        // Calculate clock divider
-        // Formula: clk_div = (src_clk + freq - 1) / freq
         // Hardware requirement: divider >= 2
         let regs = T::regs();
         let clk_div = match freq {
             FrequencyConfig::Freq(hz) => {
-                if hz.0 > 0 {
-                    T::get_freq().unwrap().0.div_ceil(hz.0)
-                } else {
-                    2
-                }
+                let src = unwrap!(crate::rcc::peripheral_clock::<T>(), "lcdc: clock disabled");
+                let (div, _) = crate::rcc::closest_divider(src, hz, u8::MAX as u32);
+                let div = div.max(2);
+                debug!(
+                    "lcdc: spi clk requested {} Hz, actual {} Hz",
+                    hz.0,
+                    src.0 / div
+                );
+                div as u8
             }
-            FrequencyConfig::Div(div) => div as u32,
-        }
-        .max(2) as u8;
+            FrequencyConfig::Div(div) => div.max(2),
+        };
         regs.spi_if_conf().modify(|w| w.set_clk_div(clk_div));
     }
 
@@ -424,10 +604,15 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
             w.set_lcd_format(lcd_format);
 
             w.set_spi_lcd_format(spi_lcd_format);
+            w.set_endian_rv(self.config.byte_swap);
         });
 
         // Configure SPI Interface (SPI_IF_CONF)
         let spi_conf = &self.config.interface_config;
+        assert!(
+            spi_conf.dummy_cycles <= MAX_DUMMY_CYCLES,
+            "lcdc: dummy_cycles exceeds the DUMMY_CYCLE field"
+        );
         regs.spi_if_conf().modify(|w| {
             w.set_line(spi_conf.line_mode);
             w.set_spi_cs_pol(spi_conf.cs_polarity);
@@ -435,7 +620,7 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
             w.set_spi_clk_init(spi_conf.clk_phase);
             w.set_spi_clk_auto_dis(true); // Disable CLK when idle
             w.set_spi_cs_no_idle(true); // Keep CS active during transaction
-            w.set_dummy_cycle(0);
+            w.set_dummy_cycle(spi_conf.dummy_cycles);
         });
         self.set_spi_frequency(spi_conf.write_frequency);
 
@@ -446,11 +631,61 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         regs.lcd_if_conf().modify(|w| w.set_lcd_rstb(true));
     }
 
+    /// Whether the interface is sending something: a frame started by
+    /// [`present`](Self::present) or [`submit`](Self::submit) (including a
+    /// queued one), or a single command/parameter access.
+    ///
+    /// While this returns `true`, [`send_cmd`](Self::send_cmd) and
+    /// [`send_cmd_data`](Self::send_cmd_data) may fail with [`Error::Busy`].
+    pub fn is_busy(&self) -> bool {
+        let regs = T::regs();
+        self.frame_in_flight()
+            || regs.status().read().lcd_busy()
+            || regs.lcd_single().read().lcd_busy()
+    }
+
+    /// A `present`/`submit` frame is being sent or queued.
+    ///
+    /// The EOF interrupt only chains a queued frame while EOF is unmasked, so
+    /// once this reads `false` no frame starts without a call through `&mut self`.
+    fn frame_in_flight(&self) -> bool {
+        self.in_flight && T::regs().setting().read().eof_mask()
+    }
+
     /// Send a command to the LCD via SPI.
+    ///
+    /// Commands never interleave with pixel data. A single access written
+    /// while a [`present`](Self::present)ed or [`submit`](Self::submit)ted
+    /// frame is still being sent would be spliced into its pixel stream, so
+    /// this returns [`Error::Busy`] instead; check [`is_busy`](Self::is_busy)
+    /// or await [`wait_present`](Self::wait_present) to send between frames.
+    /// The async [`DisplayBus`] methods wait for the frame themselves.
+    ///
+    /// Uses [`SpiConfig::dummy_cycles`]; see
+    /// [`send_cmd_with_dummy`](Self::send_cmd_with_dummy) to override it.
     pub fn send_cmd(&mut self, cmd: u32, len_bytes: u8, continuous: bool) -> Result<(), Error> {
-        if len_bytes == 0 || len_bytes > 4 {
+        let dummy_cycles = self.config.interface_config.dummy_cycles;
+        self.send_cmd_with_dummy(cmd, len_bytes, continuous, dummy_cycles)
+    }
+
+    /// [`send_cmd`](Self::send_cmd) with `dummy_cycles` idle clocks before the
+    /// data phase instead of the configured default.
+    ///
+    /// Fails with [`Error::InvalidParameter`] if `dummy_cycles` exceeds
+    /// [`MAX_DUMMY_CYCLES`].
+    pub fn send_cmd_with_dummy(
+        &mut self,
+        cmd: u32,
+        len_bytes: u8,
+        continuous: bool,
+        dummy_cycles: u8,
+    ) -> Result<(), Error> {
+        if len_bytes == 0 || len_bytes > 4 || dummy_cycles > MAX_DUMMY_CYCLES {
             return Err(Error::InvalidParameter);
         }
+        if self.frame_in_flight() {
+            return Err(Error::Busy);
+        }
 
         self.wait_busy()?;
 
@@ -460,6 +695,7 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
             // Set write mode to normal
             w.set_spi_rd_mode(SpiRdMode::Normal);
             w.set_spi_cs_auto_dis(!continuous);
+            w.set_dummy_cycle(dummy_cycles);
 
             let len_val = match len_bytes {
                 1 => SpiAccessLen::Bytes1, // SpiAccessLen::Bytes1 == 0
@@ -484,6 +720,9 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
     }
 
     /// Send data parameter to the LCD via SPI.
+    ///
+    /// Fails with [`Error::Busy`] while a frame is in flight, like
+    /// [`send_cmd`](Self::send_cmd).
     pub fn send_cmd_data(
         &mut self,
         data: u32,
@@ -493,6 +732,9 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         if len_bytes == 0 || len_bytes > 4 {
             return Err(Error::InvalidParameter);
         }
+        if self.frame_in_flight() {
+            return Err(Error::Busy);
+        }
 
         self.wait_single_busy()?;
 
@@ -521,12 +763,174 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         Ok(())
     }
 
+    /// Read `read_len` (1..=4) bytes after sending command `cmd`.
+    ///
+    /// The command goes out like [`send_cmd`](Self::send_cmd), followed by
+    /// `dummy_cycles` idle clocks and the read phase, all under one CS
+    /// assertion. The bus runs at [`SpiConfig::read_frequency`] for the
+    /// whole access and returns to the write frequency afterwards. Bytes are
+    /// returned in the order received, the first one in the most significant
+    /// position.
+    ///
+    /// ```rust,ignore
+    /// // RDDID on a ST7789: one dummy clock, then three ID bytes
+    /// let id = lcdc.read_cmd(0x04, 1, 3, 1)?;
+    /// ```
+    pub fn read_cmd(
+        &mut self,
+        cmd: u32,
+        cmd_len: u8,
+        read_len: u8,
+        dummy_cycles: u8,
+    ) -> Result<u32, Error> {
+        if read_len == 0 || read_len > 4 || dummy_cycles > MAX_DUMMY_CYCLES {
+            return Err(Error::InvalidParameter);
+        }
+        // Checked before the clock is retuned for the read, which would
+        // otherwise slow down a frame that is still being sent.
+        if self.frame_in_flight() {
+            return Err(Error::Busy);
+        }
+
+        let spi_conf = &self.config.interface_config;
+        let (read_freq, write_freq) = (spi_conf.read_frequency, spi_conf.write_frequency);
+        let default_dummy = spi_conf.dummy_cycles;
+
+        self.set_spi_frequency(read_freq);
+        let result = self.read_cmd_inner(cmd, cmd_len, read_len, dummy_cycles);
+        self.set_spi_frequency(write_freq);
+        T::regs()
+            .spi_if_conf()
+            .modify(|w| w.set_dummy_cycle(default_dummy));
+        result
+    }
+
+    fn read_cmd_inner(
+        &mut self,
+        cmd: u32,
+        cmd_len: u8,
+        read_len: u8,
+        dummy_cycles: u8,
+    ) -> Result<u32, Error> {
+        self.send_cmd_with_dummy(cmd, cmd_len, true, dummy_cycles)?;
+        self.wait_single_busy()?;
+
+        let regs = T::regs();
+        regs.spi_if_conf().modify(|w| {
+            w.set_spi_cs_auto_dis(true);
+            w.set_rd_len(match read_len {
+                1 => SpiAccessLen::Bytes1,
+                2 => SpiAccessLen::Bytes2,
+                3 => SpiAccessLen::Bytes3,
+                4 => SpiAccessLen::Bytes4,
+                _ => unreachable!(),
+            });
+        });
+        regs.lcd_single().write(|w| w.set_rd_trig(true));
+        self.wait_single_busy()?;
+
+        Ok(regs.lcd_rd().read().data())
+    }
+
+    /// Send `params` as data after a command, 4 bytes per single access.
+    ///
+    /// CS is held until the last chunk.
+    fn send_params(&mut self, params: &[u8]) -> Result<(), Error> {
+        params.chunks(4).enumerate().try_for_each(|(i, chunk)| {
+            let data_word = chunk
+                .iter()
+                .fold(0u32, |acc, &byte| (acc << 8) | (byte as u32));
+
+            let is_last = (i + 1) * 4 >= params.len();
+            self.send_cmd_data(data_word, chunk.len() as u8, !is_last)
+        })
+    }
+
+    /// Run a panel init table: each command with its parameters, and the
+    /// delays between them.
+    ///
+    /// Commands use the plain single-line framing (one command byte, then
+    /// parameters as data) of 3/4-wire SPI panels such as ST7789 and GC9A01;
+    /// QSPI panels need [`qspi_write_cmd`](Self::qspi_write_cmd) instead. A
+    /// presented or submitted frame is waited for first, so the table never
+    /// lands inside a pixel stream. With the `lcdc-presets` feature,
+    /// `lcdc::presets` has tables for common panels:
+    ///
+    /// ```rust,ignore
+    /// lcdc.reset_lcd().await;
+    /// lcdc.send_sequence(lcdc::presets::GC9A01).await?;
+    /// ```
+    pub async fn send_sequence(&mut self, ops: &[LcdOp]) -> Result<(), Error> {
+        self.wait_present().await?;
+        for op in ops {
+            match *op {
+                LcdOp::Cmd(cmd, params) => {
+                    self.send_cmd(cmd as u32, 1, !params.is_empty())?;
+                    self.send_params(params)?;
+                }
+                LcdOp::DelayMs(ms) => Timer::after(Duration::from_millis(ms as u64)).await,
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a register on a QSPI panel (CO5300, SH8601, ...).
+    ///
+    /// These panels use the QSPI flash framing: every access starts with a
+    /// 4-byte header `[opcode, 0x00, cmd, 0x00]` sent on one line, where the
+    /// opcode selects how the payload is transferred:
+    ///
+    /// | Opcode                | Payload                              |
+    /// |-----------------------|--------------------------------------|
+    /// | [`QSPI_OP_WRITE_CMD`] | register parameters, single line     |
+    /// | [`QSPI_OP_WRITE_PIXELS`] | pixel data, four lines (`RAMWR`)  |
+    ///
+    /// `data` may be empty for commands without parameters (e.g. `0x29`).
+    pub fn qspi_write_cmd(&mut self, cmd: u8, data: &[u8]) -> Result<(), Error> {
+        let header = u32::from_be_bytes([QSPI_OP_WRITE_CMD, 0x00, cmd, 0x00]);
+        self.send_cmd(header, 4, !data.is_empty())?;
+        self.send_params(data)
+    }
+
+    /// Write pixel data to a QSPI panel using the [`QSPI_OP_WRITE_PIXELS`]
+    /// header with `cmd` (normally `0x2C`, `RAMWR`).
+    ///
+    /// The column/row window must have been set with
+    /// [`qspi_write_cmd`](Self::qspi_write_cmd) (`0x2A`/`0x2B`). Buffer
+    /// requirements are those of [`send_pixel_data`](Self::send_pixel_data).
+    pub async fn qspi_write_pixels(
+        &mut self,
+        cmd: u8,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        buffer: &[u8],
+    ) -> Result<(), Error> {
+        // Validate before the command goes out, so the panel is not left
+        // expecting pixels that never come.
+        self.check_area(x0, y0, x1, y1, buffer.len())?;
+        let header = u32::from_be_bytes([QSPI_OP_WRITE_PIXELS, 0x00, cmd, 0x00]);
+        self.wait_present().await?;
+        self.send_cmd(header, 4, true)?;
+        self.send_pixel_data(x0, y0, x1, y1, buffer).await
+    }
+
     /// Send pixel data (framebuffer) asynchronously.
     ///
-    /// use #[repr(align(4))] to your buffer to ensure proper alignment.
+    /// The buffer must be aligned to [`InputColorFormat::alignment`] of the configured
+    /// input format, otherwise [`Error::InvalidParameter`] is returned. Using
+    /// `#[repr(align(4))]` on the buffer satisfies every format.
+    ///
+    /// `(x0, y0)..=(x1, y1)` must lie within the configured `width`/`height`
+    /// with `x0 <= x1` and `y0 <= y1` ([`Error::InvalidParameter`]), and the
+    /// buffer must hold exactly that area ([`Error::BufferSizeMismatch`]).
     ///
-    /// The signature is `async`, but the current implementation uses a polled wait (dead wait)
-    /// for the End-Of-Frame (EOF) flag to allow for fast verification without complex interrupt handling.
+    /// Completion is awaited on the EOF interrupt. The layer fetch is the
+    /// LCDC's own bus master, not a DMAC channel, so there is no
+    /// [`Transfer`] to await here; [`send_pixel_data_dma`](Self::send_pixel_data_dma)
+    /// awaits the DMAC copy into its staging buffer as a [`Transfer`] before
+    /// handing over to this method.
     pub async fn send_pixel_data(
         &mut self,
         x0: u16,
@@ -535,94 +939,513 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         y1: u16,
         buffer: &[u8],
     ) -> Result<(), Error> {
-        debug_assert!(
-            (buffer.as_ptr() as usize).is_multiple_of(4),
-            "Buffer address must be 4-byte aligned"
-        );
+        // A presented frame may still be scanning out of a registered framebuffer.
+        self.wait_present().await?;
+
+        self.configure_layer0(x0, y0, x1, y1, buffer.as_ptr() as usize, buffer.len())?;
+
+        let regs = T::regs();
+        // The layer fetches from `buffer` until EOF. If this future is dropped
+        // mid-frame, hold the borrow until the hardware is done with it.
+        let frame_timeout = self.config.frame_timeout;
+        let on_drop = OnDrop::new(move || {
+            let _ = blocking_wait_timeout(
+                || regs.status().read().lcd_busy() || regs.lcd_single().read().lcd_busy(),
+                frame_timeout,
+            );
+        });
+
+        let mut retries = 0;
+        let result = loop {
+            self.start_transfer();
+
+            match self.wait_for_transfer_completion().await {
+                Err(Error::DpiUnderflow) if retries < self.config.max_underrun_retries => {
+                    retries += 1;
+                    self.underrun_retries = self.underrun_retries.wrapping_add(1);
+                    warn!(
+                        "LCDC: DPI underrun, re-arming transfer ({}/{})",
+                        retries, self.config.max_underrun_retries
+                    );
+
+                    // Reset layer 0 so it refetches from the start of the buffer.
+                    regs.layer0_config().modify(|w| w.set_active(false));
+                    self.wait_busy()?;
+                    regs.layer0_config().modify(|w| w.set_active(true));
+                }
+                result => break result,
+            }
+        };
+        on_drop.defuse();
+        result
+    }
+
+    /// Configure overlay layer `index`, or disable it with `None`.
+    ///
+    /// The LCDC has [`LAYER_COUNT`] layers. Layer 0 carries the buffer of
+    /// each transfer ([`send_pixel_data`](Self::send_pixel_data),
+    /// [`submit`](Self::submit), [`present`](Self::present), ...) and is
+    /// always fully opaque; layers from 1 up are overlays configured here,
+    /// so `index` 0 is rejected with [`Error::InvalidParameter`].
+    ///
+    /// Blending order is bottom to top: canvas background, layer 0, layer 1.
+    /// Each overlay is blended over what lies below using the alpha chosen
+    /// by [`LayerConfig::alpha_sel`]: the constant [`LayerConfig::alpha`]
+    /// with [`AlphaSel::Layer`], otherwise the per-pixel alpha of an ARGB
+    /// format. Only the part of the overlay inside the canvas of a transfer
+    /// is fetched, so a partial update composites just that area.
+    ///
+    /// The overlay stays active for every following transfer until it is
+    /// reconfigured or disabled; its buffer stays borrowed for the driver's
+    /// lifetime and is cleaned from the D-cache here (if
+    /// [`Config::dcache_clean`] is set), not before every frame. Waits for
+    /// the current transfer to finish before touching the registers.
+    ///
+    /// ```rust,ignore
+    /// // Static background in layer 0, half-transparent sprite on top.
+    /// lcdc.configure_layer(1, Some(LayerConfig::new(sx, sy, 32, 32, &SPRITE)?))?;
+    /// lcdc.send_pixel_data(0, 0, 239, 239, &BACKGROUND).await?;
+    /// ```
+    pub fn configure_layer(
+        &mut self,
+        index: usize,
+        config: Option<LayerConfig<'d>>,
+    ) -> Result<(), Error> {
+        if index == 0 || index >= LAYER_COUNT {
+            error!("LCDC: no overlay layer {}", index);
+            return Err(Error::InvalidParameter);
+        }
+        let regs = T::regs();
+        let Some(c) = config else {
+            self.wait_busy()?;
+            regs.layer1_config().modify(|w| w.set_active(false));
+            return Ok(());
+        };
+
+        if c.x0 > c.x1 || c.y0 > c.y1 || c.x1 >= self.config.width || c.y1 >= self.config.height {
+            error!(
+                "LCDC: layer {} area ({}, {})..=({}, {}) outside {}x{} panel",
+                index, c.x0, c.y0, c.x1, c.y1, self.config.width, self.config.height
+            );
+            return Err(Error::InvalidParameter);
+        }
+        let width = c.x1 - c.x0 + 1;
+        let expected = width as usize * (c.y1 - c.y0 + 1) as usize * c.format.bpp() as usize;
+        if c.buffer.len() != expected {
+            error!(
+                "LCDC: layer {} buffer is {} bytes, area needs {}",
+                index,
+                c.buffer.len(),
+                expected
+            );
+            return Err(Error::BufferSizeMismatch);
+        }
+        let addr = c.buffer.as_ptr() as usize;
+        if !addr.is_multiple_of(c.format.alignment()) {
+            error!("LCDC: layer {} buffer 0x{:08X} misaligned", index, addr);
+            return Err(Error::InvalidParameter);
+        }
 
-        // Clean D-cache to ensure data consistency
         if self.config.dcache_clean {
             unsafe {
                 let mut cp = cortex_m::Peripherals::steal();
-                cp.SCB.clean_dcache_by_slice(buffer);
+                cp.SCB.clean_dcache_by_address(addr, c.buffer.len());
             }
         }
 
-        let regs = T::regs();
-
-        // Ensure previous operations are complete
         self.wait_busy()?;
-
-        let width = x1 - x0 + 1;
-
-        // Configure Canvas Area (ROI)
-        regs.canvas_tl_pos().write(|w| {
-            w.set_x0(x0);
-            w.set_y0(y0);
+        regs.layer1_tl_pos().write(|w| {
+            w.set_x0(c.x0);
+            w.set_y0(c.y0);
         });
-        regs.canvas_br_pos().write(|w| {
-            w.set_x1(x1);
-            w.set_y1(y1);
+        regs.layer1_br_pos().write(|w| {
+            w.set_x1(c.x1);
+            w.set_y1(c.y1);
         });
-
-        let layer_format = self.config.in_color_format.to_layer_format();
-
-        // Configure Layer 0
-        regs.layer0_config().write(|w| {
+        regs.layer1_src()
+            .write(|w| w.set_addr(to_system_bus_addr(addr) as u32));
+        regs.layer1_config().write(|w| {
             w.set_active(true);
-            w.set_format(layer_format);
-            w.set_alpha(255); // Fully opaque
-            w.set_alpha_sel(AlphaSel::Layer);
+            w.set_format(c.format.to_layer_format());
+            w.set_alpha(c.alpha);
+            w.set_alpha_sel(c.alpha_sel);
             w.set_prefetch_en(true);
             w.set_v_mirror(false);
-
-            // Calculate width in bytes for the pitch
-            let bpp = self.config.in_color_format.bpp();
-            let line_width_bytes = width * bpp;
-            w.set_width(line_width_bytes);
+            w.set_width(width * c.format.bpp());
         });
+        Ok(())
+    }
 
-        regs.layer0_tl_pos().write(|w| {
-            w.set_x0(x0);
-            w.set_y0(y0);
-        });
-        regs.layer0_br_pos().write(|w| {
-            w.set_x1(x1);
-            w.set_y1(y1);
-        });
+    /// Stage `buffer` into `staging` with a memory-to-memory DMA copy, then send
+    /// it like [`send_pixel_data`](Self::send_pixel_data).
+    ///
+    /// The layer fetch engine reads its source over the HPSYS system bus: it
+    /// reaches HPSYS SRAM ([`hpsys::RAM_SIZE`] bytes from [`hpsys::RAM_BASE`])
+    /// and the MPI1/MPI2 flash/PSRAM windows (their code-bus addresses are translated to the
+    /// system-bus alias). It cannot be pointed at other memories, such as
+    /// LPSYS RAM, and it needs the source aligned to
+    /// [`InputColorFormat::alignment`]. For such buffers, keep a `staging`
+    /// buffer in HPSYS SRAM and send through this method instead; the DMAC
+    /// copies byte-exact, so `buffer` itself may have any alignment.
+    ///
+    /// `staging` must be at least `buffer.len()` bytes and aligned like a
+    /// buffer passed to `send_pixel_data`. The copy uses word beats when both
+    /// buffers and the length are 4-byte aligned, bytes otherwise, in chunks
+    /// of up to 65535 beats.
+    ///
+    /// With [`Config::dcache_clean`] set, `buffer` is cleaned from the D-cache
+    /// before the copy, so pixels still in the cache (e.g. drawn into PSRAM)
+    /// reach the DMAC, and `staging` is cleaned and invalidated, so no cache
+    /// line evicted later can overwrite the copy. Do not touch `staging` from
+    /// the CPU while this runs.
+    ///
+    /// [`hpsys::RAM_BASE`]: crate::lcpu::memory_map::hpsys::RAM_BASE
+    /// [`hpsys::RAM_SIZE`]: crate::lcpu::memory_map::hpsys::RAM_SIZE
+    pub async fn send_pixel_data_dma(
+        &mut self,
+        dma: impl Peripheral<P = impl DmaChannel>,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        buffer: &[u8],
+        staging: &mut [u8],
+    ) -> Result<(), Error> {
+        self.check_area(x0, y0, x1, y1, buffer.len())?;
+        let staging = staging
+            .get_mut(..buffer.len())
+            .ok_or(Error::InvalidParameter)?;
+        into_ref!(dma);
 
-        // SPI Specific: Enable Auto CS Disable
-        regs.spi_if_conf().modify(|w| {
-            w.set_spi_cs_auto_dis(true);
-        });
+        if self.config.dcache_clean {
+            unsafe {
+                let mut cp = cortex_m::Peripherals::steal();
+                cp.SCB
+                    .clean_dcache_by_address(buffer.as_ptr() as usize, buffer.len());
+                cp.SCB
+                    .clean_invalidate_dcache_by_address(staging.as_ptr() as usize, staging.len());
+            }
+        }
 
-        // Set Source Address
-        // Note: Buffer alignment requirements
-        let addr = to_system_bus_addr(buffer.as_ptr() as usize) as u32;
-        regs.layer0_src().write(|w| w.set_addr(addr));
+        const MAX_BEATS: usize = 0xFFFF;
+        let words = (buffer.as_ptr() as usize).is_multiple_of(4)
+            && (staging.as_ptr() as usize).is_multiple_of(4)
+            && buffer.len().is_multiple_of(4);
+        if words {
+            // SAFETY: both buffers are 4-byte aligned with a length that is a
+            // multiple of 4, and u32 has no invalid bit patterns.
+            let (src, dst) = unsafe {
+                (
+                    core::slice::from_raw_parts(buffer.as_ptr() as *const u32, buffer.len() / 4),
+                    core::slice::from_raw_parts_mut(
+                        staging.as_mut_ptr() as *mut u32,
+                        staging.len() / 4,
+                    ),
+                )
+            };
+            for (s, d) in src.chunks(MAX_BEATS).zip(dst.chunks_mut(MAX_BEATS)) {
+                // SAFETY: distinct borrows of valid memory, awaited before reuse.
+                unsafe { Transfer::new_transfer(dma.reborrow(), s, d, TransferOptions::default()) }
+                    .with_timeout(self.config.frame_timeout)
+                    .await
+                    .map_err(|_| Error::Timeout)?;
+            }
+        } else {
+            for (s, d) in buffer.chunks(MAX_BEATS).zip(staging.chunks_mut(MAX_BEATS)) {
+                // SAFETY: distinct borrows of valid memory, awaited before reuse.
+                unsafe { Transfer::new_transfer(dma.reborrow(), s, d, TransferOptions::default()) }
+                    .with_timeout(self.config.frame_timeout)
+                    .await
+                    .map_err(|_| Error::Timeout)?;
+            }
+        }
+        // The DMAC's writes to `staging` complete before the layer fetch is
+        // programmed.
+        fence(Ordering::SeqCst);
 
-        // --- Interrupt Setup ---
+        self.send_pixel_data(x0, y0, x1, y1, staging).await
+    }
 
-        // Clear any pending status flags from previous runs
-        regs.irq().write(|w| {
-            w.set_eof_stat(true);
-            w.set_dpi_udr_stat(true);
-            w.set_icb_of_stat(true);
-        });
+    /// Register two full-frame buffers for double-buffered output.
+    ///
+    /// Each buffer must hold exactly `width * height` pixels of the configured
+    /// input format and be aligned to [`InputColorFormat::alignment`].
+    /// Draw into [`back_buffer`](Self::back_buffer), then call
+    /// [`present`](Self::present).
+    pub fn set_framebuffers(&mut self, fb0: &'d mut [u8], fb1: &'d mut [u8]) -> Result<(), Error> {
+        let frame_len = self.config.width as usize
+            * self.config.height as usize
+            * self.config.in_color_format.bpp() as usize;
+        let align = self.config.in_color_format.alignment();
+        for fb in [&*fb0, &*fb1] {
+            if fb.len() != frame_len || !(fb.as_ptr() as usize).is_multiple_of(align) {
+                return Err(Error::InvalidParameter);
+            }
+        }
 
-        // Enable/Unmask Interrupts
-        regs.setting().modify(|w| {
-            w.set_eof_mask(true);
-            w.set_dpi_udr_mask(true);
-            w.set_icb_of_mask(true);
+        // The previous pair may still be scanned out.
+        QUEUE.lock(|q| q.set(None));
+        self.wait_busy()?;
+        self.in_flight = false;
+        self.framebuffers = Some([fb0, fb1]);
+        self.front = 0;
+        Ok(())
+    }
+
+    /// Index of the framebuffer that is not being displayed.
+    pub fn back_index(&self) -> usize {
+        self.front ^ 1
+    }
+
+    /// The framebuffer that is safe to draw into, or `None` if no
+    /// framebuffers are registered.
+    pub fn back_buffer(&mut self) -> Option<&mut [u8]> {
+        let back = self.back_index();
+        self.framebuffers.as_mut().map(|fbs| &mut *fbs[back])
+    }
+
+    /// Start sending framebuffer `fb_index` to the panel and make it the front
+    /// buffer. Returns the index of the new back buffer.
+    ///
+    /// If a previously presented frame is still in flight, this first waits
+    /// for its End-Of-Frame, so the swap never tears the frame being sent.
+    /// The new transfer is not awaited: draw the next frame into the returned
+    /// back buffer meanwhile, and use [`wait_present`](Self::wait_present) if
+    /// the completion itself matters.
+    ///
+    /// ```rust,ignore
+    /// lcdc.set_framebuffers(&mut FB0.0, &mut FB1.0)?;
+    /// loop {
+    ///     draw(lcdc.back_buffer().unwrap());
+    ///     let back = lcdc.present(lcdc.back_index()).await?;
+    /// }
+    /// ```
+    pub async fn present(&mut self, fb_index: usize) -> Result<usize, Error> {
+        if fb_index > 1 {
+            return Err(Error::InvalidParameter);
+        }
+        let (addr, len) = match self.framebuffers.as_ref() {
+            Some(fbs) => (fbs[fb_index].as_ptr() as usize, fbs[fb_index].len()),
+            None => return Err(Error::InvalidParameter),
+        };
+
+        self.wait_present().await?;
+
+        let (x1, y1) = (self.config.width - 1, self.config.height - 1);
+        self.configure_layer0(0, 0, x1, y1, addr, len)?;
+
+        self.front = fb_index;
+        self.in_flight = true;
+        self.start_transfer();
+
+        Ok(self.back_index())
+    }
+
+    /// Wait until the last [`present`](Self::present)ed frame has been sent.
+    ///
+    /// Returns immediately if no presented frame is in flight.
+    pub async fn wait_present(&mut self) -> Result<(), Error> {
+        if !self.in_flight {
+            return Ok(());
+        }
+        let result = self.wait_for_transfer_completion().await;
+        self.in_flight = false;
+        if result.is_err() {
+            QUEUE.lock(|q| q.set(None));
+        }
+        result
+    }
+
+    /// Measure the time between consecutive End-Of-Frame interrupts.
+    ///
+    /// Re-sends the current front buffer `frames` times back to back and
+    /// returns the average EOF-to-EOF interval, i.e. the best frame time the
+    /// panel link can sustain at the current SPI clock and frame size. The
+    /// interval includes restarting the transfer from the task, so it is the
+    /// upper bound an application loop can reach. Compare it against the
+    /// loop's own frame time to tell a slow link from slow drawing:
+    ///
+    /// ```rust,ignore
+    /// let t = lcdc.measure_frame_time(8).await?;
+    /// info!("{} fps", 1_000_000 / t.as_micros());
+    /// ```
+    ///
+    /// Requires [`set_framebuffers`](Self::set_framebuffers); waits for any
+    /// frame already in flight first. `frames` must be non-zero.
+    pub async fn measure_frame_time(&mut self, frames: u32) -> Result<Duration, Error> {
+        if frames == 0 || self.framebuffers.is_none() {
+            return Err(Error::InvalidParameter);
+        }
+        let front = self.front;
+
+        // The first EOF after an idle period is the start of the window.
+        self.present(front).await?;
+        self.wait_present().await?;
+        let start = Instant::now();
+        for _ in 0..frames {
+            self.present(front).await?;
+            self.wait_present().await?;
+        }
+        Ok((Instant::now() - start) / frames)
+    }
+
+    /// Program canvas and layer 0 for a `(x0, y0)..=(x1, y1)` transfer from
+    /// the pixel buffer at `addr`.
+    fn configure_layer0(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        addr: usize,
+        len: usize,
+    ) -> Result<(), Error> {
+        let setup = self.layer0_setup(x0, y0, x1, y1, addr, len)?;
+
+        // Ensure previous operations are complete
+        self.wait_busy()?;
+
+        write_layer0(T::regs(), &setup);
+        Ok(())
+    }
+
+    /// Check that `(x0, y0)..=(x1, y1)` is a non-empty area inside the
+    /// configured panel and that `len` bytes hold exactly its pixels.
+    fn check_area(&self, x0: u16, y0: u16, x1: u16, y1: u16, len: usize) -> Result<(), Error> {
+        if x0 > x1 || y0 > y1 || x1 >= self.config.width || y1 >= self.config.height {
+            error!(
+                "LCDC: area ({}, {})..=({}, {}) outside {}x{} panel",
+                x0, y0, x1, y1, self.config.width, self.config.height
+            );
+            return Err(Error::InvalidParameter);
+        }
+        let expected = (x1 - x0 + 1) as usize
+            * (y1 - y0 + 1) as usize
+            * self.config.in_color_format.bpp() as usize;
+        if len != expected {
+            error!("LCDC: buffer is {} bytes, area needs {}", len, expected);
+            return Err(Error::BufferSizeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Check the buffer, clean it from the D-cache and compute the layer 0
+    /// register values for a transfer.
+    fn layer0_setup(
+        &self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        addr: usize,
+        len: usize,
+    ) -> Result<Layer0Setup, Error> {
+        self.check_area(x0, y0, x1, y1, len)?;
+
+        let align = self.config.in_color_format.alignment();
+        if !addr.is_multiple_of(align) {
+            error!("LCDC: buffer 0x{:08X} not {}-byte aligned", addr, align);
+            return Err(Error::InvalidParameter);
+        }
+
+        // Clean D-cache to ensure data consistency
+        if self.config.dcache_clean {
+            unsafe {
+                let mut cp = cortex_m::Peripherals::steal();
+                cp.SCB.clean_dcache_by_address(addr, len);
+            }
+        }
+
+        // Calculate width in bytes for the pitch
+        let width = x1 - x0 + 1;
+        let line_width_bytes = width * self.config.in_color_format.bpp();
+
+        Ok(Layer0Setup {
+            x0,
+            y0,
+            x1,
+            y1,
+            format: self.config.in_color_format.to_layer_format(),
+            line_width_bytes,
+            // Alignment checked above
+            src: to_system_bus_addr(addr) as u32,
+        })
+    }
+
+    /// Queue a frame for transfer without waiting for it.
+    ///
+    /// If the LCDC is idle the transfer starts right away. If a frame is
+    /// still being sent, `frame` is parked in a one-deep queue and started by
+    /// the End-Of-Frame interrupt, so preparing frame N+1 overlaps sending
+    /// frame N. With one frame sending and one queued, further submits fail
+    /// with [`Error::QueueFull`]; call [`flush`](Self::flush) first.
+    ///
+    /// `frame.buffer` stays borrowed for the driver's lifetime, since the
+    /// hardware may read it after this returns. Errors of the previous
+    /// transfer (underrun, overflow) are returned here or from `flush`;
+    /// queued frames are not re-armed after an underrun.
+    ///
+    /// ```rust,ignore
+    /// lcdc.submit(Frame::full(&FRAME_A, 240, 240)?)?;
+    /// lcdc.submit(Frame::full(&FRAME_B, 240, 240)?)?;
+    /// lcdc.flush().await?;
+    /// ```
+    pub fn submit(&mut self, frame: Frame<'d>) -> Result<(), Error> {
+        let Frame {
+            x0,
+            y0,
+            x1,
+            y1,
+            buffer,
+        } = frame;
+        let setup = self.layer0_setup(x0, y0, x1, y1, buffer.as_ptr() as usize, buffer.len())?;
+
+        let regs = T::regs();
+        let queued = critical_section::with(|_| {
+            // EOF stays unmasked while a transfer (or a chain of them) runs.
+            if !(self.in_flight && regs.setting().read().eof_mask()) {
+                return None;
+            }
+            Some(QUEUE.lock(|q| {
+                if q.get().is_some() {
+                    return Err(Error::QueueFull);
+                }
+                q.set(Some(setup));
+                Ok(())
+            }))
         });
+        if let Some(result) = queued {
+            return result;
+        }
 
-        compiler_fence(Ordering::SeqCst);
+        // Nothing is running, and with EOF masked nothing starts without
+        // `&mut self`, so the wait can run with interrupts enabled.
+        if self.in_flight {
+            // The last transfer ended; surface its error, if any.
+            self.in_flight = false;
+            let irq = regs.irq().read();
+            if irq.dpi_udr_raw_stat() {
+                return Err(Error::DpiUnderflow);
+            }
+            if irq.icb_of_raw_stat() {
+                return Err(Error::LayerOverflow);
+            }
+        }
 
-        // Start Transfer
-        regs.command().write(|w| w.set_start(true));
+        self.wait_busy()?;
+        critical_section::with(|_| {
+            write_layer0(regs, &setup);
+            self.in_flight = true;
+            self.start_transfer();
+        });
+        Ok(())
+    }
 
-        self.wait_for_transfer_completion().await
+    /// Wait until all [`submit`](Self::submit)ted frames have been sent.
+    ///
+    /// On error the queued frame, if any, is discarded.
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        self.wait_present().await
     }
 
     pub async fn send_pixel_data_rect(
@@ -631,11 +1454,15 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         height: u16,
         buffer: &[u8],
     ) -> Result<(), Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidParameter);
+        }
+
         let bpp = self.config.in_color_format.bpp() as usize;
         let len = buffer.len();
 
         if !len.is_multiple_of(bpp) {
-            return Err(Error::UnalignedData);
+            return Err(Error::BufferSizeMismatch);
         }
 
         let pixel_count = len / bpp;
@@ -648,7 +1475,7 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
                 width,
                 height
             );
-            return Err(Error::UnalignedData);
+            return Err(Error::BufferSizeMismatch);
         }
 
         self.send_pixel_data(0, 0, width - 1, height - 1, buffer)
@@ -659,6 +1486,75 @@ impl<'d, T: Instance> Lcdc<'d, T, Spi> {
         self.send_pixel_data_rect(self.config.width, self.config.height, buffer)
             .await
     }
+
+    /// Send rows `y0..=y1` of a full-panel `framebuffer`.
+    ///
+    /// `framebuffer` holds the whole panel (`width * height` pixels of the
+    /// configured input format). Only the given band of rows is transferred;
+    /// full rows are contiguous in the buffer, so no copy is needed. Use it to
+    /// update just the part of the screen that changed.
+    pub async fn flush_region(
+        &mut self,
+        framebuffer: &[u8],
+        y0: u16,
+        y1: u16,
+    ) -> Result<(), Error> {
+        let stride = self.config.width as usize * self.config.in_color_format.bpp() as usize;
+        if framebuffer.len() != stride * self.config.height as usize {
+            return Err(Error::BufferSizeMismatch);
+        }
+        if y0 > y1 || y1 >= self.config.height {
+            return Err(Error::InvalidParameter);
+        }
+        let rows = &framebuffer[y0 as usize * stride..(y1 as usize + 1) * stride];
+        self.send_pixel_data(0, y0, self.config.width - 1, y1, rows)
+            .await
+    }
+
+    /// Send an 8-bit indexed (L8) area through `palette`.
+    ///
+    /// `indices` holds one byte per pixel of `(x0, y0)..=(x1, y1)`, row-major.
+    /// They are expanded on the CPU into `scratch`, which must hold the area
+    /// as RGB565 (2 bytes per pixel, 2-byte aligned), and then sent like
+    /// [`send_pixel_data`](Self::send_pixel_data). The configured
+    /// [`Config::in_color_format`] must be [`InputColorFormat::Rgb565`].
+    /// Either mismatch fails with [`Error::PaletteMismatch`].
+    ///
+    /// A scratch buffer sized for the largest dirty rectangle, rather than the
+    /// whole screen, keeps the RAM savings of the indexed framebuffer.
+    pub async fn send_indexed_pixel_data(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        indices: &[u8],
+        palette: &Palette,
+        scratch: &mut [u8],
+    ) -> Result<(), Error> {
+        if x1 < x0 || y1 < y0 {
+            return Err(Error::InvalidParameter);
+        }
+        if self.config.in_color_format != InputColorFormat::Rgb565 {
+            error!("LCDC: palette output is RGB565, input format is not");
+            return Err(Error::PaletteMismatch);
+        }
+        let count = (x1 - x0 + 1) as usize * (y1 - y0 + 1) as usize;
+        if indices.len() != count {
+            error!(
+                "LCDC: {} palette indices, area has {} pixels",
+                indices.len(),
+                count
+            );
+            return Err(Error::PaletteMismatch);
+        }
+        let out = scratch
+            .get_mut(..count * 2)
+            .ok_or(Error::InvalidParameter)?;
+
+        palette.expand(indices, out);
+        self.send_pixel_data(x0, y0, x1, y1, out).await
+    }
 }
 
 // ============================================================================
@@ -683,13 +1579,44 @@ impl<'d, T: Instance, I: LcdInterface> Lcdc<'d, T, I> {
         T::regs().lcd_if_conf().modify(|w| w.set_lcd_rstb(!reset));
     }
 
+    /// Total number of DPI underrun recoveries since the driver was created.
+    ///
+    /// See [`Config::max_underrun_retries`].
+    pub fn underrun_retries(&self) -> u32 {
+        self.underrun_retries
+    }
+
+    /// Helper: Clear status flags, unmask interrupts and start a frame transfer.
+    fn start_transfer(&mut self) {
+        let regs = T::regs();
+
+        // Clear any pending status flags from previous runs
+        regs.irq().write(|w| {
+            w.set_eof_stat(true);
+            w.set_dpi_udr_stat(true);
+            w.set_icb_of_stat(true);
+        });
+
+        // Enable/Unmask Interrupts
+        regs.setting().modify(|w| {
+            w.set_eof_mask(true);
+            w.set_dpi_udr_mask(true);
+            w.set_icb_of_mask(true);
+        });
+
+        compiler_fence(Ordering::SeqCst);
+
+        // Start Transfer
+        regs.command().write(|w| w.set_start(true));
+    }
+
     /// Helper: Wait for the interface to be ready.
     /// Checks generic LCD busy status.
     fn wait_busy(&self) -> Result<(), Error> {
         let regs = T::regs();
-        blocking_wait_timeout_ms(
+        blocking_wait_timeout(
             || regs.status().read().lcd_busy() || regs.lcd_single().read().lcd_busy(),
-            100,
+            self.config.frame_timeout,
         )
         .map_err(|_| Error::Timeout)
     }
@@ -698,8 +1625,11 @@ impl<'d, T: Instance, I: LcdInterface> Lcdc<'d, T, I> {
     fn wait_single_busy(&self) -> Result<(), Error> {
         let regs = T::regs();
 
-        blocking_wait_timeout_ms(|| regs.lcd_single().read().lcd_busy(), 100)
-            .map_err(|_| Error::Timeout)
+        blocking_wait_timeout(
+            || regs.lcd_single().read().lcd_busy(),
+            self.config.single_timeout,
+        )
+        .map_err(|_| Error::Timeout)
     }
 
     /// Waits asynchronously for the pixel transfer to complete using interrupts.
@@ -707,7 +1637,7 @@ impl<'d, T: Instance, I: LcdInterface> Lcdc<'d, T, I> {
     async fn wait_for_transfer_completion(&mut self) -> Result<(), Error> {
         let regs = T::regs();
 
-        poll_fn(move |cx| {
+        let eof = poll_fn(move |cx| {
             WAKER.register(cx.waker());
 
             // Check for Errors by reading raw status
@@ -725,11 +1655,165 @@ impl<'d, T: Instance, I: LcdInterface> Lcdc<'d, T, I> {
             }
 
             Poll::Pending
+        });
+        with_timeout(self.config.frame_timeout, eof)
+            .await
+            .unwrap_or(Err(Error::Timeout))
+    }
+}
+
+impl<'d, T: Instance, I: LcdInterface> Drop for Lcdc<'d, T, I> {
+    fn drop(&mut self) {
+        // Don't release registered framebuffers while the LCDC still reads them.
+        if self.in_flight {
+            QUEUE.lock(|q| q.set(None));
+            let _ = self.wait_busy();
+        }
+        // Overlay buffers are only borrowed for the driver's lifetime.
+        T::regs().layer1_config().modify(|w| w.set_active(false));
+        T::taken().store(false, Ordering::SeqCst);
+    }
+}
+
+/// A pixel area for [`Lcdc::submit`].
+#[derive(Debug, Clone, Copy)]
+pub struct Frame<'a> {
+    pub x0: u16,
+    pub y0: u16,
+    /// Inclusive.
+    pub x1: u16,
+    /// Inclusive.
+    pub y1: u16,
+    /// Pixels of the area in the configured input format, aligned to
+    /// [`InputColorFormat::alignment`].
+    pub buffer: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// A full `width` x `height` frame starting at the origin.
+    ///
+    /// Fails with [`Error::InvalidParameter`] if either size is zero.
+    pub fn full(buffer: &'a [u8], width: u16, height: u16) -> Result<Self, Error> {
+        if width == 0 || height == 0 {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Self {
+            x0: 0,
+            y0: 0,
+            x1: width - 1,
+            y1: height - 1,
+            buffer,
         })
-        .await
     }
 }
 
+/// Number of LCDC layers, including layer 0. See [`Lcdc::configure_layer`].
+pub const LAYER_COUNT: usize = 2;
+
+/// An overlay layer for [`Lcdc::configure_layer`].
+#[derive(Debug, Clone, Copy)]
+pub struct LayerConfig<'a> {
+    /// Top-left corner in panel coordinates.
+    pub x0: u16,
+    pub y0: u16,
+    /// Inclusive.
+    pub x1: u16,
+    /// Inclusive.
+    pub y1: u16,
+    /// Format of `buffer`; independent of [`Config::in_color_format`].
+    pub format: InputColorFormat,
+    /// Constant layer alpha, used with [`AlphaSel::Layer`]. 255 is opaque.
+    pub alpha: u8,
+    /// Whether [`alpha`](Self::alpha) or the pixels' own alpha is used.
+    pub alpha_sel: AlphaSel,
+    /// Pixels of the area in `format`, aligned to
+    /// [`InputColorFormat::alignment`].
+    pub buffer: &'a [u8],
+}
+
+impl<'a> LayerConfig<'a> {
+    /// A `width` x `height` RGB565 overlay at `(x, y)`, blended at 50 %
+    /// layer alpha.
+    ///
+    /// Returns [`Error::InvalidParameter`] for an empty area or one that
+    /// extends past the 16-bit coordinate range.
+    pub fn new(x: u16, y: u16, width: u16, height: u16, buffer: &'a [u8]) -> Result<Self, Error> {
+        let (Some(x1), Some(y1)) = (
+            width.checked_sub(1).and_then(|w| x.checked_add(w)),
+            height.checked_sub(1).and_then(|h| y.checked_add(h)),
+        ) else {
+            error!(
+                "LCDC: layer area {}x{} at ({}, {}) invalid",
+                width, height, x, y
+            );
+            return Err(Error::InvalidParameter);
+        };
+        Ok(Self {
+            x0: x,
+            y0: y,
+            x1,
+            y1,
+            format: InputColorFormat::Rgb565,
+            alpha: 128,
+            alpha_sel: AlphaSel::Layer,
+            buffer,
+        })
+    }
+}
+
+/// Canvas and layer 0 register values for one transfer.
+#[derive(Clone, Copy)]
+struct Layer0Setup {
+    x0: u16,
+    y0: u16,
+    x1: u16,
+    y1: u16,
+    format: LayerFormat,
+    line_width_bytes: u16,
+    src: u32,
+}
+
+/// Write `setup` to canvas and layer 0. Also called from the EOF interrupt.
+fn write_layer0(regs: crate::pac::lcdc::Lcdc, setup: &Layer0Setup) {
+    // Configure Canvas Area (ROI)
+    regs.canvas_tl_pos().write(|w| {
+        w.set_x0(setup.x0);
+        w.set_y0(setup.y0);
+    });
+    regs.canvas_br_pos().write(|w| {
+        w.set_x1(setup.x1);
+        w.set_y1(setup.y1);
+    });
+
+    // Configure Layer 0
+    regs.layer0_config().write(|w| {
+        w.set_active(true);
+        w.set_format(setup.format);
+        w.set_alpha(255); // Fully opaque
+        w.set_alpha_sel(AlphaSel::Layer);
+        w.set_prefetch_en(true);
+        w.set_v_mirror(false);
+        w.set_width(setup.line_width_bytes);
+    });
+
+    regs.layer0_tl_pos().write(|w| {
+        w.set_x0(setup.x0);
+        w.set_y0(setup.y0);
+    });
+    regs.layer0_br_pos().write(|w| {
+        w.set_x1(setup.x1);
+        w.set_y1(setup.y1);
+    });
+
+    // SPI Specific: Enable Auto CS Disable
+    regs.spi_if_conf().modify(|w| {
+        w.set_spi_cs_auto_dis(true);
+    });
+
+    // Set Source Address
+    regs.layer0_src().write(|w| w.set_addr(setup.src));
+}
+
 /// Errors that can occur during LCD operations
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Error {
@@ -740,6 +1824,16 @@ pub enum Error {
     DpiUnderflow,
     /// Buffer Overflow Error
     LayerOverflow,
+    /// [`Lcdc::submit`] with a frame sending and another already queued.
+    QueueFull,
+    /// Single access while a [`Lcdc::present`] or [`Lcdc::submit`] frame is
+    /// still being sent. See [`Lcdc::is_busy`].
+    Busy,
+    /// [`Lcdc::send_indexed_pixel_data`] indices do not cover the area, or
+    /// the configured input format is not the palette's RGB565.
+    PaletteMismatch,
+    /// A buffer's length does not match the area (or panel) it is sent to.
+    BufferSizeMismatch,
 }
 
 // ============================================================================
@@ -759,6 +1853,8 @@ pub(crate) trait SealedInstance:
     crate::rcc::RccEnableReset + crate::rcc::RccGetFreq
 {
     fn regs() -> crate::pac::lcdc::Lcdc;
+    /// Set while an [`Lcdc`] driver owns this instance.
+    fn taken() -> &'static AtomicBool;
 }
 
 #[allow(private_bounds)]
@@ -770,6 +1866,10 @@ impl SealedInstance for peripherals::LCDC1 {
     fn regs() -> crate::pac::lcdc::Lcdc {
         crate::pac::LCDC1
     }
+    fn taken() -> &'static AtomicBool {
+        static TAKEN: AtomicBool = AtomicBool::new(false);
+        &TAKEN
+    }
 }
 impl Instance for peripherals::LCDC1 {
     type Interrupt = crate::interrupt::typelevel::LCDC1;
@@ -793,6 +1893,8 @@ impl<'d, T: Instance> DisplayBus for Lcdc<'d, T, Spi> {
             .iter()
             .fold(0u32, |acc, &byte| (acc << 8) | (byte as u32));
 
+        // Queue behind a presented/submitted frame rather than fail with `Busy`.
+        self.wait_present().await?;
         self.send_cmd(cmd_word, cmd.len() as u8, false)
     }
 
@@ -809,20 +1911,9 @@ impl<'d, T: Instance> DisplayBus for Lcdc<'d, T, Spi> {
             .iter()
             .fold(0u32, |acc, &byte| (acc << 8) | (byte as u32));
 
+        self.wait_present().await?;
         self.send_cmd(cmd_word, cmd.len() as u8, !params.is_empty())?;
-
-        if !params.is_empty() {
-            params.chunks(4).enumerate().try_for_each(|(i, chunk)| {
-                let data_word = chunk
-                    .iter()
-                    .fold(0u32, |acc, &byte| (acc << 8) | (byte as u32));
-
-                let is_last = (i + 1) * 4 >= params.len();
-                self.send_cmd_data(data_word, chunk.len() as u8, !is_last)
-            })
-        } else {
-            Ok(())
-        }
+        self.send_params(params)
     }
 
     async fn write_pixels(
@@ -836,15 +1927,18 @@ impl<'d, T: Instance> DisplayBus for Lcdc<'d, T, Spi> {
         }
 
         let area = metadata.area.unwrap();
+        let (x1, y1) = area.bottom_right();
+        self.check_area(area.x, area.y, x1, y1, data.len())
+            .map_err(DisplayError::BusError)?;
 
         let cmd_word = cmd
             .iter()
             .fold(0u32, |acc, &byte| (acc << 8) | (byte as u32));
 
+        self.wait_present().await.map_err(DisplayError::BusError)?;
         self.send_cmd(cmd_word, cmd.len() as u8, true)
             .map_err(DisplayError::BusError)?;
 
-        let (x1, y1) = area.bottom_right();
         self.send_pixel_data(area.x, area.y, x1, y1, data)
             .await
             .map_err(DisplayError::BusError)
@@ -0,0 +1,143 @@
+//! `embedded-graphics` draw target on top of [`Lcdc`].
+
+use core::convert::Infallible;
+
+use embedded_graphics_core::pixelcolor::raw::RawU16;
+use embedded_graphics_core::pixelcolor::Rgb565;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+use super::{Error, InputColorFormat, Instance, Lcdc, Spi};
+
+/// RGB565 [`DrawTarget`] that sends only the rows touched since the last
+/// [`flush`](Self::flush).
+///
+/// Drawing goes into a caller-provided panel-sized buffer (little-endian
+/// RGB565, as the LCDC reads it) and records the band of rows it changed.
+/// `flush` then hands just that band to [`Lcdc::flush_region`], so a UI that
+/// redraws a clock or a status line sends a few rows instead of the whole
+/// screen:
+///
+/// ```rust,ignore
+/// #[repr(align(4))]
+/// struct Buf([u8; 240 * 240 * 2]);
+/// static BUF: StaticCell<Buf> = StaticCell::new();
+///
+/// let mut display = LcdcDisplay::new(&mut lcdc, &mut BUF.init(Buf([0; 240 * 240 * 2])).0)?;
+/// display.clear(Rgb565::BLACK).unwrap();
+/// Text::new("12:34", Point::new(80, 120), style).draw(&mut display).unwrap();
+/// display.flush().await?;
+/// ```
+pub struct LcdcDisplay<'a, 'd, T: Instance> {
+    lcdc: &'a mut Lcdc<'d, T, Spi>,
+    buffer: &'a mut [u8],
+    width: u16,
+    height: u16,
+    /// Rows `(first, last)` changed since the last flush.
+    dirty: Option<(u16, u16)>,
+}
+
+impl<'a, 'd, T: Instance> LcdcDisplay<'a, 'd, T> {
+    /// Wrap `lcdc` with `buffer` as the drawing surface.
+    ///
+    /// The LCDC must be configured for [`InputColorFormat::Rgb565`]
+    /// ([`Error::InvalidParameter`] otherwise), and `buffer` must hold exactly
+    /// `width * height * 2` bytes ([`Error::BufferSizeMismatch`]). The buffer
+    /// starts out marked clean; its current contents are not sent until
+    /// something is drawn over them.
+    pub fn new(lcdc: &'a mut Lcdc<'d, T, Spi>, buffer: &'a mut [u8]) -> Result<Self, Error> {
+        if lcdc.config.in_color_format != InputColorFormat::Rgb565 {
+            return Err(Error::InvalidParameter);
+        }
+        let (width, height) = (lcdc.config.width, lcdc.config.height);
+        if buffer.len() != width as usize * height as usize * 2 {
+            return Err(Error::BufferSizeMismatch);
+        }
+        Ok(Self {
+            lcdc,
+            buffer,
+            width,
+            height,
+            dirty: None,
+        })
+    }
+
+    /// Whether anything was drawn since the last flush.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_some()
+    }
+
+    /// Mark the whole screen for the next flush.
+    pub fn invalidate(&mut self) {
+        self.dirty = Some((0, self.height - 1));
+    }
+
+    /// Send the rows changed since the last flush; a no-op when clean.
+    ///
+    /// On error the rows stay marked, so the next flush retries them.
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        let Some((y0, y1)) = self.dirty else {
+            return Ok(());
+        };
+        self.lcdc.flush_region(self.buffer, y0, y1).await?;
+        self.dirty = None;
+        Ok(())
+    }
+
+    fn mark(&mut self, y0: u16, y1: u16) {
+        self.dirty = Some(match self.dirty {
+            Some((a, b)) => (a.min(y0), b.max(y1)),
+            None => (y0, y1),
+        });
+    }
+
+    fn put(&mut self, x: u16, y: u16, color: Rgb565) {
+        let i = (y as usize * self.width as usize + x as usize) * 2;
+        let raw = RawU16::from(color).into_inner();
+        self.buffer[i..i + 2].copy_from_slice(&raw.to_le_bytes());
+    }
+}
+
+impl<'a, 'd, T: Instance> OriginDimensions for LcdcDisplay<'a, 'd, T> {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+impl<'a, 'd, T: Instance> DrawTarget for LcdcDisplay<'a, 'd, T> {
+    type Color = Rgb565;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (w, h) = (self.width as i32, self.height as i32);
+        for Pixel(p, color) in pixels {
+            if (0..w).contains(&p.x) && (0..h).contains(&p.y) {
+                self.put(p.x as u16, p.y as u16, color);
+                self.mark(p.y as u16, p.y as u16);
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = area.intersection(&self.bounding_box());
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+        let px = RawU16::from(color).into_inner().to_le_bytes();
+        let stride = self.width as usize * 2;
+        for y in area.top_left.y..=bottom_right.y {
+            let row = y as usize * stride;
+            let start = row + area.top_left.x as usize * 2;
+            let end = row + (bottom_right.x as usize + 1) * 2;
+            for chunk in self.buffer[start..end].chunks_exact_mut(2) {
+                chunk.copy_from_slice(&px);
+            }
+        }
+        self.mark(area.top_left.y as u16, bottom_right.y as u16);
+        Ok(())
+    }
+}
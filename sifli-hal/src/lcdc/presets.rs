@@ -0,0 +1,112 @@
+//! Init tables for common SPI panels, for [`Lcdc::send_sequence`](super::Lcdc::send_sequence).
+//!
+//! Each table takes the panel from hardware reset to display-on with RGB565
+//! input. They leave the address window, rotation and color order at the
+//! controller defaults; send `MADCTL` (`0x36`) afterwards if the panel is
+//! mounted rotated or shows red and blue swapped.
+
+use super::LcdOp::{self, Cmd, DelayMs};
+
+/// Sitronix ST7789 / ST7789V, as on 240x240 and 240x320 IPS modules.
+///
+/// Display inversion is turned on, which IPS modules need for correct colors.
+pub const ST7789: &[LcdOp] = &[
+    Cmd(0x01, &[]), // SWRESET
+    DelayMs(150),
+    Cmd(0x11, &[]), // SLPOUT
+    DelayMs(120),
+    Cmd(0x3A, &[0x55]),                         // COLMOD: 16 bpp
+    Cmd(0x36, &[0x00]),                         // MADCTL
+    Cmd(0xB2, &[0x0C, 0x0C, 0x00, 0x33, 0x33]), // PORCTRL
+    Cmd(0xB7, &[0x35]),                         // GCTRL
+    Cmd(0xBB, &[0x19]),                         // VCOMS
+    Cmd(0xC0, &[0x2C]),                         // LCMCTRL
+    Cmd(0xC2, &[0x01]),                         // VDVVRHEN
+    Cmd(0xC3, &[0x12]),                         // VRHS
+    Cmd(0xC4, &[0x20]),                         // VDVS
+    Cmd(0xC6, &[0x0F]),                         // FRCTRL2: 60 Hz
+    Cmd(0xD0, &[0xA4, 0xA1]),                   // PWCTRL1
+    Cmd(0x21, &[]),                             // INVON
+    Cmd(0x13, &[]),                             // NORON
+    DelayMs(10),
+    Cmd(0x29, &[]), // DISPON
+    DelayMs(20),
+];
+
+/// GalaxyCore GC9A01, the 240x240 round panel.
+///
+/// The vendor register block follows the GalaxyCore reference init; most of
+/// it has no public documentation, so keep it as is.
+pub const GC9A01: &[LcdOp] = &[
+    Cmd(0xEF, &[]), // Inter register enable 2
+    Cmd(0xEB, &[0x14]),
+    Cmd(0xFE, &[]), // Inter register enable 1
+    Cmd(0xEF, &[]),
+    Cmd(0xEB, &[0x14]),
+    Cmd(0x84, &[0x40]),
+    Cmd(0x85, &[0xFF]),
+    Cmd(0x86, &[0xFF]),
+    Cmd(0x87, &[0xFF]),
+    Cmd(0x88, &[0x0A]),
+    Cmd(0x89, &[0x21]),
+    Cmd(0x8A, &[0x00]),
+    Cmd(0x8B, &[0x80]),
+    Cmd(0x8C, &[0x01]),
+    Cmd(0x8D, &[0x01]),
+    Cmd(0x8E, &[0xFF]),
+    Cmd(0x8F, &[0xFF]),
+    Cmd(0xB6, &[0x00, 0x20]), // Display function control
+    Cmd(0x36, &[0x08]),       // MADCTL: BGR
+    Cmd(0x3A, &[0x05]),       // COLMOD: 16 bpp
+    Cmd(0x90, &[0x08, 0x08, 0x08, 0x08]),
+    Cmd(0xBD, &[0x06]),
+    Cmd(0xBC, &[0x00]),
+    Cmd(0xFF, &[0x60, 0x01, 0x04]),
+    Cmd(0xC3, &[0x13]), // Power control 2
+    Cmd(0xC4, &[0x13]), // Power control 3
+    Cmd(0xC9, &[0x22]), // Power control 4
+    Cmd(0xBE, &[0x11]),
+    Cmd(0xE1, &[0x10, 0x0E]),
+    Cmd(0xDF, &[0x21, 0x0C, 0x02]),
+    Cmd(0xF0, &[0x45, 0x09, 0x08, 0x08, 0x26, 0x2A]), // Gamma 1
+    Cmd(0xF1, &[0x43, 0x70, 0x72, 0x36, 0x37, 0x6F]), // Gamma 2
+    Cmd(0xF2, &[0x45, 0x09, 0x08, 0x08, 0x26, 0x2A]), // Gamma 3
+    Cmd(0xF3, &[0x43, 0x70, 0x72, 0x36, 0x37, 0x6F]), // Gamma 4
+    Cmd(0xED, &[0x1B, 0x0B]),
+    Cmd(0xAE, &[0x77]),
+    Cmd(0xCD, &[0x63]),
+    Cmd(
+        0x70,
+        &[0x07, 0x07, 0x04, 0x0E, 0x0F, 0x09, 0x07, 0x08, 0x03],
+    ),
+    Cmd(0xE8, &[0x34]), // Frame rate
+    Cmd(
+        0x62,
+        &[
+            0x18, 0x0D, 0x71, 0xED, 0x70, 0x70, 0x18, 0x0F, 0x71, 0xEF, 0x70, 0x70,
+        ],
+    ),
+    Cmd(
+        0x63,
+        &[
+            0x18, 0x11, 0x71, 0xF1, 0x70, 0x70, 0x18, 0x13, 0x71, 0xF3, 0x70, 0x70,
+        ],
+    ),
+    Cmd(0x64, &[0x28, 0x29, 0xF1, 0x01, 0xF1, 0x00, 0x07]),
+    Cmd(
+        0x66,
+        &[0x3C, 0x00, 0xCD, 0x67, 0x45, 0x45, 0x10, 0x00, 0x00, 0x00],
+    ),
+    Cmd(
+        0x67,
+        &[0x00, 0x3C, 0x00, 0x00, 0x00, 0x01, 0x54, 0x10, 0x32, 0x98],
+    ),
+    Cmd(0x74, &[0x10, 0x85, 0x80, 0x00, 0x00, 0x4E, 0x00]),
+    Cmd(0x98, &[0x3E, 0x07]),
+    Cmd(0x35, &[]), // TEON
+    Cmd(0x21, &[]), // INVON
+    Cmd(0x11, &[]), // SLPOUT
+    DelayMs(120),
+    Cmd(0x29, &[]), // DISPON
+    DelayMs(20),
+];
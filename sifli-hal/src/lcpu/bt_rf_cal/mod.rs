@@ -340,8 +340,12 @@ pub fn bt_rf_cal(dma_ch: impl Peripheral<P = impl Channel>) {
     // Note: SDK does EDR eFUSE power cal inside bt_rfc_txdc_cal (line 3753-3791);
     // we extract it here — the result is equivalent.
     // Read eFUSE calibration data for EDR power calibration
-    let efuse_cal = unsafe { Efuse::new(crate::peripherals::EFUSEC::steal()) }
-        .ok()
+    let has_factory_cal = crate::syscfg::read_idr()
+        .revision()
+        .supports(crate::syscfg::ChipFeature::FactoryRfCal);
+    let efuse_cal = has_factory_cal
+        .then(|| unsafe { Efuse::new(crate::peripherals::EFUSEC::steal()) }.ok())
+        .flatten()
         .map(|e| *e.calibration());
     let edr_pa_bm_opt = efuse_cal.as_ref().and_then(apply_edr_power_cal);
     match &edr_pa_bm_opt {
@@ -4,6 +4,8 @@
 //! and BLE/BT activity limits. These are written to the ROM configuration
 //! area before LCPU startup (Letter Series only for EM/ACT).
 
+use crate::syscfg::{ChipFeature, ChipRevision};
+
 /// BLE controller runtime parameters.
 ///
 /// Applied after LCPU boot to configure BLE scheduling and timing.
@@ -28,6 +30,40 @@ pub struct ControllerConfig {
     /// SDK: `rom_config_set_default_rc_cycle(HAL_RC_CAL_GetLPCycle_ex())`.
     /// Typical value: 20 (SDK default when using RC10K).
     pub rc_cycle: u8,
+
+    /// Sleep clock accuracy (ppm) reported to the controller.
+    ///
+    /// Used to widen the receive window after sleep and advertised to peers
+    /// as the local SCA. Written to NVDS as `PARAM_ID_LPCLK_DRIFT`.
+    /// Default: 500 (worst case for RC10K). Boards with an LXT can use the
+    /// crystal's rating plus temperature drift, typically 50.
+    pub sleep_clock_accuracy_ppm: u16,
+
+    /// Allow the LE 2M PHY (higher throughput).
+    ///
+    /// The ROM config has no PHY enable bits; [`BleController`] applies the
+    /// PHY flags after boot with HCI `LE_Set_Default_PHY`. With both flags
+    /// off only LE 1M is preferred, the behaviour of older releases.
+    ///
+    /// [`BleController`]: crate::bt_hci::BleController
+    pub le_2m_phy: bool,
+
+    /// Allow the LE Coded PHY (long range).
+    ///
+    /// Only Letter Series (A4/B4) silicon is accepted, see
+    /// [`ChipFeature::LeCodedPhy`](crate::syscfg::ChipFeature::LeCodedPhy);
+    /// on A3 [`Lcpu::power_on`](super::Lcpu::power_on) returns
+    /// [`ConfigError::CodedPhyUnsupported`].
+    pub le_coded_phy: bool,
+
+    /// Upper bound on how long the controller may sleep between radio events
+    /// (milliseconds), written to the ROM config as `max_sleep_time`.
+    ///
+    /// Longer sleeps save power but delay the controller's reaction to host
+    /// commands and widen the window drift it has to absorb. `0` (default)
+    /// keeps the ROM default. Letter Series (A4/B4) only; A3 has no BT ROM
+    /// config block and ignores it.
+    pub max_sleep_time_ms: u32,
 }
 
 impl Default for ControllerConfig {
@@ -36,10 +72,106 @@ impl Default for ControllerConfig {
             lld_prog_delay: 3,
             xtal_enabled: false,
             rc_cycle: 20,
+            sleep_clock_accuracy_ppm: 500,
+            le_2m_phy: false,
+            le_coded_phy: false,
+            max_sleep_time_ms: 0,
         }
     }
 }
 
+impl ControllerConfig {
+    /// Valid `lld_prog_delay` range (625us slots).
+    ///
+    /// 0 misses radio events; the delay must also stay below the shortest
+    /// connection interval (7.5 ms = 12 slots) or no event can be programmed.
+    pub const LLD_PROG_DELAY_RANGE: core::ops::RangeInclusive<u8> = 1..=11;
+
+    /// Minimum `rc_cycle` when running from the RC oscillator (no LXT).
+    ///
+    /// SDK default for RC10K. Fewer cycles make the sleep clock estimate too
+    /// coarse for the controller to wake in time for its next event.
+    pub const RC_CYCLE_MIN: u8 = 20;
+
+    /// Valid `sleep_clock_accuracy_ppm` range.
+    ///
+    /// The BLE SCA field tops out at 251..=500 ppm; 0 would make the
+    /// controller assume a perfect clock.
+    pub const SLEEP_CLOCK_ACCURACY_RANGE: core::ops::RangeInclusive<u16> = 1..=500;
+
+    /// Largest `max_sleep_time_ms`; the ROM field counts 312.5 us half-slots
+    /// in a `u32`.
+    pub const MAX_SLEEP_TIME_MS_MAX: u32 = (u32::MAX / 16) * 5;
+
+    /// `max_sleep_time_ms` in the controller's units (312.5 us half-slots),
+    /// or `None` to keep the ROM default.
+    pub(crate) fn max_sleep_time_half_slots(&self) -> Option<u32> {
+        match self.max_sleep_time_ms {
+            0 => None,
+            ms => Some(ms / 5 * 16 + ms % 5 * 16 / 5),
+        }
+    }
+
+    /// Check the fields against the ranges above.
+    ///
+    /// Called by [`Lcpu::power_on`](super::Lcpu::power_on) before the LCPU is
+    /// touched.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !Self::LLD_PROG_DELAY_RANGE.contains(&self.lld_prog_delay) {
+            return Err(ConfigError::LldProgDelayOutOfRange {
+                value: self.lld_prog_delay,
+            });
+        }
+        if !self.xtal_enabled && self.rc_cycle < Self::RC_CYCLE_MIN {
+            return Err(ConfigError::RcCycleTooSmall {
+                value: self.rc_cycle,
+            });
+        }
+        if !Self::SLEEP_CLOCK_ACCURACY_RANGE.contains(&self.sleep_clock_accuracy_ppm) {
+            return Err(ConfigError::SleepClockAccuracyOutOfRange {
+                value: self.sleep_clock_accuracy_ppm,
+            });
+        }
+        if self.max_sleep_time_ms > Self::MAX_SLEEP_TIME_MS_MAX {
+            return Err(ConfigError::MaxSleepTimeTooLarge {
+                value: self.max_sleep_time_ms,
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the fields that depend on the silicon revision.
+    ///
+    /// Called by [`Lcpu::power_on`](super::Lcpu::power_on) after
+    /// [`validate`](Self::validate).
+    pub fn validate_for(&self, revision: ChipRevision) -> Result<(), ConfigError> {
+        if self.le_coded_phy && !revision.supports(ChipFeature::LeCodedPhy) {
+            return Err(ConfigError::CodedPhyUnsupported);
+        }
+        Ok(())
+    }
+}
+
+/// Invalid [`ControllerConfig`] field or BD address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// `lld_prog_delay` outside [`ControllerConfig::LLD_PROG_DELAY_RANGE`].
+    LldProgDelayOutOfRange { value: u8 },
+    /// `rc_cycle` below [`ControllerConfig::RC_CYCLE_MIN`] without LXT.
+    RcCycleTooSmall { value: u8 },
+    /// `sleep_clock_accuracy_ppm` outside
+    /// [`ControllerConfig::SLEEP_CLOCK_ACCURACY_RANGE`].
+    SleepClockAccuracyOutOfRange { value: u16 },
+    /// `max_sleep_time_ms` above [`ControllerConfig::MAX_SLEEP_TIME_MS_MAX`].
+    MaxSleepTimeTooLarge { value: u32 },
+    /// `le_coded_phy` set on a revision without LE Coded PHY support.
+    CodedPhyUnsupported,
+    /// [`BleConfig::bd_addr`] is not a valid random static address, see
+    /// [`AddressType::RandomStatic`].
+    InvalidRandomStaticAddress,
+}
+
 /// User-configurable ROM parameters.
 #[derive(Debug, Clone, Copy)]
 pub struct RomConfig {
@@ -198,13 +330,19 @@ impl Default for ActConfig {
 }
 
 /// LCPU boot-time configuration (firmware, patches, ROM parameters).
+///
+/// The firmware image only has to live for the duration of the boot call, so it
+/// may point into a transient buffer (e.g. an image read from flash for OTA).
 #[derive(Debug, Clone, Copy)]
-pub struct BootConfig {
+pub struct BootConfig<'a> {
     /// LCPU firmware image bytes.
     ///
     /// - A3 and earlier: must be provided and copied to LPSYS RAM.
     /// - Letter Series: optional, firmware is in ROM.
-    pub firmware: Option<&'static [u8]>,
+    ///
+    /// Copied into LPSYS RAM during boot; must not exceed
+    /// [`memory_map::a3::LCPU_CODE_SIZE`](super::memory_map::a3::LCPU_CODE_SIZE).
+    pub firmware: Option<&'a [u8]>,
 
     /// ROM configuration parameters.
     pub rom: RomConfig,
@@ -220,9 +358,12 @@ pub struct BootConfig {
 
     /// Disable RF calibration (normally runs after patch installation).
     pub disable_rf_cal: bool,
+
+    /// Called at the start of each boot step, see [`BootProgress`](super::BootProgress).
+    pub on_progress: Option<fn(super::BootProgress)>,
 }
 
-impl BootConfig {
+impl<'a> BootConfig<'a> {
     /// Create a new config with all options unset.
     pub const fn new() -> Self {
         Self {
@@ -238,16 +379,32 @@ impl BootConfig {
             patch_letter: None,
             skip_frequency_check: false,
             disable_rf_cal: false,
+            on_progress: None,
         }
     }
 }
 
-impl Default for BootConfig {
+impl<'a> Default for BootConfig<'a> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// How the controller's own address in [`BleConfig::bd_addr`] is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AddressType {
+    /// Public (IEEE-assigned) address, written to NVDS for the LCPU ROM.
+    #[default]
+    Public,
+    /// Random static address, set with HCI `LE_Set_Random_Address` once the
+    /// controller is up. The public address is left at the ROM default.
+    ///
+    /// The two most significant bits (`bd_addr[5] & 0xC0`) must be `0b11`, and
+    /// the remaining 46 bits must be neither all zeros nor all ones.
+    RandomStatic,
+}
+
 /// BLE-specific configuration (post-boot controller params + BD address).
 #[derive(Debug, Clone, Copy)]
 pub struct BleConfig {
@@ -255,11 +412,16 @@ pub struct BleConfig {
     /// SDK equivalent: `ble_xtal_less_init()` in `bluetooth.c`.
     pub controller: ControllerConfig,
 
-    /// Public BD address written to NVDS shared memory.
+    /// BD address, least significant byte first (HCI byte order).
     ///
-    /// LCPU ROM reads this during initialization.
+    /// A public address is written to NVDS shared memory, where the LCPU ROM
+    /// reads it during initialization; see [`addr_type`](Self::addr_type).
     /// Default: `[0x12, 0x34, 0x56, 0x78, 0xAB, 0xCD]` (SDK default).
     pub bd_addr: [u8; 6],
+
+    /// Whether [`bd_addr`](Self::bd_addr) is a public or a random static
+    /// address. Default: [`AddressType::Public`].
+    pub addr_type: AddressType,
 }
 
 impl BleConfig {
@@ -270,10 +432,37 @@ impl BleConfig {
                 lld_prog_delay: 3,
                 xtal_enabled: false,
                 rc_cycle: 20,
+                sleep_clock_accuracy_ppm: 500,
+                le_2m_phy: false,
+                le_coded_phy: false,
+                max_sleep_time_ms: 0,
             },
             bd_addr: [0x12, 0x34, 0x56, 0x78, 0xAB, 0xCD],
+            addr_type: AddressType::Public,
+        }
+    }
+
+    /// Check the controller parameters and, for
+    /// [`AddressType::RandomStatic`], the address format.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.controller.validate()?;
+        if self.addr_type == AddressType::RandomStatic && !is_random_static(&self.bd_addr) {
+            return Err(ConfigError::InvalidRandomStaticAddress);
         }
+        Ok(())
+    }
+}
+
+/// Core spec Vol 6, Part B, 1.3.2.1: top two bits set, random part neither
+/// all zeros nor all ones.
+fn is_random_static(addr: &[u8; 6]) -> bool {
+    if addr[5] & 0xC0 != 0xC0 {
+        return false;
     }
+    let mut bytes = [0u8; 8];
+    bytes[..6].copy_from_slice(addr);
+    let random = u64::from_le_bytes(bytes) & ((1 << 46) - 1);
+    random != 0 && random != (1 << 46) - 1
 }
 
 impl Default for BleConfig {
@@ -281,3 +470,30 @@ impl Default for BleConfig {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_static_address_format() {
+        assert!(is_random_static(&[0x01, 0x00, 0x00, 0x00, 0x00, 0xC0]));
+        assert!(is_random_static(&[0x12, 0x34, 0x56, 0x78, 0x9A, 0xFB]));
+        // Top bits not 0b11 (public, resolvable or non-resolvable private).
+        assert!(!is_random_static(&[0x12, 0x34, 0x56, 0x78, 0x9A, 0x3B]));
+        assert!(!is_random_static(&[0x12, 0x34, 0x56, 0x78, 0x9A, 0x7B]));
+        // Random part all zeros or all ones.
+        assert!(!is_random_static(&[0x00, 0x00, 0x00, 0x00, 0x00, 0xC0]));
+        assert!(!is_random_static(&[0xFF; 6]));
+    }
+
+    #[test]
+    fn validate_checks_random_static_only() {
+        let mut cfg = BleConfig::new();
+        assert_eq!(cfg.validate(), Ok(()));
+        cfg.addr_type = AddressType::RandomStatic;
+        assert_eq!(cfg.validate(), Err(ConfigError::InvalidRandomStaticAddress));
+        cfg.bd_addr[5] |= 0xC0;
+        assert_eq!(cfg.validate(), Ok(()));
+    }
+}
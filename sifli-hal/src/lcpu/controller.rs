@@ -20,7 +20,8 @@
 //! | 7 | `wlan_coex_config()`      | —                          | Not needed                   |
 
 use super::config::ControllerConfig;
-use super::ram::BtRomConfig;
+use super::ram::{BtRomConfig, RomControlBlock};
+use super::LcpuError;
 use crate::syscfg;
 
 /// LCPU ROM runtime variable addresses.
@@ -260,6 +261,74 @@ fn setup_cfo_tracking() {
     );
 }
 
+/// BLE scheduler state observable from HCPU.
+///
+/// The LCPU ROM keeps no missed-event or sleep-duration counters in shared
+/// memory, so only the effective scheduler parameters and HCPU-side wake
+/// activity can be reported.
+///
+/// | Field               | A3                     | Letter Series                     |
+/// |---------------------|------------------------|-----------------------------------|
+/// | `lld_prog_delay`    | `rwip_prog_delay`      | `RomControlBlock.bt_config`       |
+/// | `sleep_enabled`     | `g_rom_config`         | `LPSYS_AON.RESERVE0` only         |
+/// | `lcpu_wake_requests`| HCPU counter           | HCPU counter                      |
+/// | `lcpu_active`       | `HPSYS_AON.ISSR`       | `HPSYS_AON.ISSR`                  |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SchedStats {
+    /// Programming delay currently used by the scheduler (625us slots).
+    pub lld_prog_delay: u8,
+    /// Whether controller sleep is enabled (ROM default and `RESERVE0` override).
+    pub sleep_enabled: bool,
+    /// Number of HCPU→LCPU wake requests since boot (excluding this read).
+    pub lcpu_wake_requests: u32,
+    /// Whether LPSYS is currently active (not sleeping).
+    pub lcpu_active: bool,
+}
+
+/// Read the BLE scheduler state. See [`SchedStats`] for per-revision sources.
+pub(crate) fn sched_stats() -> SchedStats {
+    let is_letter = syscfg::read_idr().revision().is_letter_series();
+
+    // Sample before our own wake request changes them.
+    let lcpu_active = crate::pac::HPSYS_AON.issr().read().lp_active();
+    let lcpu_wake_requests = crate::rcc::lcpu_wake_request_count();
+
+    // LPSYS RAM and registers are only accessible while LPSYS is awake.
+    let _w = unsafe { super::WakeGuard::acquire() };
+
+    let (lld_prog_delay, rom_sleep_enabled) = if is_letter {
+        let block = RomControlBlock::ADDR_LETTER as *const RomControlBlock;
+        let cfg = unsafe { core::ptr::read_volatile(core::ptr::addr_of!((*block).bt_config)) };
+        (cfg.lld_prog_delay, cfg.default_sleep_enabled != 0)
+    } else {
+        let delay = unsafe { core::ptr::read_volatile(addr::RWIP_PROG_DELAY_A3) };
+        let cfg = unsafe { core::ptr::read_volatile(addr::G_ROM_CONFIG_A3) };
+        (delay, cfg.default_sleep_enabled != 0)
+    };
+
+    // RESERVE0 = 1 is the `bt_sleep_control(0)` override, see `disable_ble_sleep()`.
+    let sleep_disabled = crate::pac::LPSYS_AON.reserve0().read().data() == 1;
+
+    SchedStats {
+        lld_prog_delay,
+        sleep_enabled: rom_sleep_enabled && !sleep_disabled,
+        lcpu_wake_requests,
+        lcpu_active,
+    }
+}
+
+/// Undo the HCPU-side parts of [`init`]: stop CFO tracking and gate PTC2.
+pub(crate) fn deinit() {
+    let ptc2 = crate::pac::PTC2;
+    ptc2.ier().modify(|w| {
+        w.set_tcie1(false);
+        w.set_teie(false);
+    });
+    ptc2.tcr1().write(|_| {});
+    crate::pac::LPSYS_RCC.enr1().modify(|w| w.set_ptc2(false));
+}
+
 /// Disable BLE controller sleep.
 ///
 /// SDK equivalent: `bt_sleep_control(0)` which writes `LPSYS_AON.RESERVE0 = 1`.
@@ -271,3 +340,25 @@ fn disable_ble_sleep() {
     crate::pac::LPSYS_AON.reserve0().write(|w| w.set_data(1));
     debug!("BLE sleep disabled (LPSYS_AON.RESERVE0=1)");
 }
+
+/// Change the `bt_sleep_control` override on a running controller.
+///
+/// The controller samples `RESERVE0` each time its scheduler decides whether
+/// to sleep, so the change takes effect from the next idle period without a
+/// reboot. There is no IPC reply for this; the write is confirmed by reading
+/// the word back while LPSYS is held awake.
+pub(crate) fn set_ble_sleep(enabled: bool) -> Result<(), LcpuError> {
+    let value = if enabled { 0 } else { 1 };
+    let _w = unsafe { super::WakeGuard::acquire() };
+    let aon = crate::pac::LPSYS_AON;
+    aon.reserve0().write(|w| w.set_data(value));
+    if aon.reserve0().read().data() != value {
+        return Err(LcpuError::SleepControlNotAcked);
+    }
+    debug!(
+        "BLE sleep {} (LPSYS_AON.RESERVE0={})",
+        if enabled { "enabled" } else { "disabled" },
+        value
+    );
+    Ok(())
+}
@@ -1,14 +1,43 @@
 //! LCPU subsystem memory map constants (single source of truth).
+//!
+//! [`layout`] collects the regions the HAL hands to the LCPU ROM for one chip
+//! revision, so they can be inspected (e.g. logged next to the linker map)
+//! instead of being read out of several modules:
+//!
+//! ```rust,ignore
+//! use sifli_hal::lcpu::memory_map;
+//!
+//! let layout = memory_map::layout(sifli_hal::syscfg::read_idr().revision());
+//! info!("ROM config at {:#x}", layout.rom_config.start);
+//! ```
+//!
+//! Each layout is checked at compile time to lie inside HPSYS/LPSYS RAM with
+//! no overlaps, and [`Lcpu::power_on`](super::Lcpu::power_on) asserts in
+//! debug builds that the HPSYS mailbox buffers are above the application's
+//! RAM (the linker script must reserve the last 1 KiB of HPSYS RAM).
+
+use crate::syscfg::ChipRevision;
+
+/// HPSYS SRAM (HCPU view).
+pub mod hpsys {
+    pub const RAM_BASE: usize = 0x2000_0000;
+    pub const RAM_SIZE: usize = 512 * 1024;
+}
 
 /// Addresses shared across all chip revisions.
 pub mod shared {
     pub const LPSYS_RAM_BASE: usize = 0x2040_0000;
+    /// LPSYS RAM as seen by HCPU, up to the end of the NVDS buffer.
+    pub const LPSYS_RAM_SIZE: usize = 64 * 1024;
     pub const NVDS_BUFF_START: usize = 0x2040_FE00;
+    pub const NVDS_BUFF_SIZE: usize = 0x200;
     pub const EM_START: usize = 0x2040_8000;
     pub const EM_SIZE: usize = 0x5000;
     pub const HCPU2LCPU_MB_CH1: usize = 0x2007_FE00;
     pub const HCPU2LCPU_MB_CH2: usize = 0x2007_FC00;
     pub const HCPU_TO_LCPU_OFFSET: usize = 0x0A00_0000;
+    /// Size of each mailbox buffer, both directions.
+    pub const MB_BUF_SIZE: usize = 512;
 }
 
 /// Bluetooth RF peripheral addresses.
@@ -21,9 +50,15 @@ pub mod rf {
 /// A3 revision specific addresses.
 pub mod a3 {
     pub const ROM_CONFIG_BASE: usize = 0x2040_FDC0;
+    /// ROM config area size (ends at `shared::NVDS_BUFF_START`).
+    pub const ROM_CONFIG_SIZE: usize = 0x40;
+    /// LCPU firmware image region at `shared::LPSYS_RAM_BASE`.
+    pub const LCPU_CODE_SIZE: usize = 24 * 1024;
     pub const LCPU2HCPU_CH1: usize = 0x2040_5C00;
     pub const LCPU2HCPU_CH2: usize = 0x2040_5E00;
     pub const PATCH_CODE_START: usize = 0x2040_6000;
+    /// Patch area size, including the record at `PATCH_RECORD_ADDR`.
+    pub const PATCH_SIZE: usize = 8 * 1024;
     pub const PATCH_RECORD_ADDR: usize = 0x2040_7F00;
     pub const RWIP_PROG_DELAY: usize = 0x2040_FA94;
     pub const G_ROM_CONFIG: usize = 0x2040_E48C;
@@ -32,9 +67,173 @@ pub mod a3 {
 /// Letter Series (A4/B4) specific addresses.
 pub mod letter {
     pub const ROM_CONFIG_BASE: usize = 0x2040_2A00;
+    /// ROM config area size (shares the 512-byte LCPU2HCPU CH2 mailbox buffer).
+    pub const ROM_CONFIG_SIZE: usize = 0x200;
     pub const LCPU2HCPU_CH1: usize = 0x2040_2800;
     pub const LCPU2HCPU_CH2: usize = 0x2040_2A00;
     pub const PATCH_BUF_START: usize = 0x2040_5000;
+    pub const PATCH_BUF_SIZE: usize = 0x3000;
     pub const PATCH_CODE_START: usize = 0x2040_500C;
     pub const PATCH_CODE_START_LCPU: usize = 0x0040_500C;
 }
+
+/// An address range in the HCPU view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Region {
+    pub start: usize,
+    pub size: usize,
+}
+
+impl Region {
+    const fn new(start: usize, size: usize) -> Self {
+        Self { start, size }
+    }
+
+    /// One past the last byte.
+    pub const fn end(&self) -> usize {
+        self.start + self.size
+    }
+
+    /// Whether `other` lies entirely inside `self`.
+    pub const fn contains(&self, other: &Region) -> bool {
+        other.start >= self.start && other.end() <= self.end()
+    }
+
+    /// Whether the two ranges share any byte.
+    pub const fn overlaps(&self, other: &Region) -> bool {
+        self.start < other.end() && other.start < self.end()
+    }
+}
+
+/// The LCPU-related memory regions for one chip revision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MemoryLayout {
+    /// ROM configuration block. On the Letter Series it reuses the
+    /// LCPU->HCPU CH2 mailbox buffer.
+    pub rom_config: Region,
+    /// HCPU->LCPU mailbox buffers (CH1, CH2), in HPSYS RAM.
+    pub hcpu2lcpu: [Region; 2],
+    /// LCPU->HCPU mailbox buffers (CH1, CH2), in LPSYS RAM.
+    pub lcpu2hcpu: [Region; 2],
+    /// LCPU firmware image loaded by the HAL; `None` when the controller
+    /// runs from ROM (Letter Series).
+    pub lcpu_code: Option<Region>,
+    /// Controller patch area.
+    pub patch: Region,
+    /// NVDS buffer.
+    pub nvds: Region,
+}
+
+/// Memory layout used for `revision`.
+///
+/// Revisions that are not Letter Series (including unrecognized ones) get
+/// the A3 layout, matching what the boot code does.
+pub const fn layout(revision: ChipRevision) -> MemoryLayout {
+    let hcpu2lcpu = [
+        Region::new(shared::HCPU2LCPU_MB_CH1, shared::MB_BUF_SIZE),
+        Region::new(shared::HCPU2LCPU_MB_CH2, shared::MB_BUF_SIZE),
+    ];
+    let nvds = Region::new(shared::NVDS_BUFF_START, shared::NVDS_BUFF_SIZE);
+    match revision {
+        ChipRevision::A4 | ChipRevision::B4 => MemoryLayout {
+            rom_config: Region::new(letter::ROM_CONFIG_BASE, letter::ROM_CONFIG_SIZE),
+            hcpu2lcpu,
+            lcpu2hcpu: [
+                Region::new(letter::LCPU2HCPU_CH1, shared::MB_BUF_SIZE),
+                Region::new(letter::LCPU2HCPU_CH2, shared::MB_BUF_SIZE),
+            ],
+            lcpu_code: None,
+            patch: Region::new(letter::PATCH_BUF_START, letter::PATCH_BUF_SIZE),
+            nvds,
+        },
+        _ => MemoryLayout {
+            rom_config: Region::new(a3::ROM_CONFIG_BASE, a3::ROM_CONFIG_SIZE),
+            hcpu2lcpu,
+            lcpu2hcpu: [
+                Region::new(a3::LCPU2HCPU_CH1, shared::MB_BUF_SIZE),
+                Region::new(a3::LCPU2HCPU_CH2, shared::MB_BUF_SIZE),
+            ],
+            lcpu_code: Some(Region::new(shared::LPSYS_RAM_BASE, a3::LCPU_CODE_SIZE)),
+            patch: Region::new(a3::PATCH_CODE_START, a3::PATCH_SIZE),
+            nvds,
+        },
+    }
+}
+
+const HPSYS_RAM: Region = Region::new(hpsys::RAM_BASE, hpsys::RAM_SIZE);
+const LPSYS_RAM: Region = Region::new(shared::LPSYS_RAM_BASE, shared::LPSYS_RAM_SIZE);
+
+impl MemoryLayout {
+    /// Whether every region lies in the RAM it is declared in and the LPSYS
+    /// buffers do not overlap each other.
+    ///
+    /// Two overlaps are by design and allowed: the Letter Series ROM config
+    /// shares the CH2 buffer, and the A3 code region spans the LCPU->HCPU
+    /// buffers, which the LCPU image itself places.
+    pub const fn is_consistent(&self) -> bool {
+        if !HPSYS_RAM.contains(&self.hcpu2lcpu[0]) || !HPSYS_RAM.contains(&self.hcpu2lcpu[1]) {
+            return false;
+        }
+        if self.hcpu2lcpu[0].overlaps(&self.hcpu2lcpu[1]) {
+            return false;
+        }
+        if let Some(code) = self.lcpu_code {
+            if !LPSYS_RAM.contains(&code) {
+                return false;
+            }
+        }
+
+        let lpsys = [
+            self.rom_config,
+            self.lcpu2hcpu[0],
+            self.lcpu2hcpu[1],
+            self.patch,
+            self.nvds,
+        ];
+        let mut i = 0;
+        while i < lpsys.len() {
+            if !LPSYS_RAM.contains(&lpsys[i]) {
+                return false;
+            }
+            let mut j = i + 1;
+            while j < lpsys.len() {
+                let shared_ch2 = i == 0 && j == 2 && lpsys[0].start == lpsys[2].start;
+                if !shared_ch2 && lpsys[i].overlaps(&lpsys[j]) {
+                    return false;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        true
+    }
+}
+
+const _: () = {
+    ::core::assert!(layout(ChipRevision::A3OrEarlier(0x03)).is_consistent());
+    ::core::assert!(layout(ChipRevision::B4).is_consistent());
+};
+
+/// Debug-build check that the application's RAM (as laid out by the linker
+/// script) ends below the HCPU->LCPU mailbox buffers.
+///
+/// `memory.x` must end `RAM` at `0x2007_FC00`; otherwise the stack grows
+/// into the mailbox and corrupts IPC traffic.
+pub(crate) fn debug_check_linker_layout() {
+    #[cfg(all(debug_assertions, target_os = "none"))]
+    {
+        extern "C" {
+            // Provided by cortex-m-rt: top of the `RAM` region by default.
+            static _stack_start: u32;
+        }
+        let ram_end = unsafe { core::ptr::addr_of!(_stack_start) } as usize;
+        ::core::assert!(
+            ram_end <= shared::HCPU2LCPU_MB_CH2,
+            "lcpu: RAM ends at {:#x}, overlapping the IPC mailbox at {:#x}; reserve the last 1 KiB of HPSYS RAM in memory.x",
+            ram_end,
+            shared::HCPU2LCPU_MB_CH2
+        );
+    }
+}
@@ -17,9 +17,13 @@ pub mod ram;
 pub use ram::LpsysRam;
 
 mod config;
-pub use config::{ActConfig, BleConfig, BootConfig, ControllerConfig, EmConfig, RomConfig};
+pub use config::{
+    ActConfig, AddressType, BleConfig, BootConfig, ConfigError, ControllerConfig, EmConfig,
+    RomConfig,
+};
 
 pub(crate) mod controller;
+pub use controller::SchedStats;
 mod nvds;
 
 pub mod bt_rf_cal;
@@ -31,13 +35,19 @@ use crate::syscfg;
 use crate::Peripheral;
 use crate::{lpaon, patch, rcc};
 
+// Extracted from the SiFli SDK by `scripts/extract_lcpu_bins.py`.
 #[cfg(feature = "sf32lb52x-lcpu")]
 mod sf32lb52x_lcpu_data {
+    /// C name: `g_lcpu_bin` (`example/rom_bin/lcpu_general_ble_img/lcpu_52x.c`)
     pub const FIRMWARE: &[u8] = include_bytes!("../../data/sf32lb52x/lcpu/lcpu_firmware.bin");
+    /// C name: `g_lcpu_patch_list` (`drivers/cmsis/sf32lb52x/lcpu_patch.c`)
     pub const PATCH_A3_LIST: &[u8] = include_bytes!("../../data/sf32lb52x/lcpu/patch_a3_list.bin");
+    /// C name: `g_lcpu_patch_bin` (`drivers/cmsis/sf32lb52x/lcpu_patch.c`)
     pub const PATCH_A3_BIN: &[u8] = include_bytes!("../../data/sf32lb52x/lcpu/patch_a3_bin.bin");
+    /// C name: `g_lcpu_patch_list` (`drivers/cmsis/sf32lb52x/lcpu_patch_rev_b.c`)
     pub const PATCH_LETTER_LIST: &[u8] =
         include_bytes!("../../data/sf32lb52x/lcpu/patch_letter_list.bin");
+    /// C name: `g_lcpu_patch_bin` (`drivers/cmsis/sf32lb52x/lcpu_patch_rev_b.c`)
     pub const PATCH_LETTER_BIN: &[u8] =
         include_bytes!("../../data/sf32lb52x/lcpu/patch_letter_bin.bin");
 }
@@ -48,15 +58,15 @@ mod sf32lb52x_lcpu_data {
 
 /// LCPU power-on configuration.
 #[derive(Debug, Clone, Copy)]
-pub struct LcpuConfig {
+pub struct LcpuConfig<'a> {
     /// Boot-time configuration (firmware, patches, ROM parameters).
-    pub boot: BootConfig,
+    pub boot: BootConfig<'a>,
 
     /// BLE-specific configuration (post-boot controller params + BD address).
     pub ble: BleConfig,
 }
 
-impl LcpuConfig {
+impl<'a> LcpuConfig<'a> {
     /// Create a new config with all options unset.
     pub const fn new() -> Self {
         Self {
@@ -65,6 +75,15 @@ impl LcpuConfig {
         }
     }
 
+    /// Use a custom LCPU firmware image (A3 and earlier).
+    ///
+    /// The image only needs to outlive the `power_on` call; it is size-checked
+    /// before the LCPU is reset and then copied into LPSYS RAM.
+    pub const fn firmware(mut self, firmware: &'a [u8]) -> Self {
+        self.boot.firmware = Some(firmware);
+        self
+    }
+
     /// Skip LPSYS HCLK frequency check during image loading.
     pub const fn skip_frequency_check(mut self, skip: bool) -> Self {
         self.boot.skip_frequency_check = skip;
@@ -92,11 +111,70 @@ impl LcpuConfig {
     /// Set public BD address.
     pub const fn bd_addr(mut self, addr: [u8; 6]) -> Self {
         self.ble.bd_addr = addr;
+        self.ble.addr_type = AddressType::Public;
+        self
+    }
+
+    /// Use a fixed random static address instead of a public one, see
+    /// [`AddressType::RandomStatic`] for the required format.
+    pub const fn random_static_addr(mut self, addr: [u8; 6]) -> Self {
+        self.ble.bd_addr = addr;
+        self.ble.addr_type = AddressType::RandomStatic;
         self
     }
+
+    /// Report each boot step to `f`, e.g. to show progress or remember the
+    /// last step reached before a hang. See [`BootProgress`].
+    pub const fn on_progress(mut self, f: fn(BootProgress)) -> Self {
+        self.boot.on_progress = Some(f);
+        self
+    }
+
+    /// Check that this config can boot the LCPU on `revision`, without
+    /// writing LPSYS RAM or resetting the LCPU.
+    ///
+    /// Covers the controller parameters, the firmware image (present where
+    /// the revision needs one, and within the code region), the patch for
+    /// the revision (within its patch region) and, unless
+    /// [`skip_frequency_check`](Self::skip_frequency_check) is set, that the
+    /// LPSYS HCLK can be read so it can be lowered for loading. That check
+    /// briefly wakes LPSYS (the LCPU keeps running).
+    ///
+    /// [`Lcpu::power_on`] runs this first, so a misconfiguration fails before
+    /// anything is overwritten. Call it directly to validate e.g. an OTA
+    /// image ahead of time.
+    pub fn preflight(&self, revision: syscfg::ChipRevision) -> Result<(), LcpuError> {
+        self.ble.validate()?;
+        self.ble.controller.validate_for(revision)?;
+
+        let rom_controller = revision.supports(syscfg::ChipFeature::RomBleController);
+        match self.boot.firmware {
+            Some(firmware) => ram::check_image_size(firmware)?,
+            None if !rom_controller => return Err(LcpuError::FirmwareMissing),
+            None => {}
+        }
+
+        let patch = if revision.is_letter_series() {
+            self.boot.patch_letter
+        } else {
+            self.boot.patch_a3
+        };
+        if let Some(data) = patch {
+            patch::check(data.list, data.bin, revision)?;
+        }
+
+        if !self.boot.skip_frequency_check {
+            let _w = unsafe { WakeGuard::acquire() };
+            if rcc::get_lpsys_hclk_freq().is_none() {
+                error!("LCPU preflight: LPSYS HCLK frequency unknown");
+                return Err(LcpuError::RccError);
+            }
+        }
+        Ok(())
+    }
 }
 
-impl Default for LcpuConfig {
+impl<'a> Default for LcpuConfig<'a> {
     fn default() -> Self {
         #[allow(unused_mut)]
         let mut cfg = Self::new();
@@ -119,6 +197,62 @@ impl Default for LcpuConfig {
     }
 }
 
+/// Step of the LCPU/BLE boot sequence.
+///
+/// Listed in boot order; steps that do not apply to the chip revision or
+/// config (image install on Letter Series, RF calibration when disabled;
+/// NVDS, warmup and controller init for [`Lcpu::power_on`]) are not
+/// reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BootStep {
+    /// Write NVDS (BD address, sleep clock) to shared memory.
+    Nvds,
+    /// Reset the LCPU and hold it halted.
+    ResetHalt,
+    /// Write the ROM configuration block.
+    RomConfig,
+    /// Lower LPSYS HCLK to the loading limit.
+    FrequencyCheck,
+    /// Copy the firmware image into LPSYS RAM (A3 and earlier).
+    ImageInstall,
+    /// Program the LCPU start vector.
+    StartVector,
+    /// Install the ROM patches.
+    PatchInstall,
+    /// Run RF calibration.
+    RfCal,
+    /// Release the LCPU.
+    Release,
+    /// Wait for the controller's warmup event.
+    Warmup,
+    /// Apply the post-boot controller configuration.
+    ControllerInit,
+}
+
+/// Boot step report passed to [`LcpuConfig::on_progress`].
+///
+/// `addr` and `len` describe the memory the step writes, or are 0 when it
+/// only touches registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BootProgress {
+    /// The step about to run.
+    pub step: BootStep,
+    /// Target address in HPSYS view.
+    pub addr: usize,
+    /// Size in bytes.
+    pub len: usize,
+}
+
+/// Log `step` and pass it to the user callback, if any.
+fn progress(config: &LcpuConfig<'_>, step: BootStep, addr: usize, len: usize) {
+    debug!("LCPU boot: {:?} at 0x{:08X} ({} bytes)", step, addr, len);
+    if let Some(f) = config.boot.on_progress {
+        f(BootProgress { step, addr, len });
+    }
+}
+
 /// Patch data (entry list + code binary), matching `g_lcpu_patch_list` / `g_lcpu_patch_bin` in SDK.
 #[derive(Debug, Clone, Copy)]
 pub struct PatchData {
@@ -170,6 +304,19 @@ pub enum LcpuError {
 
     /// Error reading BT warmup event from IPC.
     WarmupReadError,
+
+    /// The sleep control word did not read back as written (LPSYS asleep or
+    /// not clocked).
+    SleepControlNotAcked,
+
+    /// Invalid BLE controller configuration.
+    InvalidConfig(ConfigError),
+}
+
+impl From<ConfigError> for LcpuError {
+    fn from(err: ConfigError) -> Self {
+        Self::InvalidConfig(err)
+    }
 }
 
 impl From<ram::Error> for LcpuError {
@@ -290,23 +437,39 @@ impl Lcpu {
     /// ```
     pub async fn ble_power_on<R>(
         &self,
-        config: &LcpuConfig,
+        config: &LcpuConfig<'_>,
         dma_ch: impl Peripheral<P = impl Channel>,
         hci_rx: &mut R,
     ) -> Result<(), LcpuError>
     where
         R: embedded_io_async::Read,
     {
+        config.preflight(syscfg::read_idr().revision())?;
+
         // 0. Write NVDS to LCPU shared memory (SDK: bt_stack_nvds_init)
         //    Must complete before LCPU boot; ROM reads this to initialize BT parameters.
         //    Wake LCPU first to ensure shared memory is accessible.
         {
+            progress(
+                config,
+                BootStep::Nvds,
+                memory_map::shared::NVDS_BUFF_START,
+                memory_map::shared::NVDS_BUFF_SIZE,
+            );
             let _w = unsafe { WakeGuard::acquire() };
-            nvds::write_default(&config.ble.bd_addr, config.boot.rom.enable_lxt);
+            let public_addr = match config.ble.addr_type {
+                AddressType::Public => Some(&config.ble.bd_addr),
+                AddressType::RandomStatic => None,
+            };
+            nvds::write_default(
+                public_addr,
+                config.boot.rom.enable_lxt,
+                config.ble.controller.sleep_clock_accuracy_ppm,
+            );
         }
 
-        // 1. Execute standard boot sequence
-        self.power_on(config, dma_ch)?;
+        // 1. Execute standard boot sequence (already preflighted above)
+        self.boot(config, dma_ch)?;
 
         // 2. Warmup + controller init (scoped wake)
         //    SDK lcpu_power_on() releases HP2LP_REQ at the end, allowing LCPU
@@ -315,10 +478,12 @@ impl Lcpu {
             let _w = unsafe { WakeGuard::acquire() };
 
             // Read and discard warmup event
+            progress(config, BootStep::Warmup, 0, 0);
             consume_warmup_event(hci_rx).await?;
 
             // Controller initialization (SDK: bluetooth_init)
             //    Includes: sleep timing + MAC clock + CFO tracking + sleep control
+            progress(config, BootStep::ControllerInit, 0, 0);
             controller::init(&config.ble.controller);
         }
         // _w drops → cancel_lcpu_active_request, LCPU can sleep between BLE events
@@ -329,66 +494,101 @@ impl Lcpu {
     /// Blocking LCPU boot sequence.
     pub fn power_on(
         &self,
-        config: &LcpuConfig,
+        config: &LcpuConfig<'_>,
         dma_ch: impl Peripheral<P = impl Channel>,
     ) -> Result<(), LcpuError> {
+        // 0. Validate the config, image and patch before touching the
+        //    running LCPU.
+        config.preflight(syscfg::read_idr().revision())?;
+        self.boot(config, dma_ch)
+    }
+
+    /// [`power_on`](Self::power_on) after [`LcpuConfig::preflight`] passed.
+    fn boot(
+        &self,
+        config: &LcpuConfig<'_>,
+        dma_ch: impl Peripheral<P = impl Channel>,
+    ) -> Result<(), LcpuError> {
+        memory_map::debug_check_linker_layout();
+
         // 1. Wake LCPU (guard ensures cancel on early return).
-        debug!("Step 1: Waking up LCPU");
+        debug!("LCPU boot: waking LCPU");
         let _w = unsafe { WakeGuard::acquire() };
 
         // 2. Reset and halt LCPU.
-        debug!("Step 2: Resetting and halting LCPU");
+        progress(config, BootStep::ResetHalt, 0, 0);
         self.reset_and_halt_lcpu()?;
 
         // 3. Configure ROM parameters (bf0_lcpu_init.c:168).
-        debug!("Step 3: Configuring ROM parameters");
+        progress(
+            config,
+            BootStep::RomConfig,
+            ram::RomControlBlock::address(),
+            ram::RomControlBlock::region_size(),
+        );
         ram::rom_config(&config.boot.rom, &config.ble.controller)?;
 
         // 4. Enforce frequency limit while loading (bf0_lcpu_init.c:170-176).
         // If frequency exceeds 24MHz, automatically reduce it.
         let _original_freq_hz = if !config.boot.skip_frequency_check {
-            debug!("Step 4: Ensuring LCPU frequency ≤ 24MHz during loading");
+            progress(config, BootStep::FrequencyCheck, 0, 0);
             rcc::ensure_safe_lcpu_frequency().map_err(|_| LcpuError::RccError)?
         } else {
-            warn!("Step 4: Skipping frequency check (as requested by config)");
+            warn!("LCPU boot: skipping frequency check (as requested by config)");
             0 // Dummy value when skipped
         };
 
         // 5. Install image for A3 and earlier (bf0_lcpu_init.c:178-182).
-        let is_letter = syscfg::read_idr().revision().is_letter_series();
-        if !is_letter {
-            debug!("Step 5: Installing LCPU firmware image (A3/earlier)");
-
+        let rom_controller = syscfg::read_idr()
+            .revision()
+            .supports(syscfg::ChipFeature::RomBleController);
+        if !rom_controller {
             if let Some(firmware) = config.boot.firmware {
+                progress(
+                    config,
+                    BootStep::ImageInstall,
+                    LpsysRam::CODE_START,
+                    firmware.len(),
+                );
                 ram::img_install(firmware)?;
             } else {
                 error!("Firmware required for A3 and earlier revisions");
                 return Err(LcpuError::FirmwareMissing);
             }
         } else {
-            debug!("Step 5: Skipping image install (Letter Series, firmware in ROM)");
+            debug!("LCPU boot: skipping image install (Letter Series, firmware in ROM)");
         }
 
         // 6. Configure LCPU start address (bf0_lcpu_init.c:184).
-        debug!(
-            "Step 6: Configuring LCPU start address (0x{:08X})",
-            LpsysRam::CODE_START
-        );
+        progress(config, BootStep::StartVector, LpsysRam::CODE_START, 8);
         let (sp, pc) = read_start_vector_from_mem();
         lpaon::configure_lcpu_start(sp, pc);
 
         // 7. Install patches and perform RF calibration (bf0_lcpu_init.c:185).
-        debug!("Step 7: Installing patches and RF calibration");
         install_patch_and_calibrate(config, dma_ch)?;
 
         // 8. Release LCPU to run (bf0_lcpu_init.c:186).
-        debug!("Step 8: Releasing LCPU to run");
+        progress(config, BootStep::Release, 0, 0);
         self.release_lcpu()?;
 
         // _w drops here → cancel_lcpu_active_request, allow LP to enter low power.
         Ok(())
     }
 
+    /// BLE shutdown, the counterpart of [`ble_power_on`](Self::ble_power_on).
+    ///
+    /// Halts the LCPU, then clears the ROM configuration area and releases
+    /// the PTC2 clock enabled by controller init. The HCI IPC queue is not
+    /// touched here; close it first so no task waits on the halted LCPU.
+    pub fn ble_power_off(&self) -> Result<(), LcpuError> {
+        self.power_off()?;
+
+        let _w = unsafe { WakeGuard::acquire() };
+        controller::deinit();
+        ram::clear_rom_config();
+        Ok(())
+    }
+
     /// Blocking shutdown: reset and hold CPUWAIT.
     pub fn power_off(&self) -> Result<(), LcpuError> {
         info!("Powering off LCPU");
@@ -459,7 +659,7 @@ fn read_start_vector_from_mem() -> (u32, u32) {
 /// Steps 2-4 are handled inside [`bt_rf_cal::bt_rf_cal`]; step 3 is a TODO
 /// because OSLO calibration (which touches GPADC) is not yet implemented.
 fn install_patch_and_calibrate(
-    config: &LcpuConfig,
+    config: &LcpuConfig<'_>,
     dma_ch: impl Peripheral<P = impl Channel>,
 ) -> Result<(), LcpuError> {
     // SDK lcpu_ble_patch_install — step 1: patch install
@@ -477,6 +677,12 @@ fn install_patch_and_calibrate(
             data.list.len(),
             data.bin.len()
         );
+        let code_start = if syscfg::read_idr().revision().is_letter_series() {
+            ram::PatchRegion::LETTER_CODE_START
+        } else {
+            ram::PatchRegion::A3_CODE_START
+        };
+        progress(config, BootStep::PatchInstall, code_start, data.bin.len());
         patch::install(data.list, data.bin)?;
     } else {
         warn!("No patch data provided, skipping patch installation");
@@ -484,7 +690,7 @@ fn install_patch_and_calibrate(
 
     // SDK lcpu_ble_patch_install — steps 2-4: bt_rf_cal + adc_resume + EM clear
     if !config.boot.disable_rf_cal {
-        debug!("Performing RF calibration");
+        progress(config, BootStep::RfCal, 0, 0);
         bt_rf_cal::bt_rf_cal(dma_ch);
     } else {
         warn!("RF calibration disabled by config");
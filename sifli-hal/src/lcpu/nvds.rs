@@ -11,6 +11,7 @@ const NVDS_PATTERN: u32 = 0x4E56_4453; // "NVDS"
 
 mod tag {
     pub const BD_ADDRESS: u8 = 0x01;
+    pub const LPCLK_DRIFT: u8 = 0x07;
     pub const PRE_WAKEUP_TIME: u8 = 0x0D;
     pub const EXT_WAKEUP_ENABLE: u8 = 0x12;
     pub const SCHEDULING: u8 = 0x15;
@@ -30,7 +31,10 @@ struct NvdsHeader {
 ///
 /// Must be called before LCPU boot (before `power_on()`), with LCPU SRAM
 /// accessible (i.e. after `wake_lcpu()`).
-pub(crate) fn write_default(bd_addr: &[u8; 6], use_lxt: bool) {
+///
+/// Without `bd_addr` the BD address tag is left out and the ROM keeps its
+/// built-in public address (used with a random static address).
+pub(crate) fn write_default(bd_addr: Option<&[u8; 6]>, use_lxt: bool, sca_ppm: u16) {
     let mut buf = [0u8; 64];
     let mut pos = 0;
 
@@ -49,15 +53,23 @@ pub(crate) fn write_default(bd_addr: &[u8; 6], use_lxt: bool) {
     pos += 6;
 
     // Tag 0x01: BD address (6 bytes)
-    buf[pos] = tag::BD_ADDRESS;
-    buf[pos + 1] = 0x06;
-    buf[pos + 2..pos + 8].copy_from_slice(bd_addr);
-    pos += 8;
+    if let Some(bd_addr) = bd_addr {
+        buf[pos] = tag::BD_ADDRESS;
+        buf[pos + 1] = 0x06;
+        buf[pos + 2..pos + 8].copy_from_slice(bd_addr);
+        pos += 8;
+    }
 
     // Tag 0x15: scheduling = 1
     buf[pos..pos + 3].copy_from_slice(&[tag::SCHEDULING, 0x01, 0x01]);
     pos += 3;
 
+    // Tag 0x07: low power clock drift in ppm (u16 LE). The ROM falls back to
+    // 500 ppm when absent.
+    let [lo, hi] = sca_ppm.to_le_bytes();
+    buf[pos..pos + 4].copy_from_slice(&[tag::LPCLK_DRIFT, 0x02, lo, hi]);
+    pos += 4;
+
     // Write header + TLV data to shared memory
     unsafe {
         let dst = NVDS_BUFF_START as *mut u8;
@@ -70,8 +82,11 @@ pub(crate) fn write_default(bd_addr: &[u8; 6], use_lxt: bool) {
         core::ptr::copy_nonoverlapping(buf.as_ptr(), dst.add(8), pos);
     }
 
-    debug!(
-        "NVDS written: {} bytes, bd_addr={:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}",
-        pos, bd_addr[0], bd_addr[1], bd_addr[2], bd_addr[3], bd_addr[4], bd_addr[5]
-    );
+    match bd_addr {
+        Some(a) => debug!(
+            "NVDS written: {} bytes, bd_addr={:02X}:{:02X}:{:02X}:{:02X}:{:02X}:{:02X}, sca={}ppm",
+            pos, a[0], a[1], a[2], a[3], a[4], a[5], sca_ppm
+        ),
+        None => debug!("NVDS written: {} bytes, ROM bd_addr, sca={}ppm", pos, sca_ppm),
+    }
 }
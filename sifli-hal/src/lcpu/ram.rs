@@ -1,7 +1,7 @@
 //! LCPU memory management: ROM configuration write and firmware image loading.
 
 use super::config::{ActConfig, EmConfig, RomConfig};
-use crate::syscfg;
+use crate::syscfg::{self, ChipFeature};
 use core::{mem, ptr};
 
 //=============================================================================
@@ -37,10 +37,10 @@ pub struct RomControlBlock {
     pub bt_config: BtRomConfig, // 0xAC (172)
 
     // Padding to reach 0xC8 (200).
-    // BtRomConfig size is 21 bytes.
-    // 172 + 21 = 193.
-    // 200 - 193 = 7 bytes.
-    pub _pad3: [u8; 7],
+    // BtRomConfig has 21 bytes of fields, padded to 24 by its u32 alignment.
+    // 172 + 24 = 196.
+    // 200 - 196 = 4 bytes.
+    pub _pad3: [u8; 4],
 
     /// HCPU to LCPU IPC address (Letter Series only).
     pub hcpu_ipc_addr: u32, // 0xC8 (200)
@@ -84,6 +84,12 @@ impl RomControlBlock {
     /// Magic number expected by ROM.
     pub const MAGIC: u32 = 0x4545_7878;
 
+    /// ROM config area size for A3 and earlier.
+    pub const SIZE_A3: usize = super::memory_map::a3::ROM_CONFIG_SIZE;
+
+    /// ROM config area size for Letter Series (A4/B4).
+    pub const SIZE_LETTER: usize = super::memory_map::letter::ROM_CONFIG_SIZE;
+
     /// Get the configuration base address for the given chip revision.
     pub fn address() -> usize {
         if syscfg::read_idr().revision().is_letter_series() {
@@ -92,8 +98,39 @@ impl RomControlBlock {
             Self::ADDR_A3
         }
     }
+
+    /// Get the configuration area size for the given chip revision.
+    pub fn region_size() -> usize {
+        if syscfg::read_idr().revision().is_letter_series() {
+            Self::SIZE_LETTER
+        } else {
+            Self::SIZE_A3
+        }
+    }
 }
 
+// Layout checks against SDK `lcpu_config_type_int.h`. EM/ACT blocks live inside
+// `_pad2` and must not overlap each other or `bt_config`.
+const _: () = {
+    ::core::assert!(mem::offset_of!(RomControlBlock, wdt_time) == 0x0C);
+    ::core::assert!(mem::offset_of!(RomControlBlock, wdt_status) == 0x10);
+    ::core::assert!(mem::offset_of!(RomControlBlock, wdt_clk) == 0x18);
+    ::core::assert!(mem::offset_of!(RomControlBlock, is_xtal_enable) == 0x1A);
+    ::core::assert!(mem::offset_of!(RomControlBlock, is_rccal_in_l) == 0x1B);
+    ::core::assert!(mem::offset_of!(RomControlBlock, _pad2) == 0x1C);
+    ::core::assert!(mem::offset_of!(RomControlBlock, _pad2) <= EmConfig::ROM_OFFSET);
+    ::core::assert!(EmConfig::ROM_OFFSET + mem::size_of::<EmConfig>() <= ActConfig::ROM_OFFSET);
+    ::core::assert!(
+        ActConfig::ROM_OFFSET + mem::size_of::<ActConfig>()
+            <= mem::offset_of!(RomControlBlock, bt_config)
+    );
+    ::core::assert!(mem::size_of::<BtRomConfig>() == 24);
+    ::core::assert!(mem::offset_of!(RomControlBlock, bt_config) == 172);
+    ::core::assert!(mem::offset_of!(RomControlBlock, hcpu_ipc_addr) == 200);
+    ::core::assert!(mem::size_of::<RomControlBlock>() == 204);
+    ::core::assert!(mem::size_of::<RomControlBlock>() <= RomControlBlock::SIZE_LETTER);
+};
+
 /// LCPU Patch memory layout (HCPU view).
 ///
 /// Defines addresses for Patch code and buffers for different chip revisions.
@@ -118,7 +155,7 @@ impl PatchRegion {
 
     /// Total patch area size for A3.
     /// Reference: `SiFli-SDK/drivers/cmsis/sf32lb52x/mem_map.h:300`
-    pub const A3_TOTAL_SIZE: usize = 8 * 1024;
+    pub const A3_TOTAL_SIZE: usize = super::memory_map::a3::PATCH_SIZE;
 
     // ===== Letter Series (A4/B4) =====
 
@@ -137,7 +174,7 @@ impl PatchRegion {
 
     /// Patch buffer size.
     /// Reference: `SiFli-SDK/drivers/cmsis/sf32lb52x/mem_map.h:337`
-    pub const LETTER_BUF_SIZE: usize = 0x3000; // 12KB
+    pub const LETTER_BUF_SIZE: usize = super::memory_map::letter::PATCH_BUF_SIZE; // 12KB
 
     /// Patch code usable size.
     /// Reference: `SiFli-SDK/drivers/cmsis/sf32lb52x/mem_map.h:338`
@@ -160,7 +197,7 @@ impl LpsysRam {
     pub const BASE: usize = super::memory_map::shared::LPSYS_RAM_BASE;
 
     /// LPSYS RAM size for A3 and earlier revisions (24KB).
-    pub const SIZE: usize = 24 * 1024;
+    pub const SIZE: usize = super::memory_map::a3::LCPU_CODE_SIZE;
 
     /// LCPU code start address.
     pub const CODE_START: usize = Self::BASE;
@@ -172,7 +209,7 @@ pub struct IpcRegion;
 
 impl IpcRegion {
     /// Mailbox buffer size for CH1 (bytes).
-    pub const BUF_SIZE: usize = 512;
+    pub const BUF_SIZE: usize = super::memory_map::shared::MB_BUF_SIZE;
 
     /// HCPU -> LCPU (CH1) TX buffer start, HCPU view.
     pub const HCPU_TO_LCPU_CH1: usize = RomControlBlock::HCPU2LCPU_MB_CH1_BUF_START_ADDR;
@@ -236,18 +273,41 @@ pub enum Error {
     ImageTooLarge { size_bytes: usize, max_bytes: usize },
     /// Invalid chip revision.
     InvalidRevision { revid: u8 },
+    /// ROM config write would exceed the config area of the detected revision.
+    RomConfigOutOfBounds {
+        offset: usize,
+        size_bytes: usize,
+        region_bytes: usize,
+    },
 }
 
 //=============================================================================
 // Public Functions
 //=============================================================================
 
+/// Clear the ROM configuration area (magic included), so a halted LCPU
+/// cannot boot from stale parameters.
+///
+/// LPSYS must be awake.
+pub(crate) fn clear_rom_config() {
+    unsafe {
+        ptr::write_bytes(
+            RomControlBlock::address() as *mut u8,
+            0,
+            RomControlBlock::region_size(),
+        )
+    };
+}
+
 /// Configure LCPU ROM parameters.
 ///
 /// Replaces `lcpu_rom_config`.
 pub fn rom_config(config: &RomConfig, ctrl: &super::config::ControllerConfig) -> Result<(), Error> {
     let base = RomControlBlock::address();
-    let is_letter = syscfg::read_idr().revision().is_letter_series();
+    let region = RomControlBlock::region_size();
+    let is_letter = syscfg::read_idr()
+        .revision()
+        .supports(ChipFeature::ExtendedRomConfig);
 
     // Calculate size to clear/write.
     // A3: 0x40 (64 bytes)
@@ -257,6 +317,7 @@ pub fn rom_config(config: &RomConfig, ctrl: &super::config::ControllerConfig) ->
     } else {
         0x40 // LCPU_CONFIG_ROM_SIZE
     };
+    check_rom_bounds(0, size, region)?;
 
     debug!(
         "Initializing LCPU ROM config: base=0x{:08X}, size={} (Letter Series: {})",
@@ -290,7 +351,7 @@ pub fn rom_config(config: &RomConfig, ctrl: &super::config::ControllerConfig) ->
             // (without SLEEP_MODE/SLEEP_ENABLED in bit_valid, ROM uses internal
             // defaults which may enable sleep, causing 0x3E connection timeouts
             // since we lack ble_standby_sleep_after_handler).
-            let bt_cfg = BtRomConfig {
+            let mut bt_cfg = BtRomConfig {
                 bit_valid: (1 << 10)  // is_fpga
                     | (1 << 7)        // rc_cycle
                     | (1 << 6)        // xtal_enabled
@@ -307,34 +368,79 @@ pub fn rom_config(config: &RomConfig, ctrl: &super::config::ControllerConfig) ->
                 is_fpga: 0,
                 ..Default::default()
             };
+            if let Some(half_slots) = ctrl.max_sleep_time_half_slots() {
+                bt_cfg.bit_valid |= 1 << 0; // max_sleep_time
+                bt_cfg.max_sleep_time = half_slots;
+            }
 
             // Write BT config struct
             ptr::write_volatile(&mut block.bt_config, bt_cfg);
 
             // EM buffer configuration
             if let Some(ref em) = config.em_config {
+                check_rom_bounds(EmConfig::ROM_OFFSET, mem::size_of::<EmConfig>(), region)?;
                 let dst = (base + EmConfig::ROM_OFFSET) as *mut EmConfig;
                 ptr::write_volatile(dst, *em);
             }
 
             // Activity configuration
             if let Some(ref act) = config.act_config {
+                check_rom_bounds(ActConfig::ROM_OFFSET, mem::size_of::<ActConfig>(), region)?;
                 let dst = (base + ActConfig::ROM_OFFSET) as *mut ActConfig;
                 ptr::write_volatile(dst, *act);
             }
+        } else if config.em_config.is_some() || config.act_config.is_some() {
+            // EM/ACT offsets are only defined for the Letter Series ROM layout.
+            debug!("A3 ROM layout: ignoring EM/ACT config");
         }
     }
 
     Ok(())
 }
 
+/// Ensure `[offset, offset + size_bytes)` lies within the ROM config area.
+fn check_rom_bounds(offset: usize, size_bytes: usize, region_bytes: usize) -> Result<(), Error> {
+    if offset + size_bytes > region_bytes {
+        error!(
+            "ROM config write out of bounds: offset={} size={} region={}",
+            offset, size_bytes, region_bytes
+        );
+        return Err(Error::RomConfigOutOfBounds {
+            offset,
+            size_bytes,
+            region_bytes,
+        });
+    }
+    Ok(())
+}
+
+/// Validate a firmware image against the LCPU code region size.
+pub fn check_image_size(image: &[u8]) -> Result<(), Error> {
+    if image.is_empty() {
+        return Err(Error::EmptyImage);
+    }
+
+    let size_bytes = image.len();
+    if size_bytes > LpsysRam::SIZE {
+        error!(
+            "LCPU image too large: {} bytes (max {} bytes)",
+            size_bytes,
+            LpsysRam::SIZE
+        );
+        return Err(Error::ImageTooLarge {
+            size_bytes,
+            max_bytes: LpsysRam::SIZE,
+        });
+    }
+
+    Ok(())
+}
+
 /// Install LCPU firmware image.
 ///
 /// Replaces `lcpu_img::install`.
 pub fn img_install(image: &[u8]) -> Result<(), Error> {
-    if image.is_empty() {
-        return Err(Error::EmptyImage);
-    }
+    check_image_size(image)?;
 
     let revision = syscfg::read_idr().revision();
     if !revision.is_valid() {
@@ -344,20 +450,8 @@ pub fn img_install(image: &[u8]) -> Result<(), Error> {
     }
 
     // Only A3 or Earlier is required to load LCPU image
-    if !revision.is_letter_series() {
+    if !revision.supports(ChipFeature::RomBleController) {
         let size_bytes = image.len();
-        if size_bytes > LpsysRam::SIZE {
-            error!(
-                "LCPU image too large: {} bytes (max {} bytes)",
-                size_bytes,
-                LpsysRam::SIZE
-            );
-            return Err(Error::ImageTooLarge {
-                size_bytes,
-                max_bytes: LpsysRam::SIZE,
-            });
-        }
-
         debug!("Installing LCPU image: {} bytes", size_bytes);
 
         unsafe {
@@ -384,8 +478,47 @@ pub fn set_bt_tx_power(tx_pwr: u32) {
 
     let base = RomControlBlock::address();
     let addr = base + BT_TXPWR_OFFSET;
+    debug_assert!(BT_TXPWR_OFFSET + 4 <= RomControlBlock::region_size());
 
     unsafe {
         ptr::write_volatile(addr as *mut u32, tx_pwr);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_offset<T, F>(base: &T, field: &F) -> usize {
+        field as *const F as usize - base as *const T as usize
+    }
+
+    #[test]
+    fn rom_control_block_offsets() {
+        // SAFETY: all fields are plain integers/byte arrays.
+        let mut block: RomControlBlock = unsafe { mem::zeroed() };
+        block.magic = RomControlBlock::MAGIC;
+        block.hcpu_ipc_addr = 0x1234_5678;
+
+        assert_eq!(field_offset(&block, &block.magic), 0);
+        assert_eq!(field_offset(&block, &block.wdt_time), 0x0C);
+        assert_eq!(field_offset(&block, &block.is_xtal_enable), 0x1A);
+        assert_eq!(field_offset(&block, &block.bt_config), 0xAC);
+        assert_eq!(field_offset(&block, &block.hcpu_ipc_addr), 0xC8);
+
+        // Magic and IPC address land at the bytes the ROM reads.
+        let bytes: &[u8; mem::size_of::<RomControlBlock>()] =
+            unsafe { &*(&block as *const RomControlBlock).cast() };
+        assert_eq!(bytes[0..4], RomControlBlock::MAGIC.to_le_bytes());
+        assert_eq!(bytes[0xC8..0xCC], 0x1234_5678u32.to_le_bytes());
+    }
+
+    #[test]
+    fn bt_rom_config_offsets() {
+        let cfg = BtRomConfig::default();
+        assert_eq!(field_offset(&cfg, &cfg.max_sleep_time), 4);
+        assert_eq!(field_offset(&cfg, &cfg.controller_enable_bit), 8);
+        assert_eq!(field_offset(&cfg, &cfg.default_rc_cycle), 14);
+        assert_eq!(field_offset(&cfg, &cfg.sco_cfg), 20);
+    }
+}
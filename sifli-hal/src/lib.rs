@@ -16,6 +16,9 @@ pub mod aud_pll;
 pub mod audio;
 #[cfg(feature = "bt-hci")]
 pub mod bt_hci;
+pub mod delay;
+#[cfg(feature = "defmt")]
+pub mod diag;
 #[allow(clippy::all)] // modified from embassy-stm32
 pub mod dma;
 pub mod efuse;
@@ -27,10 +30,17 @@ pub mod lcdc;
 pub mod lcpu;
 pub(crate) mod lpaon;
 pub mod mailbox;
+pub mod mpi;
+pub mod ownership;
 pub mod patch;
 pub mod pmu;
+#[cfg(feature = "unstable-pac")]
+pub mod raw;
 pub mod rcc;
+pub mod reset;
 pub mod rng;
+pub mod selftest;
+pub mod storage;
 pub mod syscfg;
 pub mod time;
 #[cfg(feature = "_time-driver")]
@@ -41,7 +51,10 @@ pub mod usart;
 pub mod usb;
 
 // Reexports
+pub use delay::Delay;
 pub use embassy_hal_internal::{into_ref, Peripheral, PeripheralRef};
+/// The `sifli-pac` revision this HAL is built against. See [`raw`] for
+/// register access that respects driver ownership.
 #[cfg(feature = "unstable-pac")]
 pub use sifli_pac as pac;
 #[cfg(not(feature = "unstable-pac"))]
@@ -170,14 +183,60 @@ pub(crate) mod _generated {
     include!(concat!(env!("OUT_DIR"), "/_generated.rs"));
 }
 
-pub use _generated::interrupt;
+// `interrupt_mod!` also defines the `#[interrupt]` attribute under the same
+// name; re-export it next to the module below.
+#[cfg(feature = "rt")]
+pub use cortex_m_rt::interrupt;
+
+pub mod interrupt {
+    //! Interrupt definitions and NVIC priority control.
+    //!
+    //! SF32LB52x implements 3 NVIC priority bits, so there are eight levels,
+    //! [`Priority::P0`] (highest, the reset value of every interrupt) to
+    //! [`Priority::P7`]. The HAL never changes `AIRCR.PRIGROUP`; at its reset
+    //! value all three bits are preemption priority, so a lower number
+    //! preempts a higher one and interrupts at the same level never preempt
+    //! each other. If you set `PRIGROUP` to 5 or more yourself, the low bits
+    //! become sub-priority and only order pending interrupts of one group.
+    //!
+    //! Drivers whose latency matters take a priority in their config (e.g.
+    //! `dma::TransferOptions::interrupt_priority`, `adc::Config::irq_priority`);
+    //! anything else can be changed with [`set_priority`] after the driver is
+    //! created:
+    //!
+    //! ```rust,ignore
+    //! use sifli_hal::interrupt::{self, Interrupt, Priority};
+    //!
+    //! // Keep audio DMA ahead of a slow GPADC handler.
+    //! interrupt::set_priority(Interrupt::DMAC1_CH1, Priority::P1);
+    //! interrupt::set_priority(Interrupt::GPADC, Priority::P4);
+    //! ```
+    //!
+    //! Handlers that use embassy primitives may run at any level; only
+    //! executors started with `InterruptExecutor` care which level they share.
+
+    pub use crate::_generated::interrupt::*;
+
+    /// Set the NVIC priority of `irq`.
+    ///
+    /// Takes effect immediately, also for an interrupt that is already
+    /// enabled.
+    pub fn set_priority(irq: Interrupt, prio: Priority) {
+        InterruptExt::set_priority(irq, prio)
+    }
+
+    /// Current NVIC priority of `irq`.
+    pub fn priority(irq: Interrupt) -> Priority {
+        InterruptExt::get_priority(irq)
+    }
+}
 pub use _generated::{peripherals, Peripherals};
 
 /// Performs a busy-wait delay for a specified number of microseconds, using the `cortex-m::asm::delay` function.
 pub fn cortex_m_blocking_delay_us(us: u32) {
-    let freq = rcc::get_hclk_freq().unwrap().0 as u64;
-    let cycles = freq * us as u64 / 1_000_000;
-    cortex_m::asm::delay(cycles as u32);
+    // Read HCLK from hardware: this is also used mid clock switch, before the cache is updated.
+    let freq = rcc::read_hclk_freq().unwrap().0;
+    delay::delay_loops(delay::loops_for_us(freq, us));
 }
 
 /// Performs a busy-wait delay for a specified number of microseconds.
@@ -7,6 +7,9 @@
 //!
 //! Each channel has 16 interrupt bits that can be triggered independently.
 //!
+//! MAILBOX2 sits in LPSYS and is clocked from `LPSYS_RCC`; the RX channel
+//! constructors enable it through [`rcc::lpsys_rcc`](crate::rcc::lpsys_rcc).
+//!
 //! ## Usage
 //!
 //! ```ignore
@@ -34,6 +37,7 @@ use embassy_hal_internal::{into_ref, Peripheral, PeripheralRef};
 use embassy_sync::waitqueue::AtomicWaker;
 
 use crate::interrupt;
+use crate::ownership::Claim;
 use crate::peripherals;
 
 pub use crate::pac::mailbox::vals::LockCore;
@@ -69,13 +73,17 @@ macro_rules! impl_mailbox_channel {
         /// MAILBOX1 TX channel (HCPU → LCPU)
         pub struct $name<'d> {
             _peri: PeripheralRef<'d, peripherals::$peri>,
+            _claim: Claim,
         }
 
         impl<'d> $name<'d> {
             /// Create new channel instance
             pub fn new(peri: impl Peripheral<P = peripherals::$peri> + 'd) -> Self {
                 into_ref!(peri);
-                Self { _peri: peri }
+                Self {
+                    _peri: peri,
+                    _claim: Claim::new::<peripherals::$peri>(),
+                }
             }
 
             /// Trigger interrupt on LCPU
@@ -149,13 +157,20 @@ macro_rules! impl_mailbox_channel {
         /// MAILBOX2 RX channel (LCPU → HCPU)
         pub struct $name<'d> {
             _peri: PeripheralRef<'d, peripherals::$peri>,
+            _claim: Claim,
         }
 
         impl<'d> $name<'d> {
             /// Create new channel instance
+            ///
+            /// Enables the MAILBOX2 clock in `LPSYS_RCC` if it is gated.
             pub fn new(peri: impl Peripheral<P = peripherals::$peri> + 'd) -> Self {
                 into_ref!(peri);
-                Self { _peri: peri }
+                crate::rcc::lpsys_rcc::enable(crate::rcc::lpsys_rcc::LpPeripheral::Mailbox2);
+                Self {
+                    _peri: peri,
+                    _claim: Claim::new::<peripherals::$peri>(),
+                }
             }
 
             /// Trigger interrupt (for testing, normally LCPU triggers this)
@@ -0,0 +1,442 @@
+//! MPI (Multi-Protocol Interface) NOR flash access.
+//!
+//! Blocking erase/program for the SPI NOR flash the chip executes from, for
+//! persisting small amounts of data (BD address, RF calibration, settings).
+//!
+//! Reads go through the memory-mapped XIP window and need nothing special.
+//! While the flash is erasing or programming, XIP reads from it stall, so the
+//! commands run from RAM (`.data.ram_func`) with interrupts masked: an
+//! interrupt handler located in flash would otherwise fetch from a busy
+//! device.
+//!
+//! # Interrupt latency
+//!
+//! A sector erase takes tens of milliseconds (up to ~400 ms). Instead of
+//! masking interrupts for all of it, the erase is suspended (`0x75`) as soon
+//! as an interrupt is pending, which makes the flash readable again: the
+//! critical section is left, the handlers run from XIP, and the erase is
+//! resumed (`0x7A`). An interrupt therefore waits at most one status poll plus
+//! the flash's suspend latency (tSUS, 20-30 µs on common parts). After a
+//! resume the erase runs for [`RESUME_HOLDOFF_POLLS`] polls before it can be
+//! suspended again, so a steady interrupt load cannot starve it.
+//!
+//! Page programs are not suspended: each chunk of up to 64 bytes keeps
+//! interrupts masked for its program time (typically 0.5 ms, at most ~3 ms).
+//!
+//! The RAM routines never return to flash code while the device is busy: if
+//! an erase or program outlasts its poll budget it is suspended first, and
+//! [`Error::Busy`] is returned with the target range left partly written.
+//!
+//! Only this core is held off. Other bus masters (DMA, LCDC layers) must not
+//! read from the same flash while an erase or program is running.
+//!
+//! ```rust,ignore
+//! use sifli_hal::mpi::{Flash, SECTOR_SIZE};
+//!
+//! // Last sector of a 4 MiB flash on MPI2.
+//! const OFFSET: u32 = 4 * 1024 * 1024 - SECTOR_SIZE as u32;
+//!
+//! let mut flash = Flash::new_blocking(p.MPI2, 4 * 1024 * 1024);
+//! flash.erase(OFFSET)?;
+//! flash.write(OFFSET, &bd_addr)?;
+//! let mut buf = [0u8; 6];
+//! flash.read(OFFSET, &mut buf)?;
+//! ```
+
+use core::marker::PhantomData;
+
+use embassy_hal_internal::Peripheral;
+use embedded_storage::nor_flash::{
+    ErrorType, NorFlash, NorFlashError, NorFlashErrorKind, ReadNorFlash,
+};
+
+use crate::pac;
+use crate::peripherals;
+
+/// Erase granularity in bytes (SPI NOR `0x20` sector erase).
+pub const SECTOR_SIZE: usize = 4096;
+
+/// Program granularity in bytes; a write must not cross a page boundary in a
+/// single program command. [`Flash::write`] splits writes for you.
+pub const PAGE_SIZE: usize = 256;
+
+/// Bytes pushed through the MPI data FIFO per command.
+const FIFO_BYTES: usize = 64;
+
+/// Status register polls before a busy flash is reported as [`Error::Busy`].
+/// Covers the worst-case 4 KiB erase time (~400 ms) with margin at 240 MHz.
+const BUSY_POLLS: u32 = 20_000_000;
+
+/// Status polls after an erase resume before the erase may be suspended
+/// again. Parts need some time between resume and the next suspend to make
+/// progress (tens of µs).
+pub const RESUME_HOLDOFF_POLLS: u32 = 200;
+
+mod cmd {
+    pub const WRITE_ENABLE: u8 = 0x06;
+    pub const READ_STATUS1: u8 = 0x05;
+    pub const PAGE_PROGRAM: u8 = 0x02;
+    pub const SECTOR_ERASE: u8 = 0x20;
+    pub const ERASE_SUSPEND: u8 = 0x75;
+    pub const ERASE_RESUME: u8 = 0x7A;
+}
+
+mod status {
+    /// Write in progress.
+    pub const WIP: u8 = 1 << 0;
+    /// Write enable latch.
+    pub const WEL: u8 = 1 << 1;
+    /// Block protect bits BP0..BP4 (and TB/SEC on most parts).
+    pub const BP_MASK: u8 = 0b0111_1100;
+}
+
+/// Flash error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Offset (or erase address) not aligned as the operation requires.
+    NotAligned,
+    /// The access extends past the flash capacity.
+    OutOfBounds,
+    /// The flash stayed busy (WIP set) beyond the timeout.
+    Busy,
+    /// Status register block-protect bits are set, or the write enable latch
+    /// did not set; nothing was written.
+    WriteProtected,
+}
+
+/// Blocking NOR flash driver on an MPI instance.
+pub struct Flash<'d, T: Instance> {
+    capacity: usize,
+    _phantom: PhantomData<&'d T>,
+}
+
+impl<'d, T: Instance> Flash<'d, T> {
+    /// Create a driver for a flash of `capacity` bytes.
+    ///
+    /// The MPI must already be set up for XIP by the bootloader; its clock
+    /// and read configuration are left untouched.
+    pub fn new_blocking(_peri: impl Peripheral<P = T> + 'd, capacity: usize) -> Self {
+        Self {
+            capacity,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Flash size in bytes.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn check_bounds(&self, offset: u32, len: usize) -> Result<(), Error> {
+        match (offset as usize).checked_add(len) {
+            Some(end) if end <= self.capacity => Ok(()),
+            _ => Err(Error::OutOfBounds),
+        }
+    }
+
+    /// Read `buf.len()` bytes at `offset` through the XIP window.
+    pub fn read(&mut self, offset: u32, buf: &mut [u8]) -> Result<(), Error> {
+        self.check_bounds(offset, buf.len())?;
+        let src = (T::XIP_BASE + offset as usize) as *const u8;
+        // SAFETY: in bounds of the memory-mapped flash; no program/erase can
+        // be in flight since they hold `&mut self` and complete before return.
+        unsafe { core::ptr::copy_nonoverlapping(src, buf.as_mut_ptr(), buf.len()) };
+        Ok(())
+    }
+
+    /// Erase the 4 KiB sector at `offset`, which must be sector aligned.
+    ///
+    /// Pending interrupts suspend the erase and are served in between, see
+    /// the [module docs](self#interrupt-latency).
+    pub fn erase(&mut self, offset: u32) -> Result<(), Error> {
+        if offset as usize % SECTOR_SIZE != 0 {
+            return Err(Error::NotAligned);
+        }
+        self.check_bounds(offset, SECTOR_SIZE)?;
+
+        let r = T::regs();
+        let mut polls = BUSY_POLLS;
+        let mut resume = false;
+        let result = loop {
+            // `ram::erase` only returns once the flash is idle or suspended,
+            // so everything out here may run from XIP.
+            match critical_section::with(|_| unsafe { ram::erase(r, offset, resume, &mut polls) }) {
+                Ok(ram::Erase::Done) => break Ok(()),
+                // The flash is readable again; pending handlers run here.
+                Ok(ram::Erase::Suspended) => resume = true,
+                Err(e) => break Err(e),
+            }
+        };
+        invalidate_caches(T::XIP_BASE + offset as usize, SECTOR_SIZE);
+        result
+    }
+
+    /// Program `data` at `offset`.
+    ///
+    /// The target range must have been erased; NOR programming can only clear
+    /// bits. Writes are split at page boundaries, so any offset and length
+    /// within the capacity is accepted.
+    pub fn write(&mut self, offset: u32, data: &[u8]) -> Result<(), Error> {
+        self.check_bounds(offset, data.len())?;
+
+        let mut addr = offset as usize;
+        let mut rest = data;
+        while !rest.is_empty() {
+            let page_room = PAGE_SIZE - addr % PAGE_SIZE;
+            let n = rest.len().min(page_room).min(FIFO_BYTES);
+            let (chunk, tail) = rest.split_at(n);
+
+            let mut words = [0u32; FIFO_BYTES / 4];
+            for (i, b) in chunk.iter().enumerate() {
+                words[i / 4] |= (*b as u32) << (8 * (i % 4));
+            }
+
+            critical_section::with(|_| unsafe {
+                ram::program(T::regs(), addr as u32, &words, n, BUSY_POLLS)
+            })?;
+
+            addr += n;
+            rest = tail;
+        }
+
+        invalidate_caches(T::XIP_BASE + offset as usize, data.len());
+        Ok(())
+    }
+}
+
+impl NorFlashError for Error {
+    fn kind(&self) -> NorFlashErrorKind {
+        match self {
+            Error::NotAligned => NorFlashErrorKind::NotAligned,
+            Error::OutOfBounds => NorFlashErrorKind::OutOfBounds,
+            _ => NorFlashErrorKind::Other,
+        }
+    }
+}
+
+impl<'d, T: Instance> ErrorType for Flash<'d, T> {
+    type Error = Error;
+}
+
+impl<'d, T: Instance> ReadNorFlash for Flash<'d, T> {
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Error> {
+        Self::read(self, offset, bytes)
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl<'d, T: Instance> NorFlash for Flash<'d, T> {
+    const WRITE_SIZE: usize = 1;
+    const ERASE_SIZE: usize = SECTOR_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Error> {
+        if !(to as usize).is_multiple_of(SECTOR_SIZE) {
+            return Err(Error::NotAligned);
+        }
+        for offset in (from..to).step_by(SECTOR_SIZE) {
+            Self::erase(self, offset)?;
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Error> {
+        Self::write(self, offset, bytes)
+    }
+}
+
+/// Drop stale flash contents from the CPU caches after a program/erase.
+fn invalidate_caches(addr: usize, len: usize) {
+    // SAFETY: cache maintenance only; no other SCB state is touched.
+    unsafe {
+        let mut scb = cortex_m::Peripherals::steal().SCB;
+        scb.invalidate_dcache_by_address(addr, len);
+        scb.invalidate_icache();
+    }
+}
+
+/// Routines that run while the flash is unavailable for XIP.
+///
+/// They must not call into flash: only `#[inline(always)]` PAC accessors and
+/// plain loops are used, and the callers hold a critical section.
+mod ram {
+    use super::{cmd, status, Error};
+    use crate::pac::mpi::Mpi;
+
+    /// Issue a command on command slot 1 and wait for completion.
+    ///
+    /// `data_len` is the number of data bytes (0 for none); `write` selects
+    /// the data direction.
+    #[inline(always)]
+    unsafe fn command(r: Mpi, cmd: u8, addr: Option<u32>, data_len: usize, write: bool) {
+        if let Some(addr) = addr {
+            r.ar1().write(|w| w.set_addr(addr));
+        }
+        if data_len > 0 {
+            r.dlr1().write(|w| w.set_dlen(data_len as u32 - 1));
+        }
+        r.ccr1().write(|w| {
+            w.set_imode(1); // instruction on a single line
+            w.set_admode(if addr.is_some() { 1 } else { 0 });
+            w.set_adsize(2); // 24-bit address
+            w.set_dmode(if data_len > 0 { 1 } else { 0 });
+            w.set_fmode(write);
+        });
+        r.cmdr1().write(|w| w.set_cmd(cmd));
+        while !r.sr().read().tcf() {}
+        r.scr().write(|w| w.set_tcfc(true));
+    }
+
+    #[inline(always)]
+    unsafe fn read_status(r: Mpi) -> u8 {
+        command(r, cmd::READ_STATUS1, None, 1, false);
+        r.dr().read().data() as u8
+    }
+
+    #[inline(always)]
+    unsafe fn wait_idle(r: Mpi, polls: u32) -> Result<(), Error> {
+        let mut n = 0;
+        while n < polls {
+            if read_status(r) & status::WIP == 0 {
+                return Ok(());
+            }
+            n += 1;
+        }
+        Err(Error::Busy)
+    }
+
+    #[inline(always)]
+    unsafe fn write_enable(r: Mpi, polls: u32) -> Result<(), Error> {
+        wait_idle(r, polls)?;
+        let sr = read_status(r);
+        if sr & status::BP_MASK != 0 {
+            return Err(Error::WriteProtected);
+        }
+        command(r, cmd::WRITE_ENABLE, None, 0, false);
+        if read_status(r) & status::WEL == 0 {
+            return Err(Error::WriteProtected);
+        }
+        Ok(())
+    }
+
+    /// An exception is pending (`ICSR.VECTPENDING`), even with PRIMASK set.
+    #[inline(always)]
+    unsafe fn interrupt_pending() -> bool {
+        const ICSR: *const u32 = 0xE000_ED04 as *const u32;
+        (core::ptr::read_volatile(ICSR) >> 12) & 0x1FF != 0
+    }
+
+    /// State of an erase after [`erase`].
+    pub(super) enum Erase {
+        Done,
+        /// Suspended for a pending interrupt; call again with `resume`.
+        Suspended,
+    }
+
+    /// Suspend the program or erase in flight and spin until the flash is
+    /// readable. Suspend latency is bounded by the part (tSUS), so this does
+    /// not need a budget of its own.
+    #[inline(always)]
+    unsafe fn suspend(r: Mpi) {
+        command(r, cmd::ERASE_SUSPEND, None, 0, false);
+        while read_status(r) & status::WIP != 0 {}
+    }
+
+    /// Issue (or, with `resume`, resume) the sector erase at `addr` and wait
+    /// for it, suspending it when an interrupt is pending. `polls` is the
+    /// budget left across calls.
+    ///
+    /// Returns only with WIP clear: the erase is done or suspended. When the
+    /// budget runs out the erase is suspended too and [`Error::Busy`] is
+    /// returned, leaving the sector partly erased.
+    #[link_section = ".data.ram_func"]
+    #[inline(never)]
+    pub(super) unsafe fn erase(
+        r: Mpi,
+        addr: u32,
+        resume: bool,
+        polls: &mut u32,
+    ) -> Result<Erase, Error> {
+        let mut holdoff = 0;
+        if resume {
+            command(r, cmd::ERASE_RESUME, None, 0, false);
+            holdoff = super::RESUME_HOLDOFF_POLLS;
+        } else {
+            write_enable(r, *polls)?;
+            command(r, cmd::SECTOR_ERASE, Some(addr), 0, false);
+        }
+        while *polls > 0 {
+            *polls -= 1;
+            if read_status(r) & status::WIP == 0 {
+                return Ok(Erase::Done);
+            }
+            if holdoff > 0 {
+                holdoff -= 1;
+            } else if interrupt_pending() {
+                // WIP clears once the suspend has taken effect (or the erase
+                // finished meanwhile; the next resume is then ignored).
+                suspend(r);
+                return Ok(Erase::Suspended);
+            }
+        }
+        suspend(r);
+        Err(Error::Busy)
+    }
+
+    /// Program `len` bytes (at most one FIFO, within one page) from `words`.
+    #[link_section = ".data.ram_func"]
+    #[inline(never)]
+    pub(super) unsafe fn program(
+        r: Mpi,
+        addr: u32,
+        words: &[u32; super::FIFO_BYTES / 4],
+        len: usize,
+        polls: u32,
+    ) -> Result<(), Error> {
+        write_enable(r, polls)?;
+        // Fill the FIFO first; the command starts shifting on CMDR1 write.
+        let mut i = 0;
+        while i * 4 < len {
+            r.dr().write(|w| w.set_data(words[i]));
+            i += 1;
+        }
+        command(r, cmd::PAGE_PROGRAM, Some(addr), len, true);
+        let result = wait_idle(r, polls);
+        if result.is_err() {
+            suspend(r);
+        }
+        result
+    }
+}
+
+trait SealedInstance {
+    fn regs() -> pac::mpi::Mpi;
+}
+
+/// MPI instance.
+#[allow(private_bounds)]
+pub trait Instance: Peripheral<P = Self> + SealedInstance + 'static + Send {
+    /// Start of the memory-mapped (XIP) window.
+    const XIP_BASE: usize;
+}
+
+macro_rules! impl_instance {
+    ($inst:ident, $base:expr) => {
+        impl SealedInstance for peripherals::$inst {
+            fn regs() -> pac::mpi::Mpi {
+                pac::$inst
+            }
+        }
+        impl Instance for peripherals::$inst {
+            const XIP_BASE: usize = $base;
+        }
+    };
+}
+
+impl_instance!(MPI1, 0x1000_0000);
+impl_instance!(MPI2, 0x1200_0000);
@@ -0,0 +1,121 @@
+//! Debug-build tracking of which peripherals are owned by a driver.
+//!
+//! [`init`](crate::init) hands out each peripheral singleton once, and the
+//! borrow checker keeps two drivers off the same hardware. `steal()` (on
+//! `Peripherals` or on a single peripheral) bypasses that: a second copy of
+//! a singleton can build another driver, or reach the registers through
+//! [`raw::RegisterBlock`](crate::raw::RegisterBlock), while the first driver
+//! still runs. This is common when integrating with a C bootloader or SDK
+//! code, and the result is a silent race on the registers.
+//!
+//! With `debug_assertions` enabled, drivers record their peripheral here
+//! while they exist, and a second claim or a raw register access to a
+//! claimed peripheral logs a warning naming it:
+//!
+//! ```text
+//! WARN  sifli_hal::peripherals::I2C1 is already owned by a driver; was the
+//!       singleton stolen with `steal()` while `init()` still owns it?
+//! ```
+//!
+//! Only the overlap is detected: a stolen singleton used after the owning
+//! driver is dropped, or registers written through the PAC statics, are not
+//! seen. Drivers for global hardware ([`Adc`](crate::adc::Adc),
+//! [`AudioPll`](crate::aud_pll::AudioPll), [`Lcdc`](crate::lcdc::Lcdc))
+//! additionally panic on a second instance in every build. In release builds
+//! this module compiles to nothing.
+
+#[cfg(debug_assertions)]
+use core::cell::RefCell;
+
+#[cfg(debug_assertions)]
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+#[cfg(debug_assertions)]
+use embassy_sync::blocking_mutex::Mutex;
+
+/// Number of peripherals that can be tracked at once. Claims beyond this are
+/// not tracked.
+#[cfg(debug_assertions)]
+const MAX_CLAIMS: usize = 16;
+
+#[cfg(debug_assertions)]
+static CLAIMS: Mutex<CriticalSectionRawMutex, RefCell<[Option<&'static str>; MAX_CLAIMS]>> =
+    Mutex::new(RefCell::new([None; MAX_CLAIMS]));
+
+/// Marks peripheral `T` as owned by a driver until dropped.
+///
+/// Zero-sized in release builds.
+pub(crate) struct Claim {
+    #[cfg(debug_assertions)]
+    name: Option<&'static str>,
+}
+
+impl Claim {
+    /// Claim `T`, warning if another driver already holds it.
+    #[cfg(debug_assertions)]
+    pub(crate) fn new<T>() -> Self {
+        let name = core::any::type_name::<T>();
+        let tracked = CLAIMS.lock(|c| {
+            let mut c = c.borrow_mut();
+            if c.iter().flatten().any(|&n| n == name) {
+                warn_stolen(name);
+                return false;
+            }
+            match c.iter_mut().find(|s| s.is_none()) {
+                Some(slot) => {
+                    *slot = Some(name);
+                    true
+                }
+                None => false,
+            }
+        });
+        Self {
+            name: tracked.then_some(name),
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[inline]
+    pub(crate) fn new<T>() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for Claim {
+    fn drop(&mut self) {
+        let Some(name) = self.name else {
+            return;
+        };
+        CLAIMS.lock(|c| {
+            for slot in c.borrow_mut().iter_mut() {
+                if *slot == Some(name) {
+                    *slot = None;
+                    break;
+                }
+            }
+        });
+    }
+}
+
+/// Warn if a driver currently holds `T`. Called on raw register access,
+/// which is only reachable with the singleton while no driver borrows it.
+#[cfg(debug_assertions)]
+pub(crate) fn check_unclaimed<T>() {
+    let name = core::any::type_name::<T>();
+    if CLAIMS.lock(|c| c.borrow().iter().flatten().any(|&n| n == name)) {
+        warn_stolen(name);
+    }
+}
+
+#[cfg(not(debug_assertions))]
+#[inline]
+pub(crate) fn check_unclaimed<T>() {}
+
+#[cfg(debug_assertions)]
+fn warn_stolen(name: &str) {
+    warn!(
+        "{} is already owned by a driver; was the singleton stolen with `steal()` while \
+         `init()` still owns it?",
+        name
+    );
+}
@@ -19,7 +19,7 @@
 
 use crate::lcpu::ram::PatchRegion;
 use crate::pac;
-use crate::syscfg;
+use crate::syscfg::{self, ChipRevision};
 
 //=============================================================================
 // Constants
@@ -236,28 +236,46 @@ pub fn hal_patch_get_enabled() -> u32 {
 /// patch::install(&PATCH_LIST_BYTES, &PATCH_BIN_BYTES)?;
 /// ```
 pub fn install(list: &[u8], bin: &[u8]) -> Result<(), Error> {
-    // Parameter validation
+    let revision = syscfg::read_idr().revision();
+    check(list, bin, revision)?;
+
+    // Dispatch to A3 or Letter-Series patch installer based on revision
+    if revision.is_letter_series() {
+        install_letter(list, bin)
+    } else {
+        install_a3(list, bin)
+    }
+}
+
+/// Validate patch data for `revision` without touching LCPU RAM.
+///
+/// Runs the same checks as [`install`], so a bad patch can be rejected
+/// before the LCPU is reset.
+pub fn check(list: &[u8], bin: &[u8], revision: ChipRevision) -> Result<(), Error> {
     if list.is_empty() {
         return Err(Error::EmptyRecord);
     }
     if bin.is_empty() {
         return Err(Error::EmptyCode);
     }
-
-    let revision = syscfg::read_idr().revision();
-
     if !revision.is_valid() {
         return Err(Error::InvalidRevision {
             revid: revision.revid(),
         });
     }
 
-    // Dispatch to A3 or Letter-Series patch installer based on revision
-    if revision.is_letter_series() {
-        install_letter(list, bin)
+    let max_bytes = if revision.is_letter_series() {
+        PatchRegion::LETTER_CODE_SIZE
     } else {
-        install_a3(list, bin)
+        PatchRegion::A3_TOTAL_SIZE
+    };
+    if bin.len() > max_bytes {
+        return Err(Error::CodeTooLarge {
+            size_bytes: bin.len(),
+            max_bytes,
+        });
     }
+    Ok(())
 }
 
 /// Install A3 / earlier-format patches.
@@ -268,12 +286,6 @@ pub fn install(list: &[u8], bin: &[u8]) -> Result<(), Error> {
 /// 3. Clear and copy patch code to RAM
 fn install_a3(list: &[u8], bin: &[u8]) -> Result<(), Error> {
     let code_size = bin.len();
-    if code_size > PatchRegion::A3_TOTAL_SIZE {
-        return Err(Error::CodeTooLarge {
-            size_bytes: code_size,
-            max_bytes: PatchRegion::A3_TOTAL_SIZE,
-        });
-    }
 
     debug!(
         "Installing A3 patch: record={} bytes, code={} bytes",
@@ -357,12 +369,6 @@ fn install_a3(list: &[u8], bin: &[u8]) -> Result<(), Error> {
 /// Reference: `lcpu_patch_rev_b.c:lcpu_patch_install_rev_b()` + `bf0_hal_patch.c:HAL_PATCH_install()`
 fn install_letter(list: &[u8], bin: &[u8]) -> Result<(), Error> {
     let code_size = bin.len();
-    if code_size > PatchRegion::LETTER_CODE_SIZE {
-        return Err(Error::CodeTooLarge {
-            size_bytes: code_size,
-            max_bytes: PatchRegion::LETTER_CODE_SIZE,
-        });
-    }
 
     debug!(
         "Installing Letter Series patch: code={} bytes, list={} bytes",
@@ -424,3 +430,28 @@ fn install_letter(list: &[u8], bin: &[u8]) -> Result<(), Error> {
     info!("Letter Series patch installed successfully");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_limits_code_to_revision_region() {
+        let list = [0u8; 4];
+        let a3 = ChipRevision::A3OrEarlier(0x03);
+        let too_big = [0u8; PatchRegion::A3_TOTAL_SIZE + 1];
+        assert_eq!(
+            check(&list, &too_big, a3),
+            Err(Error::CodeTooLarge {
+                size_bytes: PatchRegion::A3_TOTAL_SIZE + 1,
+                max_bytes: PatchRegion::A3_TOTAL_SIZE,
+            })
+        );
+        assert_eq!(check(&list, &[0u8; 16], a3), Ok(()));
+        assert_eq!(check(&[], &[0u8; 16], ChipRevision::A4), Err(Error::EmptyRecord));
+        assert_eq!(
+            check(&list, &[0u8; 16], ChipRevision::Invalid(0x42)),
+            Err(Error::InvalidRevision { revid: 0x42 })
+        );
+    }
+}
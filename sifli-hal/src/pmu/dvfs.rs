@@ -1,3 +1,5 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
 use crate::pac::hpsys_cfg::regs::Ulpmcr;
 use crate::pac::lpsys_cfg::regs::Ulpmcr as LpUlpmcr;
 use crate::pac::{HPSYS_CFG, LPSYS_CFG, PMUC};
@@ -127,6 +129,25 @@ impl HpsysDvfsMode {
     }
 }
 
+/// HPSYS LDO output code for 1.1 V (SDK `HAL_PMU_GetHpsysVoutRef`).
+///
+/// Nominal until [`apply_factory_trim`](super::trim::apply_factory_trim)
+/// stores the EFUSE value. Per-mode LDO codes are this plus `ldo_offset`.
+static HPSYS_VOUT_REF: AtomicU8 = AtomicU8::new(HPSYS_VOUT_REF_DEFAULT);
+
+/// Untrimmed HPSYS LDO 1.1 V code.
+pub(crate) const HPSYS_VOUT_REF_DEFAULT: u8 = 0xB;
+
+pub(crate) fn set_hpsys_vout_ref(vout_ref: u8) {
+    HPSYS_VOUT_REF.store(vout_ref, Ordering::Relaxed);
+}
+
+/// HPSYS LDO output code for `mode`, relative to the (trimmed) reference.
+pub(crate) fn hpsys_ldo_vout(mode: HpsysDvfsMode) -> u8 {
+    let vout_ref = HPSYS_VOUT_REF.load(Ordering::Relaxed) as i8;
+    (vout_ref + mode.get_config().ldo_offset) as u8
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct HpsysDvfsConfig {
     pub ldo_offset: i8,
@@ -182,10 +203,9 @@ fn config_hcpu_sx_mode_volt(target_dvfs_mode: HpsysDvfsMode) {
     });
 
     // configure LDO voltage
-    // TODO: use efuse value (HAL_PMU_GetHpsysVoutRef[2])
-    let vout_ref = dvfs_config.ldo;
+    let vout = hpsys_ldo_vout(target_dvfs_mode);
     PMUC.hpsys_vout().modify(|w| {
-        w.set_vout(vout_ref);
+        w.set_vout(vout);
     });
 }
 
@@ -223,9 +243,7 @@ where
 
     // configure LDO voltage for D mode
     // SDK uses: HAL_PMU_GetHpsysVoutRef() + ldo_offset
-    // eFuse default reference is ~0xB (1.1V). TODO: read actual eFuse value.
-    let efuse_ref: i8 = 0xB;
-    let vref = (efuse_ref + dvfs_config.ldo_offset) as u8;
+    let vref = hpsys_ldo_vout(target_dvfs_mode);
     PMUC.hpsys_ldo().modify(|w| {
         w.set_vref(vref);
     });
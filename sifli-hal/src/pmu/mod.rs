@@ -1 +1,4 @@
 pub mod dvfs;
+pub mod trim;
+
+pub use trim::{apply_factory_trim, TrimError, TrimSource};
@@ -0,0 +1,131 @@
+//! Factory voltage trim from EFUSE bank1.
+//!
+//! The SDK loads these values into the PMU during boot
+//! (`HAL_PMU_LoadCalData`). Without them the rails run at the nominal codes,
+//! which can be off by a step or more on individual parts; at S1 (240 MHz)
+//! that leaves little margin on the HPSYS core voltage.
+//!
+//! | EFUSE field        | PMUC register / field            |
+//! |--------------------|----------------------------------|
+//! | `buck_vos_trim`    | `BUCK_CR1.BG_BUF_VOS_TRIM`       |
+//! | `buck_vos_polar`   | `BUCK_CR1.BG_BUF_VOS_POLAR`      |
+//! | `hpsys_ldo_vout`   | `HPSYS_VOUT.VOUT` (1.1 V code)   |
+//! | `lpsys_ldo_vout`   | `LPSYS_VOUT.VOUT`                |
+//! | `vret_trim`        | `VRET_CR.TRIM`                   |
+//!
+//! `hpsys_ldo_vout` is also the reference the HPSYS DVFS tables are offset
+//! from, so later [`rcc`](crate::rcc) clock switches keep using the trimmed
+//! value for both the S-mode (`HPSYS_VOUT`) and D-mode (`HPSYS_LDO.VREF`)
+//! rails. LPSYS DVFS transitions still write the nominal table codes.
+//!
+//! # Sequencing
+//!
+//! Call once, right after [`init`](crate::init) and before raising HCLK
+//! above 48 MHz (or before [`rcc::reconfigure_sysclk`](crate::rcc)), so the
+//! S-mode switch already uses the trimmed reference:
+//!
+//! ```rust,ignore
+//! let p = sifli_hal::init(Default::default());
+//! let efuse = sifli_hal::efuse::Efuse::new(p.EFUSEC)?;
+//! sifli_hal::pmu::apply_factory_trim(efuse.calibration())?;
+//! ```
+//!
+//! Calling it later also works: it re-applies the HPSYS LDO code for the
+//! current DVFS mode. The factory codes are written as they are, though, so
+//! each rail may step up or down from the nominal code while running; on a
+//! part trimmed below nominal that briefly narrows the margin at S1, which
+//! is why the early call is preferred.
+
+use crate::efuse::Bank1Calibration;
+use crate::pac::PMUC;
+use crate::pmu::dvfs::{self, HpsysDvfsMode};
+use crate::rcc;
+
+/// Which bank1 calibration row was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TrimSource {
+    /// The primary row (`Bank1Primary`).
+    Primary,
+    /// The second voltage row (`Bank1Vol2`), used when the primary row is blank.
+    Vol2,
+}
+
+/// Factory trim error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TrimError {
+    /// Neither calibration row is programmed (blank EFUSE); nothing was written.
+    NotProgrammed,
+}
+
+/// Trim values taken from one calibration row.
+struct Trim {
+    buck_vos_trim: u8,
+    buck_vos_polar: bool,
+    hpsys_ldo_vout: u8,
+    lpsys_ldo_vout: u8,
+    vret_trim: u8,
+}
+
+/// Write the factory LDO/BUCK trim from `cal` into the PMU.
+///
+/// The primary row is used when its HPSYS LDO code is programmed (non-zero),
+/// otherwise the `vol2` row. A blank EFUSE returns
+/// [`TrimError::NotProgrammed`] and leaves the PMU untouched.
+pub fn apply_factory_trim(cal: &Bank1Calibration) -> Result<TrimSource, TrimError> {
+    let primary = &cal.primary.low;
+    let (source, trim) = if cal.primary.is_programmed() {
+        (
+            TrimSource::Primary,
+            Trim {
+                buck_vos_trim: primary.buck_vos_trim(),
+                buck_vos_polar: primary.buck_vos_polar(),
+                hpsys_ldo_vout: primary.hpsys_ldo_vout(),
+                lpsys_ldo_vout: primary.lpsys_ldo_vout(),
+                vret_trim: primary.vret_trim(),
+            },
+        )
+    } else if cal.vol2.is_programmed() {
+        let vol2 = &cal.vol2;
+        (
+            TrimSource::Vol2,
+            Trim {
+                buck_vos_trim: vol2.buck_vos_trim(),
+                buck_vos_polar: vol2.buck_vos_polar(),
+                hpsys_ldo_vout: vol2.hpsys_ldo_vout(),
+                lpsys_ldo_vout: vol2.lpsys_ldo_vout(),
+                vret_trim: vol2.vret_trim(),
+            },
+        )
+    } else {
+        return Err(TrimError::NotProgrammed);
+    };
+
+    critical_section::with(|_| {
+        PMUC.buck_cr1().modify(|w| {
+            w.set_bg_buf_vos_trim(trim.buck_vos_trim);
+            w.set_bg_buf_vos_polar(trim.buck_vos_polar);
+        });
+        PMUC.lpsys_vout().modify(|w| w.set_vout(trim.lpsys_ldo_vout));
+        PMUC.vret_cr().modify(|w| w.set_trim(trim.vret_trim));
+
+        // Re-derive the HPSYS LDO code for the mode we are running in.
+        dvfs::set_hpsys_vout_ref(trim.hpsys_ldo_vout);
+        let mode = rcc::get_hclk_freq()
+            .and_then(|f| HpsysDvfsMode::from_hertz(f).ok())
+            .unwrap_or(HpsysDvfsMode::D1);
+        let vout = dvfs::hpsys_ldo_vout(mode);
+        if dvfs::is_hpsys_dvfs_mode_s() {
+            PMUC.hpsys_vout().modify(|w| w.set_vout(vout));
+        } else {
+            PMUC.hpsys_ldo().modify(|w| w.set_vref(vout));
+        }
+    });
+
+    debug!(
+        "pmu: factory trim applied ({:?}): hpsys_vout=0x{:X} lpsys_vout=0x{:X} vret=0x{:X}",
+        source, trim.hpsys_ldo_vout, trim.lpsys_ldo_vout, trim.vret_trim
+    );
+    Ok(source)
+}
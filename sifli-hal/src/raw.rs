@@ -0,0 +1,119 @@
+//! Register access for features the drivers do not model.
+//!
+//! The drivers cover the common configuration, but some knobs (PLL loop
+//! filter, ADC analog trims, undocumented debug bits) are only reachable
+//! through the PAC. With the `unstable-pac` feature, [`crate::pac`] re-exports
+//! the exact `sifli-pac` revision this HAL is built against, so register
+//! types from it always match the ones the drivers use.
+//!
+//! [`RegisterBlock`] hands out a peripheral's register block through its
+//! singleton, as a [`Regs`] guard that holds the `&mut` borrow. While the
+//! guard is alive no driver can be built from the singleton, and while a
+//! driver built from a reborrow is alive the borrow checker rejects register
+//! access; once the driver is dropped the registers are yours again:
+//!
+//! ```rust,ignore
+//! use sifli_hal::raw::RegisterBlock;
+//!
+//! let mut p = sifli_hal::init(Default::default());
+//! {
+//!     let mut adc = Adc::new_blocking(&mut p.GPADC, Default::default());
+//!     // p.GPADC.regs() here does not compile
+//! }
+//! p.GPADC.regs().cfg_reg1().modify(|w| w.set_anau_gpadc_cmm(0x12));
+//! ```
+//!
+//! Keeping the guard across driver construction does not compile either:
+//!
+//! ```rust,compile_fail
+//! use sifli_hal::adc::Adc;
+//! use sifli_hal::raw::RegisterBlock;
+//!
+//! let mut p = sifli_hal::init(Default::default());
+//! let r = p.GPADC.regs();
+//! let adc = Adc::new_blocking(&mut p.GPADC, Default::default());
+//! r.cfg_reg1().modify(|w| w.set_anau_gpadc_cmm(0x12));
+//! ```
+//!
+//! The PAC handle inside the guard is `Copy`, so dereferencing it out
+//! (`*p.GPADC.regs()`) escapes the borrow, as does writing the PAC statics
+//! (`pac::GPADC`) directly; nothing then stops a driver from overwriting the
+//! change. In debug builds, calling `regs()` on a stolen singleton while a
+//! driver owns the peripheral logs a warning, see
+//! [`ownership`](crate::ownership).
+
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::{pac, peripherals, Peripheral};
+
+/// A peripheral singleton with a PAC register block.
+pub trait RegisterBlock: Peripheral<P = Self> + 'static {
+    /// PAC register block type.
+    type Block: Copy;
+
+    /// This peripheral's registers, borrowed through its singleton.
+    fn regs(&mut self) -> Regs<'_, Self::Block>;
+}
+
+/// PAC register block borrowed from a peripheral singleton by
+/// [`RegisterBlock::regs`].
+///
+/// Derefs to the register block. Not `Copy`: it keeps the singleton mutably
+/// borrowed for as long as it is used.
+pub struct Regs<'a, R> {
+    regs: R,
+    _borrow: PhantomData<&'a mut ()>,
+}
+
+impl<R> Deref for Regs<'_, R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &R {
+        &self.regs
+    }
+}
+
+macro_rules! impl_register_block {
+    ($($(#[$attr:meta])* $peri:ident => $regs:ty,)*) => {
+        $(
+            $(#[$attr])*
+            impl RegisterBlock for peripherals::$peri {
+                type Block = $regs;
+
+                #[inline]
+                fn regs(&mut self) -> Regs<'_, Self::Block> {
+                    crate::ownership::check_unclaimed::<Self>();
+                    Regs {
+                        regs: pac::$peri,
+                        _borrow: PhantomData,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// DMAC1/DMAC2 are left out: their channels are separate singletons that may be
+// owned by other drivers. Timers used by the time driver are left out too.
+impl_register_block! {
+    #[cfg(not(feature = "time-driver-atim1"))]
+    ATIM1 => pac::atim::Atim,
+    #[cfg(not(feature = "time-driver-gptim1"))]
+    GPTIM1 => pac::gptim::Gptim,
+    #[cfg(not(feature = "time-driver-gptim2"))]
+    GPTIM2 => pac::gptim::Gptim,
+    AUDPRC => pac::audprc::Audprc,
+    GPADC => pac::gpadc::Gpadc,
+    LCDC1 => pac::lcdc::Lcdc,
+    MPI1 => pac::mpi::Mpi,
+    MPI2 => pac::mpi::Mpi,
+    USART1 => pac::usart::Usart,
+    USART2 => pac::usart::Usart,
+    USART3 => pac::usart::Usart,
+    I2C1 => pac::i2c::I2c,
+    I2C2 => pac::i2c::I2c,
+    I2C3 => pac::i2c::I2c,
+    I2C4 => pac::i2c::I2c,
+}
@@ -7,7 +7,7 @@ pub use crate::pac::hpsys_rcc::vals::{Dllstg as DllStage, Pdiv as PclkPrescaler,
 
 use crate::time::{Hertz, MaybeHertz};
 use core::ops;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 // =============================================================================
 // Global Clock State
@@ -19,6 +19,9 @@ static CLOCK_FREQS_INIT: AtomicBool = AtomicBool::new(false);
 /// Cached HPSYS clock frequencies (LPSYS clocks are always read from hardware).
 static mut CLOCK_FREQS: Clocks = Clocks::ZERO;
 
+/// Incremented every time `CLOCK_FREQS` is updated.
+static CLOCK_GENERATION: AtomicU32 = AtomicU32::new(0);
+
 /// Sets the clock frequencies.
 ///
 /// Safety: Sets a mutable global.
@@ -26,6 +29,17 @@ pub(crate) unsafe fn set_freqs(freqs: Clocks) {
     debug!("rcc: {:?}", freqs);
     unsafe { CLOCK_FREQS = freqs };
     CLOCK_FREQS_INIT.store(true, Ordering::Release);
+    CLOCK_GENERATION.fetch_add(1, Ordering::Release);
+    crate::delay::calibrate_loop();
+}
+
+/// Clock configuration generation counter.
+///
+/// Changes whenever the cached clock frequencies are updated (init, sysclk
+/// reconfiguration, clk_peri switch, audio PLL). Drivers that derive dividers
+/// from [`clocks()`] can store this value and recompute when it differs.
+pub fn clock_generation() -> u32 {
+    CLOCK_GENERATION.load(Ordering::Acquire)
 }
 
 /// Safety: Reads a mutable global. Must be called after `set_freqs()`.
@@ -33,6 +47,36 @@ pub(crate) unsafe fn get_freqs() -> &'static Clocks {
     unsafe { &*core::ptr::addr_of!(CLOCK_FREQS) }
 }
 
+/// Cached clocks, or `None` before the first `set_freqs()`.
+pub(crate) fn cached_freqs() -> Option<&'static Clocks> {
+    if CLOCK_FREQS_INIT.load(Ordering::Acquire) {
+        Some(unsafe { get_freqs() })
+    } else {
+        None
+    }
+}
+
+// Hot-path getters. These return the value cached by the last `set_freqs()`
+// (i.e. valid for the current `clock_generation()`) and only fall back to
+// the `read_*` hardware readers before `init()`. Code running in the middle
+// of a clock switch must call the `read_*` functions directly.
+
+/// Current HCLK frequency.
+pub(crate) fn get_hclk_freq() -> Option<Hertz> {
+    match cached_freqs() {
+        Some(c) => c.hclk.to_hertz(),
+        None => super::read_hclk_freq(),
+    }
+}
+
+/// Current PCLK (APB1) frequency.
+pub(crate) fn get_pclk_freq() -> Option<Hertz> {
+    match cached_freqs() {
+        Some(c) => c.pclk.to_hertz(),
+        None => super::read_pclk_freq(),
+    }
+}
+
 /// Get the current HPSYS clock configuration.
 ///
 /// # Panics
@@ -57,59 +101,115 @@ pub const CLK_LXT32_FREQ: Hertz = Hertz(32_768);
 pub const CLK_HRC48_FREQ: Hertz = Hertz(48_000_000);
 pub const CLK_HXT48_FREQ: Hertz = Hertz(48_000_000);
 
+// =============================================================================
+// Divider helpers
+// =============================================================================
+
+/// Pick the divider that brings `src` closest to `target` without exceeding it.
+///
+/// The divider is rounded up so the resulting frequency never overshoots the
+/// request, then clamped to `1..=max_div`. Returns `(divider, actual_freq)`.
+/// A zero `target` selects `max_div` (slowest available clock).
+///
+/// Drivers with a larger minimum divider (e.g. LCDC requires >= 2) should
+/// apply it to the returned divider.
+pub const fn closest_divider(src: Hertz, target: Hertz, max_div: u32) -> (u32, Hertz) {
+    let max_div = if max_div == 0 { 1 } else { max_div };
+    let div = if target.0 == 0 {
+        max_div
+    } else {
+        let div = src.0.div_ceil(target.0);
+        if div == 0 {
+            1
+        } else if div > max_div {
+            max_div
+        } else {
+            div
+        }
+    };
+    (div, Hertz(src.0 / div))
+}
+
 // =============================================================================
 // Clock Mux
 // =============================================================================
 
+/// A clock setting that `init` either programs or leaves as it found it.
+///
+/// `Keep` is for selectors the bootloader already set up and that are unsafe
+/// to change blindly, such as the MPI1 clock while executing from flash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigOption<T> {
+    /// Leave the register untouched.
+    Keep,
+    /// Program this value.
+    Update(T),
+}
+
+impl<T: Copy> ConfigOption<T> {
+    /// The value to program, if any.
+    pub const fn get(&self) -> Option<T> {
+        match *self {
+            ConfigOption::Keep => None,
+            ConfigOption::Update(v) => Some(v),
+        }
+    }
+}
+
 /// Clock multiplexer configuration
+///
+/// `init` applies the selectors in field order, after DLL1/DLL2 are locked.
 #[non_exhaustive]
 pub struct ClockMux {
-    pub rtcsel: Rtcsel,
-    pub wdtsel: Wdtsel,
+    /// RTC clock source (default: keep). Not supported yet: any update is
+    /// rejected with [`Error::SourceNotSupported`](super::Error::SourceNotSupported).
+    pub rtcsel: ConfigOption<Rtcsel>,
+    /// WDT / low-power clock source (default: LRC32).
+    pub wdtsel: ConfigOption<Wdtsel>,
     pub usbsel: Usbsel,
     pub perisel: Perisel,
-    pub mpi1sel: Mpisel,
-    pub mpi2sel: Mpisel,
-    pub ticksel: Ticksel,
-    pub lpsel: Lpsel,
+    /// MPI1 (Flash1) clock source (default: keep).
+    ///
+    /// The MPI divider is not touched, so the new source must not overclock
+    /// the flash with the divider the bootloader set.
+    pub mpi1sel: ConfigOption<Mpisel>,
+    /// MPI2 (Flash2/PSRAM) clock source (default: keep). Same caveat as
+    /// `mpi1sel`.
+    pub mpi2sel: ConfigOption<Mpisel>,
+    /// SysTick reference clock (default: clk_rtc).
+    pub ticksel: ConfigOption<Ticksel>,
+    /// Whether clk_hpsys follows `sys` or runs from clk_wdt (default: keep).
+    pub lpsel: ConfigOption<Lpsel>,
 }
 
 impl Default for ClockMux {
     fn default() -> Self {
-        Self {
-            rtcsel: Rtcsel::Lrc10,
-            wdtsel: Wdtsel::Lrc32,
-            usbsel: Usbsel::Sysclk,
-            perisel: Perisel::Hxt48,
-            mpi1sel: Mpisel::Peri,
-            mpi2sel: Mpisel::Dll2,
-            ticksel: Ticksel::ClkRtc,
-            lpsel: Lpsel::SelSys,
-        }
+        Self::new()
     }
 }
 
 impl ClockMux {
     pub const fn new() -> Self {
         Self {
-            rtcsel: Rtcsel::Lrc10,
-            wdtsel: Wdtsel::Lrc32,
+            rtcsel: ConfigOption::Keep,
+            wdtsel: ConfigOption::Update(Wdtsel::Lrc32),
             usbsel: Usbsel::Sysclk,
             perisel: Perisel::Hxt48,
-            mpi1sel: Mpisel::Peri,
-            mpi2sel: Mpisel::Dll2,
-            ticksel: Ticksel::ClkRtc,
-            lpsel: Lpsel::SelSys,
+            mpi1sel: ConfigOption::Keep,
+            mpi2sel: ConfigOption::Keep,
+            ticksel: ConfigOption::Update(Ticksel::ClkRtc),
+            lpsel: ConfigOption::Keep,
         }
     }
 
     pub const fn with_rtcsel(mut self, rtcsel: Rtcsel) -> Self {
-        self.rtcsel = rtcsel;
+        self.rtcsel = ConfigOption::Update(rtcsel);
         self
     }
 
     pub const fn with_wdtsel(mut self, wdtsel: Wdtsel) -> Self {
-        self.wdtsel = wdtsel;
+        self.wdtsel = ConfigOption::Update(wdtsel);
         self
     }
 
@@ -124,22 +224,22 @@ impl ClockMux {
     }
 
     pub const fn with_mpi1sel(mut self, mpi1sel: Mpisel) -> Self {
-        self.mpi1sel = mpi1sel;
+        self.mpi1sel = ConfigOption::Update(mpi1sel);
         self
     }
 
     pub const fn with_mpi2sel(mut self, mpi2sel: Mpisel) -> Self {
-        self.mpi2sel = mpi2sel;
+        self.mpi2sel = ConfigOption::Update(mpi2sel);
         self
     }
 
     pub const fn with_ticksel(mut self, ticksel: Ticksel) -> Self {
-        self.ticksel = ticksel;
+        self.ticksel = ConfigOption::Update(ticksel);
         self
     }
 
     pub const fn with_lpsel(mut self, lpsel: Lpsel) -> Self {
-        self.lpsel = lpsel;
+        self.lpsel = ConfigOption::Update(lpsel);
         self
     }
 }
@@ -217,8 +317,17 @@ impl Dll {
         }
     }
 
+    /// Oscillator frequency before the output divider: `(stg + 1) * 24 MHz`.
+    ///
+    /// The 4-bit stage keeps this within the DLL lock range (24-384 MHz)
+    /// for every value.
+    pub const fn vco_hz(&self) -> u32 {
+        24_000_000 * (self.stg.to_bits() as u32 + 1)
+    }
+
+    /// Output frequency: [`vco_hz`](Self::vco_hz), halved by `out_div2`.
     pub const fn freq_hz(&self) -> u32 {
-        let base = 24_000_000 * (self.stg.to_bits() as u32 + 1);
+        let base = self.vco_hz();
         if self.out_div2 {
             base / 2
         } else {
@@ -2,18 +2,138 @@
 
 use crate::cortex_m_blocking_delay_us;
 use crate::pac::hpsys_rcc::vals::mux::Perisel;
-use crate::pac::{HPSYS_AON, HPSYS_RCC};
+use crate::pac::{HPSYS_AON, HPSYS_RCC, MPI1, MPI2};
 use crate::pac::{HPSYS_CFG, PMUC};
 use crate::time::Hertz;
 use core::sync::atomic::{compiler_fence, Ordering};
 
+use super::{get_clk_dll1_freq, get_clk_dll2_freq, get_clk_peri_freq};
 use super::{get_freqs, set_freqs};
-use super::{get_hclk_freq, read_hpsys_clocks_from_hw};
+use super::{read_hclk_freq, read_hpsys_clocks_from_hw};
 use super::{
-    ClockMux, Clocks, Dll, DllStage, HclkPrescaler, Lpsel, Mpisel, PclkPrescaler, Rtcsel, Sysclk,
-    Ticksel, Usbsel, Wdtsel,
+    ClockMux, Clocks, Dll, DllStage, HclkPrescaler, Mpisel, PclkPrescaler, Sysclk, Ticksel, Usbsel,
+    Wdtsel,
 };
 
+/// Clock domain whose source selection failed validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockDomain {
+    /// System clock (`sys`).
+    Sysclk,
+    /// MPI1 (Flash1) clock (`mux.mpi1sel`).
+    Mpi1,
+    /// MPI2 (Flash2/PSRAM) clock (`mux.mpi2sel`).
+    Mpi2,
+    /// USB clock (`mux.usbsel`).
+    Usb,
+    /// Peripheral clock (`clk_peri`, see [`set_peri_source`]).
+    Peri,
+    /// RTC clock (`mux.rtcsel`).
+    Rtc,
+}
+
+/// RCC configuration error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Error {
+    /// A clock domain selects DLL1/DLL2, but that DLL has no configuration.
+    SourceNotConfigured { domain: ClockDomain },
+    /// A clock domain selects a source this driver does not support yet
+    /// (DBL96), or asks for a switch it cannot make yet (`mux.rtcsel`).
+    SourceNotSupported { domain: ClockDomain },
+    /// HXT48 did not report ready within the timeout (crystal missing or faulty).
+    Hxt48Timeout,
+    /// DLL2, HCLK, PCLK1 or PCLK2 exceeds the chip limit (see
+    /// [`ConfigBuilder::validate_frequencies`]).
+    FrequencyTooHigh { hz: u32, max_hz: u32 },
+    /// An HRC48 trim code does not fit `HRC_CR.FREQ_TRIM` (see
+    /// [`apply_hrc48_trim`]).
+    InvalidHrc48Trim { trim: u16 },
+    /// The requested source is not running (oscillator off or DLL not
+    /// locked), see [`set_sys_source`].
+    SourceNotReady { domain: ClockDomain },
+    /// An MPI flash clock would exceed the flash part's rated limit (see
+    /// [`ConfigBuilder::validate_flash_speed`]).
+    FlashTooFast {
+        /// 1 for MPI1, 2 for MPI2.
+        mpi: u8,
+        hz: u32,
+        max_hz: u32,
+    },
+}
+
+impl Error {
+    /// Static description, usable from `const` validation.
+    pub const fn message(&self) -> &'static str {
+        match self {
+            Error::SourceNotConfigured { domain } => match domain {
+                ClockDomain::Sysclk => "sysclk is set to DLL1, but dll1 is None",
+                ClockDomain::Mpi1 => "MPI1 clock source is set to a DLL that is not configured",
+                ClockDomain::Mpi2 => "MPI2 clock source is set to a DLL that is not configured",
+                ClockDomain::Usb => "USB clock source is set to DLL2, but dll2 is None",
+                // Never returned: clk_peri selects HRC48/HXT48 only, and a
+                // stopped one is reported as `SourceNotReady`.
+                ClockDomain::Peri => "clk_peri has no DLL source to configure",
+                // Never returned: the RTC source is rejected as unsupported.
+                ClockDomain::Rtc => "RTC clock source is not configured",
+            },
+            Error::SourceNotSupported { domain } => match domain {
+                ClockDomain::Rtc => "rtcsel cannot be applied, RTC is not in the PAC yet",
+                _ => "DBL96 is not implemented yet",
+            },
+            Error::Hxt48Timeout => "HXT48 did not become ready",
+            Error::FrequencyTooHigh { .. } => "clock frequency exceeds the chip limit",
+            Error::InvalidHrc48Trim { .. } => "HRC48 trim code out of range",
+            Error::SourceNotReady { domain } => match domain {
+                ClockDomain::Peri => "clk_peri source oscillator is not running",
+                _ => "clock source is not ready",
+            },
+            Error::FlashTooFast { .. } => "MPI flash clock exceeds the flash part's limit",
+        }
+    }
+}
+
+/// Check HCLK against the 240 MHz chip limit and PCLK1/PCLK2 against the
+/// limits of the DVFS mode that HCLK implies (S mode above 48 MHz).
+const fn validate_bus_freqs(
+    hclk_hz: u32,
+    pdiv1: PclkPrescaler,
+    pdiv2: PclkPrescaler,
+) -> Result<(), Error> {
+    const HCLK_MAX_HZ: u32 = 240_000_000;
+    if cfg!(feature = "unchecked-overclocking") {
+        return Ok(());
+    }
+    if hclk_hz > HCLK_MAX_HZ {
+        return Err(Error::FrequencyTooHigh {
+            hz: hclk_hz,
+            max_hz: HCLK_MAX_HZ,
+        });
+    }
+    let (pclk1_max, pclk2_max) = if hclk_hz > 48_000_000 {
+        (120_000_000, 7_500_000)
+    } else {
+        (48_000_000, 6_000_000)
+    };
+    let pclk1_hz = hclk_hz >> (pdiv1 as u32);
+    if pclk1_hz > pclk1_max {
+        return Err(Error::FrequencyTooHigh {
+            hz: pclk1_hz,
+            max_hz: pclk1_max,
+        });
+    }
+    let pclk2_hz = hclk_hz >> (pdiv2 as u32);
+    if pclk2_hz > pclk2_max {
+        return Err(Error::FrequencyTooHigh {
+            hz: pclk2_hz,
+            max_hz: pclk2_max,
+        });
+    }
+    Ok(())
+}
+
 /// Clock configuration
 ///
 /// hdiv, pdiv1, pdiv2 = 1, 1, 6 by default SDK settings
@@ -50,6 +170,14 @@ pub struct ConfigBuilder {
     /// Set to `false` if USB is not used, allowing more flexible clock configurations.
     pub usb: bool,
 
+    /// Rated clock limit of the flash on MPI1, checked by
+    /// [`validate_flash_speed`](Self::validate_flash_speed). `None` (default)
+    /// skips the check.
+    pub mpi1_max_hz: Option<u32>,
+    /// Rated clock limit of the flash/PSRAM on MPI2. `None` (default) skips
+    /// the check.
+    pub mpi2_max_hz: Option<u32>,
+
     // pub audpll: Option<AudPll>,
     // Clock mux configuration
     pub mux: ClockMux,
@@ -72,6 +200,8 @@ impl Default for ConfigBuilder {
             }),
             hrc48_calibrate: true, // Calibrate HRC48 by default for better accuracy
             usb: true,
+            mpi1_max_hz: None,
+            mpi2_max_hz: None,
             mux: ClockMux::default(),
         }
     }
@@ -94,6 +224,8 @@ impl ConfigBuilder {
             }),
             hrc48_calibrate: true,
             usb: true,
+            mpi1_max_hz: None,
+            mpi2_max_hz: None,
             mux: ClockMux::new(),
         }
     }
@@ -112,17 +244,10 @@ impl ConfigBuilder {
             dll2: None,
             hrc48_calibrate: false,
             usb: false,
-            mux: ClockMux {
-                // MPI defaults to Peri (no DLL dependency) for safe validation
-                mpi1sel: Mpisel::Peri,
-                mpi2sel: Mpisel::Peri,
-                rtcsel: Rtcsel::Lrc10,
-                wdtsel: Wdtsel::Lrc32,
-                usbsel: Usbsel::Sysclk,
-                perisel: Perisel::Hxt48,
-                ticksel: Ticksel::ClkRtc,
-                lpsel: Lpsel::SelSys,
-            },
+            mpi1_max_hz: None,
+            mpi2_max_hz: None,
+            // MPI selectors default to `Keep` (no DLL dependency)
+            mux: ClockMux::new(),
         }
     }
 
@@ -171,63 +296,92 @@ impl ConfigBuilder {
         self
     }
 
-    /// Validate the clock configuration at compile time.
-    ///
-    /// Panics with a descriptive message if the configuration is invalid.
-    /// Use inside `const { }` blocks to get compile-time errors.
+    pub const fn with_mpi1_max_hz(mut self, hz: u32) -> Self {
+        self.mpi1_max_hz = Some(hz);
+        self
+    }
+
+    pub const fn with_mpi2_max_hz(mut self, hz: u32) -> Self {
+        self.mpi2_max_hz = Some(hz);
+        self
+    }
+
+    /// Check that every mux selecting a DLL has that DLL configured, and
+    /// that no mux asks for a source the driver cannot apply yet.
     ///
-    /// Note: Uses `::core::panic!` to bypass defmt's panic override,
-    /// which is not const-compatible.
-    pub const fn check(&self) {
-        // Check sysclk source consistency
+    /// This is the source-ordering part of [`check`](Self::check), returned as
+    /// an error instead of a panic so runtime-built configs can be rejected
+    /// gracefully.
+    pub const fn validate_sources(&self) -> Result<(), Error> {
         match self.sys {
             Sysclk::Dll1 => {
                 if self.dll1.is_none() {
-                    ::core::panic!("sysclk is set to DLL1, but dll1 is None");
+                    return Err(Error::SourceNotConfigured {
+                        domain: ClockDomain::Sysclk,
+                    });
                 }
             }
             Sysclk::Hrc48 | Sysclk::Hxt48 => {}
             Sysclk::Dbl96 => {
-                ::core::panic!("DBL96 is not implemented yet");
+                return Err(Error::SourceNotSupported {
+                    domain: ClockDomain::Sysclk,
+                });
             }
         }
 
-        // Check MPI (Flash/PSRAM) clock source consistency
-        match self.mux.mpi1sel {
-            Mpisel::Dll2 => {
-                if self.dll2.is_none() {
-                    ::core::panic!("MPI1 clock source is set to DLL2, but dll2 is None");
-                }
-            }
-            Mpisel::Dll1 => {
-                if self.dll1.is_none() {
-                    ::core::panic!("MPI1 clock source is set to DLL1, but dll1 is None");
-                }
-            }
-            _ => {}
+        if self.mux.rtcsel.get().is_some() {
+            return Err(Error::SourceNotSupported {
+                domain: ClockDomain::Rtc,
+            });
         }
-        match self.mux.mpi2sel {
-            Mpisel::Dll2 => {
-                if self.dll2.is_none() {
-                    ::core::panic!("MPI2 clock source is set to DLL2, but dll2 is None");
-                }
-            }
-            Mpisel::Dll1 => {
-                if self.dll1.is_none() {
-                    ::core::panic!("MPI2 clock source is set to DLL1, but dll1 is None");
-                }
-            }
-            _ => {}
+
+        if !self.mpi_source_configured(self.mux.mpi1sel.get()) {
+            return Err(Error::SourceNotConfigured {
+                domain: ClockDomain::Mpi1,
+            });
+        }
+        if !self.mpi_source_configured(self.mux.mpi2sel.get()) {
+            return Err(Error::SourceNotConfigured {
+                domain: ClockDomain::Mpi2,
+            });
         }
 
-        // Check USB clock constraints (only when USB is enabled)
         if self.usb {
             if let Usbsel::Dll2 = self.mux.usbsel {
                 if self.dll2.is_none() {
-                    ::core::panic!("USB clock source is set to DLL2, but dll2 is None");
+                    return Err(Error::SourceNotConfigured {
+                        domain: ClockDomain::Usb,
+                    });
                 }
             }
+        }
+
+        Ok(())
+    }
+
+    const fn mpi_source_configured(&self, sel: Option<Mpisel>) -> bool {
+        match sel {
+            Some(Mpisel::Dll1) => self.dll1.is_some(),
+            Some(Mpisel::Dll2) => self.dll2.is_some(),
+            _ => true,
+        }
+    }
+
+    /// Validate the clock configuration at compile time.
+    ///
+    /// Panics with a descriptive message if the configuration is invalid.
+    /// Use inside `const { }` blocks to get compile-time errors.
+    ///
+    /// Note: Uses `::core::panic!` to bypass defmt's panic override,
+    /// which is not const-compatible.
+    pub const fn check(&self) {
+        // Check clock source consistency
+        if let Err(e) = self.validate_sources() {
+            ::core::panic!("{}", e.message());
+        }
 
+        // Check USB clock constraints (only when USB is enabled)
+        if self.usb {
             let usb_source_freq = match self.mux.usbsel {
                 Usbsel::Sysclk => self.get_sysclk_freq_hz(),
                 Usbsel::Dll2 => match self.dll2 {
@@ -290,22 +444,10 @@ impl ConfigBuilder {
             );
         }
 
-        // Check DLL1 frequency range if configured
-        if let Some(dll1) = self.dll1 {
-            let dll1_freq = 24_000_000 * (dll1.stg.to_bits() as u32 + 1);
-            const_rcc_assert!(
-                dll1_freq >= 24_000_000 && dll1_freq <= 384_000_000,
-                "DLL1 frequency out of valid range (24-384 MHz)"
-            );
-        }
-
-        // Check DLL2 frequency range if configured
+        // Every `DllStage` lies within the DLL lock range (24-384 MHz), so
+        // only the DVFS limit on DLL2 needs checking.
         if let Some(dll2) = self.dll2 {
-            let dll2_freq = 24_000_000 * (dll2.stg.to_bits() as u32 + 1);
-            const_rcc_assert!(
-                dll2_freq >= 24_000_000 && dll2_freq <= 384_000_000,
-                "DLL2 frequency out of valid range (24-384 MHz)"
-            );
+            let dll2_freq = dll2.vco_hz();
 
             // Check DLL2 vs DVFS mode limit
             let hclk_mhz = hclk_hz / 1_000_000;
@@ -318,6 +460,92 @@ impl ConfigBuilder {
         }
     }
 
+    /// Check DLL2 against the 288 MHz S-mode limit (its frequency before
+    /// `out_div2`, as in [`check`](Self::check)), HCLK against the 240 MHz
+    /// chip limit and PCLK1/PCLK2 against the limits
+    /// of the resulting DVFS mode. All SF32LB52x revisions share these limits.
+    ///
+    /// Always `Ok` with the `unchecked-overclocking` feature.
+    pub const fn validate_frequencies(&self) -> Result<(), Error> {
+        if cfg!(feature = "unchecked-overclocking") {
+            return Ok(());
+        }
+
+        const DLL2_S_MODE_MAX_HZ: u32 = 288_000_000;
+
+        // An unconfigured sysclk source is reported by `validate_sources`.
+        if self.validate_sources().is_err() {
+            return Ok(());
+        }
+        let hclk_hz = self.get_hclk_freq_hz();
+        if let Some(dll2) = self.dll2 {
+            if hclk_hz > 48_000_000 && dll2.vco_hz() > DLL2_S_MODE_MAX_HZ {
+                return Err(Error::FrequencyTooHigh {
+                    hz: dll2.vco_hz(),
+                    max_hz: DLL2_S_MODE_MAX_HZ,
+                });
+            }
+        }
+        validate_bus_freqs(hclk_hz, self.pdiv1, self.pdiv2)
+    }
+
+    /// Check that the MPI1/MPI2 flash clocks stay within
+    /// [`mpi1_max_hz`](Self::mpi1_max_hz)/[`mpi2_max_hz`](Self::mpi2_max_hz)
+    /// once this configuration is applied.
+    ///
+    /// The flash clock is the MPI kernel clock (`mux.mpi1sel`/`mpi2sel`, or
+    /// the current selection for `Keep`) divided by the MPI's `PSCLR`
+    /// divider. The bootloader programs that divider for the flash part and
+    /// this HAL leaves it alone, so raising a DLL that feeds XIP flash past
+    /// what the divider allows makes the next instruction fetch hard-fault.
+    /// DLLs this configuration does not set are taken at their running
+    /// frequency.
+    ///
+    /// Reads the mux and dividers from hardware, so unlike
+    /// [`validate_frequencies`](Self::validate_frequencies) this is not
+    /// `const`. [`init`](crate::init) panics and [`reconfigure_sysclk`]
+    /// returns the error before any clock is changed.
+    pub fn validate_flash_speed(&self) -> Result<(), Error> {
+        let csr = HPSYS_RCC.csr().read();
+        let mpi1_sel = self.mux.mpi1sel.get().unwrap_or(csr.sel_mpi1());
+        let mpi2_sel = self.mux.mpi2sel.get().unwrap_or(csr.sel_mpi2());
+        let mpis = [
+            (1, self.mpi1_max_hz, mpi1_sel, MPI1),
+            (2, self.mpi2_max_hz, mpi2_sel, MPI2),
+        ];
+        for (mpi, max_hz, sel, regs) in mpis {
+            let Some(max_hz) = max_hz else {
+                continue;
+            };
+            let Some(src_hz) = self.mpi_source_hz(sel) else {
+                continue;
+            };
+            // PSCLR.DIV = 0 passes the kernel clock through, like 1.
+            let hz = src_hz / regs.psclr().read().div().max(1) as u32;
+            if hz > max_hz {
+                return Err(Error::FlashTooFast { mpi, hz, max_hz });
+            }
+        }
+        Ok(())
+    }
+
+    /// Frequency of MPI kernel clock source `sel` under this configuration.
+    fn mpi_source_hz(&self, sel: Mpisel) -> Option<u32> {
+        let hz = match sel {
+            Mpisel::Peri => get_clk_peri_freq(),
+            Mpisel::Dll1 => match self.dll1 {
+                Some(dll1) => Some(Hertz(dll1.freq_hz())),
+                None => get_clk_dll1_freq(),
+            },
+            Mpisel::Dll2 => match self.dll2 {
+                Some(dll2) => Some(Hertz(dll2.freq_hz())),
+                None => get_clk_dll2_freq(),
+            },
+            _ => None,
+        };
+        hz.map(|f| f.0)
+    }
+
     /// Validate and return a [`Config`]. Use in `const { }` blocks for compile-time checking.
     ///
     /// ```rust,ignore
@@ -328,6 +556,23 @@ impl ConfigBuilder {
         Config(self)
     }
 
+    /// Like [`checked`](Self::checked), but reports an unconfigured clock
+    /// source as [`Error::SourceNotConfigured`] and an over-limit DLL2, HCLK
+    /// or PCLK as [`Error::FrequencyTooHigh`] instead of panicking.
+    ///
+    /// Intended for configurations assembled at runtime. The remaining limits
+    /// (sysclk range, USB) still panic, as in [`check`](Self::check).
+    pub const fn try_checked(self) -> Result<Config, Error> {
+        if let Err(e) = self.validate_sources() {
+            return Err(e);
+        }
+        if let Err(e) = self.validate_frequencies() {
+            return Err(e);
+        }
+        self.check();
+        Ok(Config(self))
+    }
+
     pub(crate) const fn get_sysclk_freq_hz(&self) -> u32 {
         match self.sys {
             Sysclk::Hrc48 | Sysclk::Hxt48 => 48_000_000,
@@ -437,14 +682,19 @@ fn apply_dividers_and_sysclk(config: &ConfigBuilder) {
 ///
 /// Use [`ConfigBuilder::sysclk()`] to create a configuration suitable for runtime switching.
 ///
+/// Fails with [`Error::FlashTooFast`], leaving the clocks untouched, if the
+/// new DLL1 frequency would overclock flash on an MPI clocked from DLL1 (see
+/// [`ConfigBuilder::validate_flash_speed`]).
+///
 /// // TODO: once peripheral drivers start borrowing `&'d Hclk` / `&'d Pclk` tokens,
 /// // this function should take `&mut Hclk, &mut Pclk, &mut Pclk2` to enforce at
 /// // compile time that no peripheral is using those clock domains during reconfiguration.
 /// // Currently no driver borrows these tokens, so the mechanism is not yet active.
 #[cfg(not(feature = "time-driver-gptim1"))]
-pub fn reconfigure_sysclk(config: Config) {
+pub fn reconfigure_sysclk(config: Config) -> Result<(), Error> {
     let config = &config.0;
-    let current_hclk = get_hclk_freq().unwrap_or(Hertz(48_000_000));
+    config.validate_flash_speed()?;
+    let current_hclk = read_hclk_freq().unwrap_or(Hertz(48_000_000));
     let target_hclk = config.get_hclk_freq();
 
     crate::pmu::dvfs::config_hcpu_dvfs(current_hclk, target_hclk, || {
@@ -468,6 +718,7 @@ pub fn reconfigure_sysclk(config: Config) {
             ..hw
         });
     }
+    Ok(())
 }
 
 // =============================================================================
@@ -478,6 +729,10 @@ pub fn reconfigure_sysclk(config: Config) {
 pub(crate) unsafe fn init(config: Config) {
     let config = &config.0;
 
+    if let Err(e) = config.validate_flash_speed() {
+        panic!("rcc: {:?}", e);
+    }
+
     // not switch back to XT48 if other clock source has been selected already
     if HPSYS_RCC.csr().read().sel_sys() == Sysclk::Hxt48 {
         // HAL_HPAON_EnableXT48
@@ -542,7 +797,7 @@ pub(crate) unsafe fn init(config: Config) {
         });
 
         let hclk = config.get_hclk_freq();
-        let current_hclk = get_hclk_freq().unwrap_or(Hertz(48_000_000));
+        let current_hclk = read_hclk_freq().unwrap_or(Hertz(48_000_000));
 
         crate::pmu::dvfs::config_hcpu_dvfs(current_hclk, hclk, || {
             switch_away_from_dll1();
@@ -636,21 +891,7 @@ pub(crate) unsafe fn init(config: Config) {
             w.set_sel_peri(config.mux.perisel);
         });
 
-        // Configure low-power clock (for WDT) according to config.mux.wdtsel
-        PMUC.cr().modify(|w| {
-            w.set_sel_lpclk(config.mux.wdtsel);
-        });
-
-        // Configure RTC clock
-        // TODO: Uncomment when RTC peripheral is added to PAC
-        // crate::pac::RTC.cr().modify(|w| {
-        //     w.set_lpcksel(config.mux.rtcsel);
-        // });
-
-        // Configure system tick clock according to config.mux.ticksel
-        HPSYS_RCC
-            .csr()
-            .modify(|w| w.set_sel_tick(config.mux.ticksel));
+        apply_mux(&config.mux);
     }
 
     // Store the final clock frequencies for later access via clocks()
@@ -661,10 +902,244 @@ pub(crate) unsafe fn init(config: Config) {
     set_freqs(final_clocks);
 }
 
+/// Apply the `ConfigOption` selectors of `mux`, in field order.
+///
+/// Runs after DLL1/DLL2 are configured; each source is waited on before it
+/// is selected.
+fn apply_mux(mux: &ClockMux) {
+    // `rtcsel` is rejected by `validate_sources` until the RTC is in the PAC.
+    // TODO: crate::pac::RTC.cr().modify(|w| w.set_lpcksel(rtcsel));
+
+    // LRC10 is always on, LRC32 was enabled above
+    if let Some(wdtsel) = mux.wdtsel.get() {
+        PMUC.cr().modify(|w| w.set_sel_lpclk(wdtsel));
+    }
+
+    if let Some(sel) = mux.mpi1sel.get() {
+        wait_mpi_source(sel);
+        HPSYS_RCC.csr().modify(|w| w.set_sel_mpi1(sel));
+    }
+    if let Some(sel) = mux.mpi2sel.get() {
+        wait_mpi_source(sel);
+        HPSYS_RCC.csr().modify(|w| w.set_sel_mpi2(sel));
+    }
+
+    if let Some(ticksel) = mux.ticksel.get() {
+        match ticksel {
+            Ticksel::Hrc48 => while !HPSYS_AON.acr().read().hrc48_rdy() {},
+            Ticksel::Hxt48 => while !HPSYS_AON.acr().read().hxt48_rdy() {},
+            _ => {}
+        }
+        HPSYS_RCC.csr().modify(|w| w.set_sel_tick(ticksel));
+    }
+
+    if let Some(lpsel) = mux.lpsel.get() {
+        HPSYS_RCC.csr().modify(|w| w.set_sel_sys_lp(lpsel));
+    }
+}
+
+/// Wait until the DLL behind `sel` is locked.
+fn wait_mpi_source(sel: Mpisel) {
+    let dll = match sel {
+        Mpisel::Dll1 => 0,
+        Mpisel::Dll2 => 1,
+        _ => return,
+    };
+    while !HPSYS_RCC.dllcr(dll).read().ready() {}
+}
+
+/// Switch the peripheral clock (clk_peri) source at runtime.
+///
+/// HXT48 gives better accuracy (UART baud rates, I2C timing); HRC48 starts
+/// faster. The target oscillator is not enabled by this function: it waits up
+/// to 2 ms for its ready flag and rejects the switch with
+/// [`Error::SourceNotReady`] if it never comes up.
+///
+/// On success the clocks cache is updated, which bumps [`clock_generation`](super::clock_generation)
+/// so drivers clocked from clk_peri (and MPI on `Mpisel::Peri`) can recompute
+/// their dividers.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// rcc::set_peri_source(rcc::Perisel::Hrc48)?;
+/// ```
+pub fn set_peri_source(source: Perisel) -> Result<(), Error> {
+    const READY_TIMEOUT_US: u32 = 2_000;
+
+    let is_ready = || {
+        let acr = HPSYS_AON.acr().read();
+        match source {
+            Perisel::Hrc48 => acr.hrc48_rdy(),
+            Perisel::Hxt48 => acr.hxt48_rdy(),
+        }
+    };
+
+    let mut waited_us = 0;
+    while !is_ready() {
+        if waited_us >= READY_TIMEOUT_US {
+            return Err(Error::SourceNotReady {
+                domain: ClockDomain::Peri,
+            });
+        }
+        cortex_m_blocking_delay_us(10);
+        waited_us += 10;
+    }
+
+    if HPSYS_RCC.csr().read().sel_peri() == source {
+        return Ok(());
+    }
+
+    HPSYS_RCC.csr().modify(|w| w.set_sel_peri(source));
+
+    unsafe {
+        let prev = *get_freqs();
+        let hw = read_hpsys_clocks_from_hw();
+        set_freqs(Clocks {
+            // Preserve audio PLL state (managed by AUDCODEC driver)
+            clk_aud_pll: prev.clk_aud_pll,
+            clk_aud_pll_div16: prev.clk_aud_pll_div16,
+            ..hw
+        });
+    }
+
+    Ok(())
+}
+
+/// Switch the system clock (clk_sys) source at runtime.
+///
+/// Meant for dropping to 48 MHz while idle and back to DLL1 for bursts of
+/// work. The target must already be running: HRC48 and HXT48 must report
+/// ready (see [`enable_hxt48`]) and DLL1 must be enabled and locked, e.g.
+/// left running from [`init`](crate::init) or set up again with
+/// [`reconfigure_sysclk`]. Otherwise the switch is rejected with
+/// [`Error::SourceNotReady`] and clk_sys is left untouched. The clk_sys mux
+/// switches glitch-free between running sources.
+///
+/// The HCLK/PCLK dividers are kept, so HCLK follows the source frequency, and
+/// the core voltage (DVFS mode) is adjusted around the switch. If the kept
+/// dividers would put HCLK or PCLK1/PCLK2 over its limit at the new source
+/// frequency, the switch is rejected with [`Error::FrequencyTooHigh`] before
+/// anything is touched; raise the dividers with [`reconfigure_sysclk`] first. When leaving
+/// DLL1 it is powered down unless MPI1/MPI2 run from it; switching back then
+/// fails until DLL1 is reconfigured with [`reconfigure_sysclk`]. The clocks
+/// cache is refreshed, which bumps [`clock_generation`](super::clock_generation).
+///
+/// USB clocked from `Usbsel::Sysclk` follows clk_sys and stops working at
+/// 48 MHz; move it to DLL2 or stop it first.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Idle: 240 MHz -> 48 MHz, DLL1 off.
+/// rcc::set_sys_source(rcc::Sysclk::Hrc48)?;
+/// ```
+#[cfg(not(feature = "time-driver-gptim1"))]
+pub fn set_sys_source(source: Sysclk) -> Result<(), Error> {
+    let not_ready = Error::SourceNotReady {
+        domain: ClockDomain::Sysclk,
+    };
+    let acr = HPSYS_AON.acr().read();
+    let source_hz = match source {
+        Sysclk::Hrc48 if acr.hrc48_rdy() => 48_000_000,
+        Sysclk::Hxt48 if acr.hxt48_rdy() => 48_000_000,
+        Sysclk::Dll1 => match super::get_clk_dll1_freq() {
+            Some(hz) if HPSYS_RCC.dllcr(0).read().ready() => hz.0,
+            _ => return Err(not_ready),
+        },
+        Sysclk::Dbl96 => {
+            return Err(Error::SourceNotSupported {
+                domain: ClockDomain::Sysclk,
+            })
+        }
+        _ => return Err(not_ready),
+    };
+
+    let previous = HPSYS_RCC.csr().read().sel_sys();
+    if previous == source {
+        return Ok(());
+    }
+
+    let cfgr = HPSYS_RCC.cfgr().read();
+    let target_hclk = Hertz(source_hz / (cfgr.hdiv().max(1) as u32));
+    validate_bus_freqs(target_hclk.0, cfgr.pdiv1(), cfgr.pdiv2())?;
+    let current_hclk = read_hclk_freq().unwrap_or(Hertz(48_000_000));
+
+    crate::pmu::dvfs::config_hcpu_dvfs(current_hclk, target_hclk, || {
+        HPSYS_RCC.csr().modify(|w| w.set_sel_sys(source));
+    });
+
+    if previous == Sysclk::Dll1 {
+        let csr = HPSYS_RCC.csr().read();
+        if csr.sel_mpi1() != Mpisel::Dll1 && csr.sel_mpi2() != Mpisel::Dll1 {
+            HPSYS_RCC.dllcr(0).modify(|w| w.set_en(false));
+        }
+    }
+
+    unsafe {
+        let prev = *get_freqs();
+        let hw = read_hpsys_clocks_from_hw();
+        set_freqs(Clocks {
+            // Preserve audio PLL state (managed by AUDCODEC driver)
+            clk_aud_pll: prev.clk_aud_pll,
+            clk_aud_pll_div16: prev.clk_aud_pll_div16,
+            ..hw
+        });
+    }
+
+    Ok(())
+}
+
+/// Request the 48 MHz crystal oscillator (HXT48) and wait until it is ready.
+///
+/// Use before selecting HXT48 for clk_sys or clk_peri (see
+/// [`set_peri_source`]), before [`calibrate_hrc48`], and before starting the
+/// audio PLL, which runs from the HXT audio buffer. Returns immediately if the
+/// crystal is already running.
+///
+/// Polls the ready flag every 10 us for up to `timeout_us`. A crystal
+/// typically starts within 1-2 ms; [`Error::Hxt48Timeout`] usually means none
+/// is populated. The request stays set on timeout, so a slow crystal can still
+/// come up later.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// rcc::enable_hxt48(5_000)?;
+/// rcc::set_peri_source(rcc::Perisel::Hxt48)?;
+/// ```
+pub fn enable_hxt48(timeout_us: u32) -> Result<(), Error> {
+    if HPSYS_AON.acr().read().hxt48_rdy() {
+        return Ok(());
+    }
+
+    // HAL_HPAON_EnableXT48
+    HPSYS_AON.acr().modify(|w| w.set_hxt48_req(true));
+
+    let mut waited_us = 0;
+    while !HPSYS_AON.acr().read().hxt48_rdy() {
+        if waited_us >= timeout_us {
+            warn!("rcc: HXT48 not ready after {} us", timeout_us);
+            return Err(Error::Hxt48Timeout);
+        }
+        cortex_m_blocking_delay_us(10);
+        waited_us += 10;
+    }
+    debug!("rcc: HXT48 ready after ~{} us", waited_us);
+    Ok(())
+}
+
 /// Calibrate HRC48 (48MHz internal RC oscillator) against HXT48 (external crystal)
 ///
 /// Uses binary search algorithm to adjust HRC48 frequency trim to match HXT48.
 ///
+/// The search stops once HRC48 is within 64 counts of HXT48 over a
+/// 16383-cycle window (about 0.4 %) and fails above 160 counts (about 1 %).
+/// An untrimmed HRC48 can be several percent off, which is outside what a
+/// UART receiver tolerates. The trim is not stored in EFUSE on SF32LB52x;
+/// boards without a crystal have to carry a measured value over with
+/// [`hrc48_trim`] / [`apply_hrc48_trim`].
+///
 /// # Returns
 /// - `Ok(())` if calibration succeeds (frequency difference < 160 counts)
 /// - `Err(&str)` if calibration fails or HXT48 is not ready
@@ -737,3 +1212,87 @@ pub unsafe fn calibrate_hrc48() -> Result<(), &'static str> {
         Ok(())
     }
 }
+
+/// Largest HRC48 trim code (`HRC_CR.FREQ_TRIM` is 10 bits wide).
+pub const HRC48_TRIM_MAX: u16 = 0x3FF;
+
+/// Current HRC48 frequency trim code.
+///
+/// After [`calibrate_hrc48`] this is the calibrated value. Store it (e.g. in
+/// flash with [`mpi::Flash`](crate::mpi::Flash)) on a unit or during a
+/// production step where HXT48 is available, and restore it with
+/// [`apply_hrc48_trim`] on boots without a crystal.
+pub fn hrc48_trim() -> u16 {
+    PMUC.hrc_cr().read().freq_trim()
+}
+
+/// Apply a previously measured HRC48 trim code.
+///
+/// The trim varies from chip to chip, so only a value measured on this
+/// unit restores the ~0.4 % accuracy of [`calibrate_hrc48`]. Returns
+/// [`Error::InvalidHrc48Trim`] for codes above [`HRC48_TRIM_MAX`].
+///
+/// ```rust,ignore
+/// let mut buf = [0u8; 2];
+/// flash.read(TRIM_OFFSET, &mut buf)?;
+/// let trim = u16::from_le_bytes(buf);
+/// if trim != 0xFFFF {
+///     rcc::apply_hrc48_trim(trim)?;
+/// }
+/// ```
+pub fn apply_hrc48_trim(trim: u16) -> Result<(), Error> {
+    if trim > HRC48_TRIM_MAX {
+        return Err(Error::InvalidHrc48Trim { trim });
+    }
+    PMUC.hrc_cr().modify(|w| w.set_freq_trim(trim));
+    // Let the oscillator settle, as between calibration steps.
+    cortex_m_blocking_delay_us(3);
+    debug!("rcc: HRC48 trim set to 0x{:03X}", trim);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rcc::Rtcsel;
+
+    #[test]
+    fn default_config_is_within_limits() {
+        assert_eq!(ConfigBuilder::new().validate_frequencies(), Ok(()));
+    }
+
+    #[test]
+    fn dll2_over_s_mode_limit() {
+        let cfg = ConfigBuilder::new().with_dll2(Dll::new().with_stg(DllStage::from_bits(15)));
+        assert_eq!(
+            cfg.validate_frequencies(),
+            Err(Error::FrequencyTooHigh {
+                hz: 384_000_000,
+                max_hz: 288_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn pclk1_over_s_mode_limit() {
+        let cfg = ConfigBuilder::new().with_pdiv1(PclkPrescaler::Div1);
+        assert_eq!(
+            cfg.validate_frequencies(),
+            Err(Error::FrequencyTooHigh {
+                hz: 240_000_000,
+                max_hz: 120_000_000,
+            })
+        );
+    }
+
+    #[test]
+    fn rtcsel_is_rejected() {
+        let cfg = ConfigBuilder::new().with_mux(ClockMux::new().with_rtcsel(Rtcsel::from_bits(0)));
+        assert_eq!(
+            cfg.validate_sources(),
+            Err(Error::SourceNotSupported {
+                domain: ClockDomain::Rtc,
+            })
+        );
+    }
+}
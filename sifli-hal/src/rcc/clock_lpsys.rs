@@ -7,12 +7,15 @@ use super::{get_hrc48_freq, get_hxt48_freq};
 use crate::pac::lpsys_rcc::vals as lpsys_vals;
 use crate::pac::{HPSYS_AON, LPSYS_RCC};
 use crate::time::Hertz;
-use core::sync::atomic::{AtomicU8, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
 
 /// LCPU wakeup reference counter (SF32LB52X specific).
 /// Used to track nested wakeup requests.
 static LCPU_WAKEUP_REF_COUNT: AtomicU8 = AtomicU8::new(0);
 
+/// Total number of LCPU wake requests since boot (diagnostics only).
+static LCPU_WAKE_REQUESTS: AtomicU32 = AtomicU32::new(0);
+
 // =============================================================================
 // LPSYS Frequency Read
 // =============================================================================
@@ -236,6 +239,12 @@ pub unsafe fn wake_lcpu() {
 
     // Increment reference counter
     LCPU_WAKEUP_REF_COUNT.fetch_add(1, Ordering::Relaxed);
+    LCPU_WAKE_REQUESTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Number of [`wake_lcpu`] calls since boot.
+pub fn lcpu_wake_request_count() -> u32 {
+    LCPU_WAKE_REQUESTS.load(Ordering::Relaxed)
 }
 
 /// Cancel LCPU active request (paired with wake_lcpu)
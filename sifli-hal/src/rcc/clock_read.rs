@@ -1,17 +1,21 @@
 //! Hardware clock frequency read functions (HPSYS).
 //!
 //! These functions read the current clock frequencies directly from hardware registers.
+//! The system/bus clock readers are public under `read_*` names for debugging;
+//! drivers should use the cached getters in `clock.rs` instead.
 
 use super::{
-    Clocks, Mpisel, Sysclk, Usbsel, Wdtsel, CLK_HRC48_FREQ, CLK_HXT48_FREQ, CLK_LRC10_FREQ,
-    CLK_LRC32_FREQ,
+    Clocks, Mpisel, Sysclk, Ticksel, Usbsel, Wdtsel, CLK_HRC48_FREQ, CLK_HXT48_FREQ,
+    CLK_LRC10_FREQ, CLK_LRC32_FREQ,
 };
 use crate::pac::hpsys_rcc::vals::mux::Perisel;
 use crate::pac::{HPSYS_AON, HPSYS_RCC, PMUC};
 use crate::time::Hertz;
 
-/// Get current sysclk frequency from hardware registers.
-pub(crate) fn get_clk_sys_freq() -> Option<Hertz> {
+/// Read the current sysclk frequency from hardware registers.
+///
+/// Bypasses the clock cache; see [`clocks()`](super::clocks) for the cached value.
+pub fn read_clk_sys_freq() -> Option<Hertz> {
     match HPSYS_RCC.csr().read().sel_sys() {
         Sysclk::Hrc48 => get_hrc48_freq(),
         Sysclk::Hxt48 => get_hxt48_freq(),
@@ -20,9 +24,9 @@ pub(crate) fn get_clk_sys_freq() -> Option<Hertz> {
     }
 }
 
-/// Get current HCLK frequency from hardware registers.
-pub(crate) fn get_hclk_freq() -> Option<Hertz> {
-    let clk_sys = get_clk_sys_freq()?;
+/// Read the current HCLK frequency from hardware registers.
+pub fn read_hclk_freq() -> Option<Hertz> {
+    let clk_sys = read_clk_sys_freq()?;
     let hdiv = HPSYS_RCC.cfgr().read().hdiv();
     // HDIV=0 means no division (same as HDIV=1)
     if hdiv == 0 {
@@ -32,15 +36,15 @@ pub(crate) fn get_hclk_freq() -> Option<Hertz> {
     }
 }
 
-/// Get current PCLK (APB1) frequency from hardware registers.
-pub(crate) fn get_pclk_freq() -> Option<Hertz> {
-    let hclk = get_hclk_freq()?;
+/// Read the current PCLK (APB1) frequency from hardware registers.
+pub fn read_pclk_freq() -> Option<Hertz> {
+    let hclk = read_hclk_freq()?;
     Some(hclk / (1u32 << HPSYS_RCC.cfgr().read().pdiv1().to_bits()))
 }
 
-/// Get current PCLK2 (APB2) frequency from hardware registers.
-pub(crate) fn get_pclk2_freq() -> Option<Hertz> {
-    let hclk = get_hclk_freq()?;
+/// Read the current PCLK2 (APB2) frequency from hardware registers.
+pub fn read_pclk2_freq() -> Option<Hertz> {
+    let hclk = read_hclk_freq()?;
     Some(hclk / (1u32 << HPSYS_RCC.cfgr().read().pdiv2().to_bits()))
 }
 
@@ -102,7 +106,7 @@ pub(crate) fn get_clk_peri_div2_freq() -> Option<Hertz> {
 /// Get USB clock frequency from hardware registers.
 pub(crate) fn get_clk_usb_freq() -> Option<Hertz> {
     let source_freq = match get_clk_usb_source() {
-        Usbsel::Sysclk => get_clk_sys_freq(),
+        Usbsel::Sysclk => read_clk_sys_freq(),
         Usbsel::Dll2 => get_clk_dll2_freq(),
     };
     let usb_div = get_clk_usb_div();
@@ -133,6 +137,20 @@ pub(crate) fn get_clk_rtc_freq() -> Option<Hertz> {
     Some(CLK_LRC10_FREQ)
 }
 
+/// Get the SysTick reference clock (`SEL_TICK` source / `TICKDIV`) from
+/// hardware registers.
+pub(crate) fn get_systick_ref_freq() -> Option<Hertz> {
+    let src = match HPSYS_RCC.csr().read().sel_tick() {
+        Ticksel::ClkRtc => get_clk_rtc_freq()?,
+        Ticksel::Hrc48 => CLK_HRC48_FREQ,
+        Ticksel::Hxt48 => CLK_HXT48_FREQ,
+        _ => return None, // Reserved
+    };
+    // TICKDIV=0 means no division (same as TICKDIV=1)
+    let div = HPSYS_RCC.cfgr().read().tickdiv();
+    Some(if div > 1 { src / div as u32 } else { src })
+}
+
 /// Get MPI1 clock frequency from hardware registers.
 pub(crate) fn get_clk_mpi1_freq() -> Option<Hertz> {
     match HPSYS_RCC.csr().read().sel_mpi1() {
@@ -158,10 +176,10 @@ pub(crate) fn get_clk_mpi2_freq() -> Option<Hertz> {
 /// Used by both `init()` and `reconfigure_sysclk()` to build `Clocks` from hardware state.
 pub(crate) fn read_hpsys_clocks_from_hw() -> Clocks {
     Clocks {
-        sysclk: get_clk_sys_freq().into(),
-        hclk: get_hclk_freq().into(),
-        pclk: get_pclk_freq().into(),
-        pclk2: get_pclk2_freq().into(),
+        sysclk: read_clk_sys_freq().into(),
+        hclk: read_hclk_freq().into(),
+        pclk: read_pclk_freq().into(),
+        pclk2: read_pclk2_freq().into(),
         dll1: get_clk_dll1_freq().into(),
         dll2: get_clk_dll2_freq().into(),
         clk_peri: get_clk_peri_freq().into(),
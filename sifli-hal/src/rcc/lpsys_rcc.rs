@@ -0,0 +1,143 @@
+//! LPSYS peripheral clock gating and reset.
+//!
+//! Counterpart of [`enable`](super::enable) / [`enable_and_reset`](super::enable_and_reset)
+//! for peripherals whose clock gate lives in `LPSYS_RCC` rather than
+//! `HPSYS_RCC` (MAILBOX2, LPSYS timers, USART4/5, ...). Most of these have no
+//! HAL singleton yet, so they are addressed by [`LpPeripheral`] instead of a
+//! type parameter.
+//!
+//! LPSYS registers are only reachable while LPSYS is awake. Every helper here
+//! raises an HP2LP wake request for the duration of the access and releases it
+//! afterwards, so they are safe to call whether or not the LCPU is running.
+//!
+//! ```rust,ignore
+//! use sifli_hal::rcc::lpsys_rcc::{self, LpPeripheral};
+//!
+//! lpsys_rcc::enable(LpPeripheral::Btim3);
+//! ```
+
+use super::{cancel_lcpu_active_request, wake_lcpu};
+use crate::pac::LPSYS_RCC;
+
+/// Peripheral with a clock gate in `LPSYS_RCC.ENR1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LpPeripheral {
+    Dmac2,
+    Mailbox2,
+    Pinmux2,
+    Patch,
+    Usart4,
+    Usart5,
+    /// Security block. Has an enable bit but no reset bit.
+    Secu2,
+    Ptc2,
+    Btim3,
+    Btim4,
+    Syscfg2,
+    Gpio2,
+    Rfc,
+    Phy,
+    Mac,
+    Crc2,
+}
+
+impl LpPeripheral {
+    /// Bit position in `ENR1`/`ESR1`/`ECR1`, and in `RSTR1` where present.
+    const fn bit(self) -> u32 {
+        match self {
+            Self::Dmac2 => 1,
+            Self::Mailbox2 => 2,
+            Self::Pinmux2 => 3,
+            Self::Patch => 4,
+            Self::Usart4 => 5,
+            Self::Usart5 => 6,
+            Self::Secu2 => 7,
+            Self::Ptc2 => 8,
+            Self::Btim3 => 9,
+            Self::Btim4 => 10,
+            Self::Syscfg2 => 15,
+            Self::Gpio2 => 16,
+            Self::Rfc => 18,
+            Self::Phy => 19,
+            Self::Mac => 20,
+            Self::Crc2 => 21,
+        }
+    }
+
+    const fn mask(self) -> u32 {
+        1 << self.bit()
+    }
+
+    const fn has_reset(self) -> bool {
+        !matches!(self, Self::Secu2)
+    }
+}
+
+/// Run `f` with LPSYS held awake.
+fn with_lpsys_awake<R>(f: impl FnOnce() -> R) -> R {
+    // SAFETY: paired with `cancel_lcpu_active_request` below.
+    unsafe { wake_lcpu() };
+    let r = f();
+    unsafe { cancel_lcpu_active_request() };
+    r
+}
+
+/// Enables the clock of LPSYS peripheral `p`.
+///
+/// Uses the `ESR1` set register, so concurrent updates from the LCPU to
+/// other bits are not lost.
+pub fn enable(p: LpPeripheral) {
+    with_lpsys_awake(|| LPSYS_RCC.esr1().write(|w| w.0 = p.mask()));
+}
+
+/// Disables the clock of LPSYS peripheral `p`.
+///
+/// # Safety
+///
+/// Peripheral must not be in use, by either core.
+// TODO: should this be `unsafe`?
+pub fn disable(p: LpPeripheral) {
+    with_lpsys_awake(|| LPSYS_RCC.ecr1().write(|w| w.0 = p.mask()));
+}
+
+/// Pulses the reset line of LPSYS peripheral `p`. No-op for peripherals
+/// without a reset bit.
+///
+/// # Safety
+///
+/// Peripheral must not be in use, by either core.
+// TODO: should this be `unsafe`?
+pub fn reset(p: LpPeripheral) {
+    if !p.has_reset() {
+        return;
+    }
+    with_lpsys_awake(|| critical_section::with(|_| reset_awake(p)));
+}
+
+/// Enables and resets LPSYS peripheral `p`.
+///
+/// # Safety
+///
+/// Peripheral must not be in use, by either core.
+// TODO: should this be `unsafe`?
+pub fn enable_and_reset(p: LpPeripheral) {
+    with_lpsys_awake(|| {
+        LPSYS_RCC.esr1().write(|w| w.0 = p.mask());
+        if p.has_reset() {
+            critical_section::with(|_| reset_awake(p));
+        }
+    });
+}
+
+/// Whether the clock of LPSYS peripheral `p` is enabled.
+pub fn is_enabled(p: LpPeripheral) -> bool {
+    with_lpsys_awake(|| LPSYS_RCC.enr1().read().0 & p.mask() != 0)
+}
+
+fn reset_awake(p: LpPeripheral) {
+    let mask = p.mask();
+    LPSYS_RCC.rstr1().modify(|w| w.0 |= mask);
+    while LPSYS_RCC.rstr1().read().0 & mask == 0 {}
+    LPSYS_RCC.rstr1().modify(|w| w.0 &= !mask);
+}
@@ -5,6 +5,7 @@ pub use clock::*;
 
 mod clock_read;
 pub(crate) use clock_read::*;
+pub use clock_read::{read_clk_sys_freq, read_hclk_freq, read_pclk2_freq, read_pclk_freq};
 
 mod clock_config;
 pub use clock_config::*;
@@ -12,6 +13,8 @@ pub use clock_config::*;
 mod clock_lpsys;
 pub use clock_lpsys::*;
 
+pub mod lpsys_rcc;
+
 mod token;
 pub use token::*;
 
@@ -28,6 +31,11 @@ pub(crate) trait SealedRccEnableReset {
     fn rcc_disable() {}
 
     fn rcc_reset() {}
+
+    /// Whether the peripheral's clock gate is open.
+    fn rcc_is_enabled() -> bool {
+        true
+    }
 }
 #[allow(private_bounds)]
 pub trait RccEnableReset: SealedRccEnableReset + 'static {}
@@ -36,6 +44,12 @@ pub(crate) trait SealedRccGetFreq {
     /// Get peripheral frequency
     /// Returns `None` if clock is disabled
     fn get_freq() -> Option<Hertz>;
+
+    /// Whether the peripheral clock is gated on. Always `true` for
+    /// peripherals without an enable bit.
+    fn clock_enabled() -> bool {
+        true
+    }
 }
 
 #[allow(private_bounds)]
@@ -49,6 +63,23 @@ pub trait RccGetFreq: SealedRccGetFreq + 'static {
     }
 }
 
+/// Clock actually seen by peripheral `P`.
+///
+/// Resolves the peripheral's clock domain through its source selects and
+/// dividers: `clk_peri` for USART/SPI/I2C, `pclk` for EFUSEC/GPADC/timers,
+/// `hclk` for LCDC/DMAC, and so on. Returns `None` if the domain frequency is
+/// unknown or the peripheral's clock gate is closed.
+///
+/// ```rust,ignore
+/// let src = rcc::peripheral_clock::<peripherals::USART1>().unwrap();
+/// ```
+pub fn peripheral_clock<P: RccGetFreq>() -> Option<Hertz> {
+    if !P::clock_enabled() {
+        return None;
+    }
+    P::get_freq()
+}
+
 /// Enables peripheral `T`.
 ///
 /// # Safety
@@ -90,6 +121,40 @@ pub fn enable_and_reset<T: RccEnableReset>() {
     critical_section::with(|cs| enable_and_reset_with_cs::<T>(cs));
 }
 
+/// Pulses the reset line of peripheral `T`, leaving its clock enable as is.
+///
+/// # Safety
+///
+/// Peripheral must not be in use.
+// TODO: should this be `unsafe`?
+pub fn reset_with_cs<T: RccEnableReset>(_cs: CriticalSection) {
+    T::rcc_reset();
+}
+
+/// Pulses the reset line of peripheral `T`, leaving its clock enable as is.
+///
+/// All registers of `T` return to their reset values, so a driver that was
+/// using it has to be set up again afterwards (e.g. [`Lcdc::init`]). Use this
+/// to recover a peripheral stuck mid-transfer without rebuilding the driver
+/// from the singleton. No-op for peripherals without a reset bit.
+///
+/// ```rust,ignore
+/// if let Err(lcdc::Error::Timeout) = lcdc.send_cmd(0x29, 1, false) {
+///     rcc::reset::<peripherals::LCDC1>();
+///     lcdc.init();
+/// }
+/// ```
+///
+/// [`Lcdc::init`]: crate::lcdc::Lcdc::init
+///
+/// # Safety
+///
+/// Peripheral must not be in use.
+// TODO: should this be `unsafe`?
+pub fn reset<T: RccEnableReset>() {
+    critical_section::with(|cs| reset_with_cs::<T>(cs));
+}
+
 /// Disables peripheral `T`.
 ///
 /// # Safety
@@ -114,3 +179,44 @@ pub fn test_print_clocks() {
     let clocks = clocks();
     info!("Clock frequencies: {:#?}", clocks);
 }
+
+/// Log the raw HPSYS clock tree registers via defmt.
+///
+/// Complements [`test_print_clocks`]: when a frequency resolves to `None`
+/// or an unexpected value, the selector, divider and DLL bits shown here
+/// say why. Paste the output into bug reports about clock configuration.
+///
+/// Only registers that are always accessible are read. LPSYS_RCC is left
+/// out because LPSYS may be asleep, and reading it then would fault.
+#[cfg(feature = "defmt")]
+pub fn dump_clock_registers() {
+    use crate::pac::{HPSYS_AON, HPSYS_RCC, PMUC};
+
+    info!(
+        "rcc: HPSYS_RCC CSR={:08x} CFGR={:08x} USBCR={:08x}",
+        HPSYS_RCC.csr().read().0,
+        HPSYS_RCC.cfgr().read().0,
+        HPSYS_RCC.usbcr().read().0,
+    );
+    info!(
+        "rcc: HPSYS_RCC DLL1CR={:08x} DLL2CR={:08x} HRCCAL1={:08x} HRCCAL2={:08x}",
+        HPSYS_RCC.dllcr(0).read().0,
+        HPSYS_RCC.dllcr(1).read().0,
+        HPSYS_RCC.hrccal1().read().0,
+        HPSYS_RCC.hrccal2().read().0,
+    );
+    info!(
+        "rcc: HPSYS_RCC ENR1={:08x} ENR2={:08x}",
+        HPSYS_RCC.enr1().read().0,
+        HPSYS_RCC.enr2().read().0,
+    );
+    info!("rcc: HPSYS_AON ACR={:08x}", HPSYS_AON.acr().read().0);
+    info!(
+        "rcc: PMUC CR={:08x} HRC_CR={:08x} HXT_CR1={:08x} LRC32_CR={:08x}",
+        PMUC.cr().read().0,
+        PMUC.hrc_cr().read().0,
+        PMUC.hxt_cr1().read().0,
+        PMUC.lrc32_cr().read().0,
+    );
+    info!("rcc: clock generation {}", clock_generation());
+}
@@ -0,0 +1,121 @@
+//! Reset cause reporting.
+//!
+//! The PMU latches why HPSYS last came out of reset in `PMUC.WSR`. The flags
+//! survive the reset itself (and hibernate), so read them early in `main`,
+//! log or store them, then [`clear_flags`] so the next boot starts clean:
+//!
+//! ```rust,ignore
+//! let p = sifli_hal::init(Default::default());
+//! let cause = sifli_hal::reset::cause();
+//! info!("reset cause: {:?}", cause);
+//! sifli_hal::reset::clear_flags();
+//! ```
+//!
+//! Flags accumulate until cleared. When several are set, [`cause`] reports
+//! the most specific one in this order: watchdog, power key, software, pin
+//! wakeup, RTC/LPTIM wakeup. No flag at all means a cold power-on (or a
+//! previous boot that cleared them and then lost power).
+
+use crate::pac::PMUC;
+
+/// `PMUC.WSR` / `PMUC.WCR` bit positions (SDK `PMUC_WSR_*`).
+mod wsr {
+    pub const RTC: u32 = 1 << 0;
+    pub const LPTIM: u32 = 1 << 1;
+    /// Wakeup pins PIN0..PIN4.
+    pub const PIN_MASK: u32 = 0b1_1111 << 2;
+    pub const LOWBAT: u32 = 1 << 9;
+    pub const IWDT: u32 = 1 << 10;
+    pub const PWRKEY: u32 = 1 << 11;
+    /// Set by the SDK-compatible software reboot path (`PMUC.CR.REBOOT`).
+    pub const REBOOT: u32 = 1 << 12;
+
+    pub const ALL: u32 = RTC | LPTIM | PIN_MASK | LOWBAT | IWDT | PWRKEY | REBOOT;
+}
+
+/// Why the chip last reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResetCause {
+    /// Cold power-on: no reset flag is set.
+    PowerOn,
+    /// The independent watchdog expired.
+    Watchdog,
+    /// Long press on the power key.
+    PowerKey,
+    /// Software-requested reboot.
+    Software,
+    /// Wakeup from hibernate through a wakeup pin (PIN0..PIN4).
+    Pin {
+        /// Bitmask of the wakeup pins that fired, bit 0 = PIN0.
+        pins: u8,
+    },
+    /// Wakeup from hibernate through the RTC alarm.
+    Rtc,
+    /// Wakeup from hibernate through the low-power timer.
+    Lptim,
+    /// Battery dropped below the low-voltage threshold.
+    LowBattery,
+    /// Flags set that this driver does not know how to interpret.
+    Unknown(u32),
+}
+
+impl ResetCause {
+    fn from_bits(bits: u32) -> Self {
+        if bits & !wsr::ALL != 0 {
+            Self::Unknown(bits)
+        } else if bits & wsr::IWDT != 0 {
+            Self::Watchdog
+        } else if bits & wsr::PWRKEY != 0 {
+            Self::PowerKey
+        } else if bits & wsr::REBOOT != 0 {
+            Self::Software
+        } else if bits & wsr::PIN_MASK != 0 {
+            Self::Pin {
+                pins: ((bits & wsr::PIN_MASK) >> 2) as u8,
+            }
+        } else if bits & wsr::RTC != 0 {
+            Self::Rtc
+        } else if bits & wsr::LPTIM != 0 {
+            Self::Lptim
+        } else if bits & wsr::LOWBAT != 0 {
+            Self::LowBattery
+        } else {
+            Self::PowerOn
+        }
+    }
+}
+
+/// Raw `PMUC.WSR` reset/wakeup flags.
+pub fn raw_flags() -> u32 {
+    PMUC.wsr().read().0
+}
+
+/// Decode the latched reset flags.
+pub fn cause() -> ResetCause {
+    ResetCause::from_bits(raw_flags())
+}
+
+/// Clear all latched reset flags so the next boot reports only its own cause.
+pub fn clear_flags() {
+    PMUC.wcr().write(|w| w.0 = wsr::ALL);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_priority() {
+        assert_eq!(ResetCause::from_bits(0), ResetCause::PowerOn);
+        assert_eq!(
+            ResetCause::from_bits(wsr::IWDT | wsr::RTC),
+            ResetCause::Watchdog
+        );
+        assert_eq!(
+            ResetCause::from_bits(wsr::PIN_MASK & (1 << 3)),
+            ResetCause::Pin { pins: 0b10 }
+        );
+        assert_eq!(ResetCause::from_bits(1 << 31), ResetCause::Unknown(1 << 31));
+    }
+}
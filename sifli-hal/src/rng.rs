@@ -11,11 +11,15 @@ use embassy_sync::waitqueue::AtomicWaker;
 use crate::interrupt::typelevel::Binding;
 use crate::interrupt::InterruptExt;
 use crate::mode::{Async, Blocking, Mode};
+use crate::ownership::Claim;
 use crate::pac::TRNG;
 use crate::{interrupt, peripherals, rcc};
 
 static WAKER: AtomicWaker = AtomicWaker::new();
 
+/// Number of seed/generation attempts before a blocking call gives up.
+const MAX_ATTEMPTS: u8 = 3;
+
 /// TRNG error.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -28,6 +32,7 @@ pub enum Error {
 
 /// TRNG driver.
 pub struct Rng<'d, M: Mode> {
+    _claim: Claim,
     _phantom: PhantomData<(&'d peripherals::TRNG, M)>,
 }
 
@@ -42,6 +47,7 @@ impl<'d, M: Mode> Rng<'d, M> {
         });
 
         Self {
+            _claim: Claim::new::<peripherals::TRNG>(),
             _phantom: PhantomData,
         }
     }
@@ -88,10 +94,26 @@ impl<'d, M: Mode> Rng<'d, M> {
         buf[7] = TRNG.rand_num7().read().val();
     }
 
+    /// Run seed + generation, retrying transient failures up to [`MAX_ATTEMPTS`] times.
+    ///
+    /// Returns the error of the last attempt if all of them fail.
+    fn blocking_seed_and_rand(&self) -> Result<(), Error> {
+        let mut attempt = 1;
+        loop {
+            match self.blocking_seed().and_then(|_| self.blocking_rand()) {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < MAX_ATTEMPTS => {
+                    warn!("TRNG: {:?}, retrying ({}/{})", e, attempt, MAX_ATTEMPTS);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// Generate a single random u32 (blocking).
     pub fn blocking_generate(&mut self) -> Result<u32, Error> {
-        self.blocking_seed()?;
-        self.blocking_rand()?;
+        self.blocking_seed_and_rand()?;
         Ok(TRNG.rand_num0().read().val())
     }
 
@@ -103,8 +125,7 @@ impl<'d, M: Mode> Rng<'d, M> {
         let mut offset = 0;
 
         while offset < buf.len() {
-            self.blocking_seed()?;
-            self.blocking_rand()?;
+            self.blocking_seed_and_rand()?;
             self.read_rand_nums(&mut rand_buf);
 
             for word in &rand_buf {
@@ -0,0 +1,170 @@
+//! Power-on self-test for manufacturing bring-up.
+//!
+//! [`run`] checks the oscillators, DLL1, EFUSE, chip revision and the cached
+//! clock tree in one call and reports each result, so a test fixture can
+//! reject a bad board before flashing the application. Every check is also
+//! available on its own.
+//!
+//! ```rust,ignore
+//! let p = sifli_hal::init(Default::default());
+//! let report = sifli_hal::selftest::run(&mut p.EFUSEC);
+//! info!("{}", report);
+//! assert!(report.passed());
+//! ```
+
+use embassy_hal_internal::Peripheral;
+
+use crate::efuse::Efuse;
+use crate::pac::{HPSYS_AON, HPSYS_RCC};
+use crate::{peripherals, rcc, syscfg};
+
+/// Outcome of a single self-test check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Check {
+    Pass,
+    Fail,
+    /// The check does not apply to the current configuration, e.g. DLL1
+    /// when it is not enabled, or an oscillator the board does not run.
+    Skipped,
+}
+
+impl Check {
+    fn from_bool(ok: bool) -> Self {
+        if ok {
+            Check::Pass
+        } else {
+            Check::Fail
+        }
+    }
+
+    /// Whether the check did not fail. [`Check::Skipped`] counts as passed.
+    pub fn is_ok(self) -> bool {
+        self != Check::Fail
+    }
+}
+
+/// Results of [`run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfTestReport {
+    /// See [`check_hxt48`].
+    pub hxt48_ready: Check,
+    /// See [`check_hrc48`].
+    pub hrc48_ready: Check,
+    /// See [`check_dll1`].
+    pub dll1_locked: Check,
+    /// See [`check_efuse`].
+    pub efuse: Check,
+    /// See [`check_chip_revision`].
+    pub chip_revision: Check,
+    /// See [`check_clocks`].
+    pub clocks: Check,
+}
+
+impl SelfTestReport {
+    /// Whether no check failed.
+    pub fn passed(&self) -> bool {
+        [
+            self.hxt48_ready,
+            self.hrc48_ready,
+            self.dll1_locked,
+            self.efuse,
+            self.chip_revision,
+            self.clocks,
+        ]
+        .into_iter()
+        .all(Check::is_ok)
+    }
+}
+
+/// Run every check.
+///
+/// `efusec` is only borrowed for the EFUSE read; pass `&mut p.EFUSEC` to keep
+/// using it afterwards.
+pub fn run<'d>(efusec: impl Peripheral<P = peripherals::EFUSEC> + 'd) -> SelfTestReport {
+    let report = SelfTestReport {
+        hxt48_ready: check_hxt48(),
+        hrc48_ready: check_hrc48(),
+        dll1_locked: check_dll1(),
+        efuse: check_efuse(efusec),
+        chip_revision: check_chip_revision(),
+        clocks: check_clocks(),
+    };
+    if !report.passed() {
+        warn!("selftest: {:?}", report);
+    }
+    report
+}
+
+/// The 48 MHz crystal oscillator reports ready. Skipped if it is neither
+/// requested nor selected for clk_sys or clk_peri, e.g. on boards without
+/// the crystal.
+pub fn check_hxt48() -> Check {
+    let acr = HPSYS_AON.acr().read();
+    let csr = HPSYS_RCC.csr().read();
+    let used = acr.hxt48_req()
+        || csr.sel_sys() == rcc::Sysclk::Hxt48
+        || csr.sel_peri() == rcc::Perisel::Hxt48;
+    if !used {
+        return Check::Skipped;
+    }
+    Check::from_bool(acr.hxt48_rdy())
+}
+
+/// The 48 MHz RC oscillator reports ready. Skipped if it is neither
+/// requested nor selected for clk_sys or clk_peri.
+pub fn check_hrc48() -> Check {
+    let acr = HPSYS_AON.acr().read();
+    let csr = HPSYS_RCC.csr().read();
+    let used = acr.hrc48_req()
+        || csr.sel_sys() == rcc::Sysclk::Hrc48
+        || csr.sel_peri() == rcc::Perisel::Hrc48;
+    if !used {
+        return Check::Skipped;
+    }
+    Check::from_bool(acr.hrc48_rdy())
+}
+
+/// DLL1 is locked. Skipped if DLL1 is not enabled.
+pub fn check_dll1() -> Check {
+    let dllcr = HPSYS_RCC.dllcr(0).read();
+    if !dllcr.en() {
+        return Check::Skipped;
+    }
+    Check::from_bool(dllcr.ready())
+}
+
+/// EFUSE banks can be read and bank1 holds factory calibration.
+pub fn check_efuse<'d>(efusec: impl Peripheral<P = peripherals::EFUSEC> + 'd) -> Check {
+    match Efuse::new(efusec) {
+        Ok(efuse) => Check::from_bool(efuse.programmed_calibration().is_some()),
+        Err(e) => {
+            warn!("selftest: efuse read failed: {:?}", e);
+            Check::Fail
+        }
+    }
+}
+
+/// The chip revision is one the SDK recognises.
+pub fn check_chip_revision() -> Check {
+    Check::from_bool(syscfg::read_idr().revision().is_valid())
+}
+
+/// The clock frequencies cached at `init()` match what the RCC registers
+/// currently produce. Skipped before `init()`.
+///
+/// A mismatch means the clock tree was changed behind the HAL's back, and
+/// every driver deriving dividers from [`rcc::clocks`] is misconfigured.
+pub fn check_clocks() -> Check {
+    let Some(cached) = rcc::cached_freqs() else {
+        return Check::Skipped;
+    };
+    let pairs = [
+        (cached.sysclk.to_hertz(), rcc::read_clk_sys_freq()),
+        (cached.hclk.to_hertz(), rcc::read_hclk_freq()),
+        (cached.pclk.to_hertz(), rcc::read_pclk_freq()),
+        (cached.pclk2.to_hertz(), rcc::read_pclk2_freq()),
+    ];
+    Check::from_bool(pairs.iter().all(|(c, hw)| c.is_some() && c == hw))
+}
@@ -0,0 +1,865 @@
+//! Key-value store for settings in reserved flash sectors.
+//!
+//! [`Nvs`] keeps small values (BD address, display calibration, user
+//! settings) under 16-bit keys in a run of flash sectors set aside for it. It
+//! works on any [`NorFlash`], in practice [`mpi::Flash`](crate::mpi::Flash):
+//!
+//! ```rust,ignore
+//! use sifli_hal::mpi::{Flash, SECTOR_SIZE};
+//! use sifli_hal::storage::{Key, Nvs};
+//!
+//! const BRIGHTNESS: Key<u8> = Key::new(1);
+//!
+//! // Last two sectors of a 4 MiB flash on MPI2.
+//! let flash = Flash::new_blocking(p.MPI2, 4 * 1024 * 1024);
+//! let mut nvs = Nvs::new(flash, (4 * 1024 * 1024 - 2 * SECTOR_SIZE) as u32, 2)?;
+//! nvs.seed_from_efuse(&efuse)?;
+//!
+//! let level = nvs.get(&BRIGHTNESS)?.unwrap_or(128);
+//! nvs.set(&BRIGHTNESS, &level.saturating_add(16))?;
+//! ```
+//!
+//! # Layout
+//!
+//! The region is `sectors` consecutive erase sectors (at least two). Exactly
+//! one, the one with the highest sequence number, holds the live log:
+//!
+//! ```text
+//! sector:  magic "NVS1" (u32) | seq (u32) | record | record | ... | 0xFF...
+//! record:  key (u16) | len (u16) | crc32 (u32) | data, padded to 4 bytes
+//! ```
+//!
+//! All fields are little-endian. A write appends a record; the last record of
+//! a key with a valid CRC is its value, and a record with `len = 0xFFFE` and
+//! no data deletes the key. Key `0xFFFF` (erased flash) ends the log. The CRC
+//! is CRC-32 (IEEE) over key, len and data, so a record torn by a reset is
+//! skipped and the key keeps its previous value.
+//!
+//! When the active sector is full, the latest value of every key is copied
+//! into the next sector in turn, and its header is written last with
+//! `seq + 1`. A reset during the copy leaves a sector without a header, which
+//! is ignored, so the old sector stays active. Rotating through all sectors
+//! spreads the erases evenly.
+//!
+//! Keys from [`keys::RESERVED_START`] upwards are used by the HAL, see
+//! [`keys`].
+
+use core::marker::PhantomData;
+
+use embedded_storage::nor_flash::NorFlash;
+
+use crate::efuse::Efuse;
+use crate::lcpu::LcpuConfig;
+use crate::mode::Mode;
+
+/// "NVS1" in little-endian.
+const MAGIC: u32 = 0x3153_564E;
+const SECTOR_HEADER_LEN: u32 = 8;
+const RECORD_HEADER_LEN: u32 = 8;
+/// Key of an unwritten record slot.
+const ERASED_KEY: u16 = 0xFFFF;
+/// `len` of a record that deletes its key.
+const TOMBSTONE: u16 = 0xFFFE;
+/// Chunk size for reading and copying record data.
+const CHUNK: usize = 32;
+
+/// Well-known keys.
+pub mod keys {
+    use super::Key;
+
+    /// First key reserved for the HAL. Application keys should stay below.
+    pub const RESERVED_START: u16 = 0xFF00;
+
+    /// Chip UID, seeded by [`Nvs::seed_from_efuse`](super::Nvs::seed_from_efuse).
+    pub const EFUSE_UID: Key<[u8; 16]> = Key::new(0xFF00);
+    /// Raw EFUSE bank1 words (little-endian), seeded by
+    /// [`Nvs::seed_from_efuse`](super::Nvs::seed_from_efuse) on parts with
+    /// factory calibration.
+    pub const EFUSE_BANK1: Key<[u8; 32]> = Key::new(0xFF01);
+    /// Public BD address, in the byte order of
+    /// [`LcpuConfig::bd_addr`](crate::lcpu::LcpuConfig::bd_addr). Applied by
+    /// [`Nvs::load_bd_addr`](super::Nvs::load_bd_addr).
+    pub const BD_ADDR: Key<[u8; 6]> = Key::new(0xFF02);
+}
+
+/// Store error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// The underlying flash failed.
+    Flash(E),
+    /// The region is not sector aligned, has fewer than two sectors or
+    /// extends past the flash.
+    InvalidRegion,
+    /// Key `0xFFFF` is reserved for erased flash.
+    InvalidKey,
+    /// The value does not fit in one sector.
+    ValueTooLarge,
+    /// The live values of all keys do not fit in one sector.
+    Full,
+    /// The stored value is `len` bytes, which does not fit the buffer (or,
+    /// for [`Nvs::get`], is not the size of the type).
+    LengthMismatch { len: usize },
+}
+
+/// A fixed-size value stored under a [`Key`].
+pub trait Value: Sized {
+    /// Encoded form.
+    type Bytes: AsRef<[u8]> + AsMut<[u8]>;
+    /// Zeroed buffer to read the encoded form into.
+    const ZERO: Self::Bytes;
+
+    /// Encode `self`.
+    fn to_bytes(&self) -> Self::Bytes;
+    /// Decode stored bytes, or `None` if they are not a valid `Self`.
+    fn from_bytes(bytes: &Self::Bytes) -> Option<Self>;
+}
+
+macro_rules! impl_value_int {
+    ($($t:ty),*) => {$(
+        impl Value for $t {
+            type Bytes = [u8; core::mem::size_of::<$t>()];
+            const ZERO: Self::Bytes = [0; core::mem::size_of::<$t>()];
+
+            fn to_bytes(&self) -> Self::Bytes {
+                self.to_le_bytes()
+            }
+
+            fn from_bytes(bytes: &Self::Bytes) -> Option<Self> {
+                Some(<$t>::from_le_bytes(*bytes))
+            }
+        }
+    )*};
+}
+
+impl_value_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Value for bool {
+    type Bytes = [u8; 1];
+    const ZERO: Self::Bytes = [0];
+
+    fn to_bytes(&self) -> Self::Bytes {
+        [*self as u8]
+    }
+
+    fn from_bytes(bytes: &Self::Bytes) -> Option<Self> {
+        match bytes[0] {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl<const N: usize> Value for [u8; N] {
+    type Bytes = [u8; N];
+    const ZERO: Self::Bytes = [0; N];
+
+    fn to_bytes(&self) -> Self::Bytes {
+        *self
+    }
+
+    fn from_bytes(bytes: &Self::Bytes) -> Option<Self> {
+        Some(*bytes)
+    }
+}
+
+/// A key with the type of its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key<V> {
+    id: u16,
+    _value: PhantomData<fn() -> V>,
+}
+
+impl<V> Key<V> {
+    /// Key `id`. `0xFFFF` is rejected by every access.
+    pub const fn new(id: u16) -> Self {
+        Self {
+            id,
+            _value: PhantomData,
+        }
+    }
+
+    /// The raw key.
+    pub const fn id(&self) -> u16 {
+        self.id
+    }
+}
+
+/// A record found in a sector.
+#[derive(Clone, Copy)]
+struct Record {
+    /// Offset of the record header within the sector.
+    pos: u32,
+    key: u16,
+    len: u16,
+    crc: u32,
+}
+
+impl Record {
+    fn data_len(&self) -> u32 {
+        if self.len == TOMBSTONE {
+            0
+        } else {
+            self.len as u32
+        }
+    }
+
+    /// Total size, header and padding included.
+    fn size(&self) -> u32 {
+        RECORD_HEADER_LEN + align4(self.data_len())
+    }
+
+    fn next(&self) -> u32 {
+        self.pos + self.size()
+    }
+}
+
+enum Step {
+    Record(Record),
+    /// Erased flash: the log ends here.
+    End,
+    /// A header that can't be a record; the rest of the sector is unusable.
+    Corrupt,
+}
+
+/// Key-value store in a flash region, see the [module docs](self).
+pub struct Nvs<F: NorFlash> {
+    flash: F,
+    base: u32,
+    sectors: u32,
+    /// Sector holding the live log.
+    active: u32,
+    /// Sequence number of `active`.
+    seq: u32,
+    /// Offset of the next free byte in `active`.
+    write_pos: u32,
+}
+
+impl<F: NorFlash> Nvs<F> {
+    /// Mount the store in the `sectors` erase sectors starting at `base`.
+    ///
+    /// A region without a valid sector (first use) is formatted.
+    pub fn new(flash: F, base: u32, sectors: u32) -> Result<Self, Error<F::Error>> {
+        let sector_size = F::ERASE_SIZE as u32;
+        let end = (sectors as u64) * (sector_size as u64) + base as u64;
+        if sectors < 2 || !base.is_multiple_of(sector_size) || end > flash.capacity() as u64 {
+            return Err(Error::InvalidRegion);
+        }
+
+        let mut nvs = Self {
+            flash,
+            base,
+            sectors,
+            active: 0,
+            seq: 0,
+            write_pos: SECTOR_HEADER_LEN,
+        };
+
+        let mut found: Option<(u32, u32)> = None;
+        for sector in 0..sectors {
+            if let Some(seq) = nvs.sector_seq(sector)? {
+                // Sequence numbers wrap; compare by distance.
+                if found.is_none_or(|(_, best)| (seq.wrapping_sub(best) as i32) > 0) {
+                    found = Some((sector, seq));
+                }
+            }
+        }
+
+        match found {
+            Some((sector, seq)) => {
+                nvs.active = sector;
+                nvs.seq = seq;
+                nvs.write_pos = nvs.scan_end(sector)?;
+            }
+            None => {
+                debug!("nvs: formatting {} sectors at 0x{:08x}", sectors, base);
+                nvs.erase_sector(0)?;
+                nvs.write_sector_header(0, 0)?;
+            }
+        }
+        Ok(nvs)
+    }
+
+    /// Release the flash.
+    pub fn release(self) -> F {
+        self.flash
+    }
+
+    /// Read the value of `key` into `buf` and return its length, or `None`
+    /// if the key is not set.
+    pub fn read(&mut self, key: u16, buf: &mut [u8]) -> Result<Option<usize>, Error<F::Error>> {
+        check_key(key)?;
+        let Some(rec) = self.find(key)? else {
+            return Ok(None);
+        };
+        if rec.len == TOMBSTONE {
+            return Ok(None);
+        }
+        let len = rec.len as usize;
+        if buf.len() < len {
+            return Err(Error::LengthMismatch { len });
+        }
+        let addr = self.sector_addr(self.active) + rec.pos + RECORD_HEADER_LEN;
+        self.flash
+            .read(addr, &mut buf[..len])
+            .map_err(Error::Flash)?;
+        Ok(Some(len))
+    }
+
+    /// Set `key` to `data`.
+    ///
+    /// Nothing is written if the stored value is already `data`.
+    pub fn write(&mut self, key: u16, data: &[u8]) -> Result<(), Error<F::Error>> {
+        check_key(key)?;
+        let sector_size = F::ERASE_SIZE as u32;
+        if data.len() >= TOMBSTONE as usize
+            || RECORD_HEADER_LEN + align4(data.len() as u32) > sector_size - SECTOR_HEADER_LEN
+        {
+            return Err(Error::ValueTooLarge);
+        }
+        if let Some(rec) = self.find(key)? {
+            if rec.len as usize == data.len() && self.data_equals(self.active, &rec, data)? {
+                return Ok(());
+            }
+        }
+        self.append(key, data.len() as u16, data)
+    }
+
+    /// Delete `key`. No-op if it is not set.
+    pub fn delete(&mut self, key: u16) -> Result<(), Error<F::Error>> {
+        check_key(key)?;
+        match self.find(key)? {
+            Some(rec) if rec.len != TOMBSTONE => self.append(key, TOMBSTONE, &[]),
+            _ => Ok(()),
+        }
+    }
+
+    /// Read a typed value. `None` if the key is not set or the stored bytes
+    /// do not decode.
+    pub fn get<V: Value>(&mut self, key: &Key<V>) -> Result<Option<V>, Error<F::Error>> {
+        let mut bytes = V::ZERO;
+        let expected = bytes.as_ref().len();
+        match self.read(key.id, bytes.as_mut())? {
+            Some(len) if len == expected => Ok(V::from_bytes(&bytes)),
+            Some(len) => Err(Error::LengthMismatch { len }),
+            None => Ok(None),
+        }
+    }
+
+    /// Write a typed value.
+    pub fn set<V: Value>(&mut self, key: &Key<V>, value: &V) -> Result<(), Error<F::Error>> {
+        self.write(key.id, value.to_bytes().as_ref())
+    }
+
+    /// Store the factory values from `efuse` under [`keys::EFUSE_UID`] and,
+    /// if bank1 is programmed, [`keys::EFUSE_BANK1`], unless already set.
+    ///
+    /// Call after mounting so a blank store starts from the factory values;
+    /// later calls write nothing.
+    pub fn seed_from_efuse<M: Mode>(
+        &mut self,
+        efuse: &Efuse<'_, M>,
+    ) -> Result<(), Error<F::Error>> {
+        if self.get(&keys::EFUSE_UID)?.is_none() {
+            self.set(&keys::EFUSE_UID, efuse.uid().bytes())?;
+        }
+        if efuse.programmed_calibration().is_some() && self.get(&keys::EFUSE_BANK1)?.is_none() {
+            let mut bytes = [0u8; 32];
+            for (chunk, word) in bytes.chunks_exact_mut(4).zip(efuse.bank1_words()) {
+                chunk.copy_from_slice(&word.to_le_bytes());
+            }
+            self.set(&keys::EFUSE_BANK1, &bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Use the public BD address stored under [`keys::BD_ADDR`] in `config`,
+    /// which is returned unchanged if none is stored.
+    ///
+    /// ```rust,ignore
+    /// let config = nvs.load_bd_addr(LcpuConfig::default())?;
+    /// let ble = BleController::new(p.LCPU, p.MAILBOX1_CH1, p.DMAC2_CH8, Irqs, &config).await?;
+    /// ```
+    pub fn load_bd_addr<'a>(
+        &mut self,
+        config: LcpuConfig<'a>,
+    ) -> Result<LcpuConfig<'a>, Error<F::Error>> {
+        Ok(match self.get(&keys::BD_ADDR)? {
+            Some(addr) => config.bd_addr(addr),
+            None => config,
+        })
+    }
+
+    fn sector_addr(&self, sector: u32) -> u32 {
+        self.base + sector * F::ERASE_SIZE as u32
+    }
+
+    fn erase_sector(&mut self, sector: u32) -> Result<(), Error<F::Error>> {
+        let addr = self.sector_addr(sector);
+        self.flash
+            .erase(addr, addr + F::ERASE_SIZE as u32)
+            .map_err(Error::Flash)
+    }
+
+    fn write_sector_header(&mut self, sector: u32, seq: u32) -> Result<(), Error<F::Error>> {
+        let mut header = [0u8; SECTOR_HEADER_LEN as usize];
+        header[..4].copy_from_slice(&MAGIC.to_le_bytes());
+        header[4..].copy_from_slice(&seq.to_le_bytes());
+        let addr = self.sector_addr(sector);
+        self.flash.write(addr, &header).map_err(Error::Flash)?;
+        self.active = sector;
+        self.seq = seq;
+        Ok(())
+    }
+
+    /// Sequence number of `sector`, or `None` if it has no valid header.
+    fn sector_seq(&mut self, sector: u32) -> Result<Option<u32>, Error<F::Error>> {
+        let mut header = [0u8; SECTOR_HEADER_LEN as usize];
+        let addr = self.sector_addr(sector);
+        self.flash.read(addr, &mut header).map_err(Error::Flash)?;
+        let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let seq = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        Ok((magic == MAGIC).then_some(seq))
+    }
+
+    fn record_at(&mut self, sector: u32, pos: u32) -> Result<Step, Error<F::Error>> {
+        let sector_size = F::ERASE_SIZE as u32;
+        if pos + RECORD_HEADER_LEN > sector_size {
+            return Ok(Step::End);
+        }
+        let mut header = [0u8; RECORD_HEADER_LEN as usize];
+        let addr = self.sector_addr(sector) + pos;
+        self.flash.read(addr, &mut header).map_err(Error::Flash)?;
+        let rec = Record {
+            pos,
+            key: u16::from_le_bytes([header[0], header[1]]),
+            len: u16::from_le_bytes([header[2], header[3]]),
+            crc: u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+        };
+        if rec.key == ERASED_KEY {
+            return Ok(Step::End);
+        }
+        if rec.len != TOMBSTONE && rec.next() > sector_size {
+            return Ok(Step::Corrupt);
+        }
+        Ok(Step::Record(rec))
+    }
+
+    /// Offset of the first free byte in `sector`. A corrupt header marks the
+    /// sector full, so the next write moves to a fresh sector.
+    fn scan_end(&mut self, sector: u32) -> Result<u32, Error<F::Error>> {
+        let mut pos = SECTOR_HEADER_LEN;
+        loop {
+            match self.record_at(sector, pos)? {
+                Step::Record(rec) => pos = rec.next(),
+                Step::End => return Ok(pos),
+                Step::Corrupt => return Ok(F::ERASE_SIZE as u32),
+            }
+        }
+    }
+
+    /// Whether `rec`'s CRC matches its contents.
+    fn is_valid(&mut self, sector: u32, rec: &Record) -> Result<bool, Error<F::Error>> {
+        let mut crc = Crc32::new();
+        crc.update(&rec.key.to_le_bytes());
+        crc.update(&rec.len.to_le_bytes());
+        let mut addr = self.sector_addr(sector) + rec.pos + RECORD_HEADER_LEN;
+        let mut left = rec.data_len() as usize;
+        let mut chunk = [0u8; CHUNK];
+        while left > 0 {
+            let n = left.min(CHUNK);
+            self.flash
+                .read(addr, &mut chunk[..n])
+                .map_err(Error::Flash)?;
+            crc.update(&chunk[..n]);
+            addr += n as u32;
+            left -= n;
+        }
+        Ok(crc.finish() == rec.crc)
+    }
+
+    fn data_equals(
+        &mut self,
+        sector: u32,
+        rec: &Record,
+        data: &[u8],
+    ) -> Result<bool, Error<F::Error>> {
+        let mut addr = self.sector_addr(sector) + rec.pos + RECORD_HEADER_LEN;
+        let mut chunk = [0u8; CHUNK];
+        for expected in data.chunks(CHUNK) {
+            let stored = &mut chunk[..expected.len()];
+            self.flash.read(addr, stored).map_err(Error::Flash)?;
+            if stored != expected {
+                return Ok(false);
+            }
+            addr += expected.len() as u32;
+        }
+        Ok(true)
+    }
+
+    /// Last valid record of `key` in the active sector, tombstones included.
+    fn find(&mut self, key: u16) -> Result<Option<Record>, Error<F::Error>> {
+        self.find_from(self.active, SECTOR_HEADER_LEN, key)
+    }
+
+    fn find_from(
+        &mut self,
+        sector: u32,
+        mut pos: u32,
+        key: u16,
+    ) -> Result<Option<Record>, Error<F::Error>> {
+        let mut found = None;
+        while let Step::Record(rec) = self.record_at(sector, pos)? {
+            if rec.key == key && self.is_valid(sector, &rec)? {
+                found = Some(rec);
+            }
+            pos = rec.next();
+        }
+        Ok(found)
+    }
+
+    fn append(&mut self, key: u16, len: u16, data: &[u8]) -> Result<(), Error<F::Error>> {
+        let size = RECORD_HEADER_LEN + align4(data.len() as u32);
+        if self.write_pos + size > F::ERASE_SIZE as u32 {
+            self.collect()?;
+            if self.write_pos + size > F::ERASE_SIZE as u32 {
+                return Err(Error::Full);
+            }
+        }
+
+        let mut crc = Crc32::new();
+        crc.update(&key.to_le_bytes());
+        crc.update(&len.to_le_bytes());
+        crc.update(data);
+
+        let mut header = [0u8; RECORD_HEADER_LEN as usize];
+        header[..2].copy_from_slice(&key.to_le_bytes());
+        header[2..4].copy_from_slice(&len.to_le_bytes());
+        header[4..].copy_from_slice(&crc.finish().to_le_bytes());
+
+        // Header first: once it is written the slot is taken, and a reset
+        // before the data is complete only leaves a record with a bad CRC.
+        let addr = self.sector_addr(self.active) + self.write_pos;
+        self.write_pos += size;
+        self.flash.write(addr, &header).map_err(Error::Flash)?;
+        if !data.is_empty() {
+            self.flash
+                .write(addr + RECORD_HEADER_LEN, data)
+                .map_err(Error::Flash)?;
+        }
+        Ok(())
+    }
+
+    /// Copy the live records into the next sector and make it active.
+    fn collect(&mut self) -> Result<(), Error<F::Error>> {
+        let old = self.active;
+        let new = (old + 1) % self.sectors;
+        debug!("nvs: moving live records from sector {} to {}", old, new);
+        self.erase_sector(new)?;
+
+        let mut src = SECTOR_HEADER_LEN;
+        let mut dst = SECTOR_HEADER_LEN;
+        while let Step::Record(rec) = self.record_at(old, src)? {
+            src = rec.next();
+            if rec.len == TOMBSTONE || !self.is_valid(old, &rec)? {
+                continue;
+            }
+            // Only the last valid record of a key is live.
+            if self.find_from(old, rec.next(), rec.key)?.is_some() {
+                continue;
+            }
+            self.copy_record(old, &rec, new, dst)?;
+            dst += rec.size();
+        }
+
+        // The header goes last, so a reset during the copy leaves `old` active.
+        self.write_sector_header(new, self.seq.wrapping_add(1))?;
+        self.write_pos = dst;
+        Ok(())
+    }
+
+    fn copy_record(
+        &mut self,
+        from: u32,
+        rec: &Record,
+        to: u32,
+        pos: u32,
+    ) -> Result<(), Error<F::Error>> {
+        let mut src = self.sector_addr(from) + rec.pos;
+        let mut dst = self.sector_addr(to) + pos;
+        let mut left = (RECORD_HEADER_LEN + rec.data_len()) as usize;
+        let mut chunk = [0u8; CHUNK];
+        while left > 0 {
+            let n = left.min(CHUNK);
+            self.flash
+                .read(src, &mut chunk[..n])
+                .map_err(Error::Flash)?;
+            self.flash.write(dst, &chunk[..n]).map_err(Error::Flash)?;
+            src += n as u32;
+            dst += n as u32;
+            left -= n;
+        }
+        Ok(())
+    }
+}
+
+fn check_key<E>(key: u16) -> Result<(), Error<E>> {
+    if key == ERASED_KEY {
+        Err(Error::InvalidKey)
+    } else {
+        Ok(())
+    }
+}
+
+const fn align4(n: u32) -> u32 {
+    (n + 3) & !3
+}
+
+/// CRC-32 (IEEE 802.3, reflected, as used by zlib).
+struct Crc32(u32);
+
+impl Crc32 {
+    const fn new() -> Self {
+        Self(0xFFFF_FFFF)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.0 ^= b as u32;
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+    }
+
+    const fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_storage::nor_flash::{ErrorType, NorFlashErrorKind, ReadNorFlash};
+
+    use super::*;
+    use crate::efuse::SimEfuse;
+
+    const SECTOR: usize = 256;
+
+    /// RAM-backed NOR flash: erase sets bytes to 0xFF, writes only clear bits.
+    struct RamFlash([u8; SECTOR * 4]);
+
+    impl RamFlash {
+        fn new() -> Self {
+            Self([0xFF; SECTOR * 4])
+        }
+    }
+
+    impl ErrorType for RamFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for RamFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let start = offset as usize;
+            bytes.copy_from_slice(&self.0[start..start + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl NorFlash for RamFlash {
+        const WRITE_SIZE: usize = 1;
+        const ERASE_SIZE: usize = SECTOR;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.0[from as usize..to as usize].fill(0xFF);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            for (dst, src) in self.0[offset as usize..].iter_mut().zip(bytes) {
+                *dst &= *src;
+            }
+            Ok(())
+        }
+    }
+
+    fn mount(flash: RamFlash) -> Nvs<RamFlash> {
+        Nvs::new(flash, SECTOR as u32, 3).unwrap()
+    }
+
+    #[test]
+    fn crc32_matches_reference() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn rejects_bad_region() {
+        assert!(matches!(
+            Nvs::new(RamFlash::new(), 0, 1),
+            Err(Error::InvalidRegion)
+        ));
+        assert!(matches!(
+            Nvs::new(RamFlash::new(), 1, 2),
+            Err(Error::InvalidRegion)
+        ));
+        assert!(matches!(
+            Nvs::new(RamFlash::new(), SECTOR as u32 * 3, 2),
+            Err(Error::InvalidRegion)
+        ));
+    }
+
+    #[test]
+    fn write_read_delete() {
+        let mut nvs = mount(RamFlash::new());
+        let mut buf = [0u8; 8];
+        assert_eq!(nvs.read(1, &mut buf), Ok(None));
+
+        nvs.write(1, b"abc").unwrap();
+        nvs.write(1, b"hello").unwrap();
+        assert_eq!(nvs.read(1, &mut buf), Ok(Some(5)));
+        assert_eq!(&buf[..5], b"hello");
+        assert_eq!(
+            nvs.read(1, &mut [0u8; 2]),
+            Err(Error::LengthMismatch { len: 5 })
+        );
+
+        nvs.delete(1).unwrap();
+        assert_eq!(nvs.read(1, &mut buf), Ok(None));
+        assert_eq!(nvs.write(0xFFFF, b"x"), Err(Error::InvalidKey));
+    }
+
+    #[test]
+    fn typed_values() {
+        const COUNT: Key<u32> = Key::new(7);
+        const FLAG: Key<bool> = Key::new(8);
+        let mut nvs = mount(RamFlash::new());
+
+        nvs.set(&COUNT, &0xDEAD_BEEF).unwrap();
+        nvs.set(&FLAG, &true).unwrap();
+        assert_eq!(nvs.get(&COUNT), Ok(Some(0xDEAD_BEEF)));
+        assert_eq!(nvs.get(&FLAG), Ok(Some(true)));
+        assert_eq!(
+            nvs.get(&Key::<u16>::new(7)),
+            Err(Error::LengthMismatch { len: 4 })
+        );
+    }
+
+    #[test]
+    fn survives_remount_and_sector_rotation() {
+        let mut nvs = mount(RamFlash::new());
+        nvs.write(1, b"keep").unwrap();
+        // Enough rewrites of another key to fill several sectors.
+        for i in 0u32..100 {
+            nvs.write(2, &i.to_le_bytes()).unwrap();
+        }
+        // Every sector has been active at least once.
+        assert!(nvs.seq >= 3);
+
+        let mut nvs = mount(nvs.release());
+        let mut buf = [0u8; 4];
+        assert_eq!(nvs.read(1, &mut buf), Ok(Some(4)));
+        assert_eq!(&buf, b"keep");
+        assert_eq!(nvs.read(2, &mut buf), Ok(Some(4)));
+        assert_eq!(u32::from_le_bytes(buf), 99);
+    }
+
+    #[test]
+    fn unchanged_value_is_not_rewritten() {
+        let mut nvs = mount(RamFlash::new());
+        nvs.write(1, b"same").unwrap();
+        let pos = nvs.write_pos;
+        nvs.write(1, b"same").unwrap();
+        assert_eq!(nvs.write_pos, pos);
+    }
+
+    #[test]
+    fn torn_record_keeps_previous_value() {
+        let mut nvs = mount(RamFlash::new());
+        nvs.write(1, b"old!").unwrap();
+        let pos = nvs.write_pos;
+        nvs.write(1, b"new!").unwrap();
+
+        // Clear bits in the new record's data, as an interrupted program would.
+        let addr = (nvs.sector_addr(nvs.active) + pos + RECORD_HEADER_LEN) as usize;
+        let mut flash = nvs.release();
+        flash.0[addr] = 0;
+
+        let mut nvs = mount(flash);
+        let mut buf = [0u8; 4];
+        assert_eq!(nvs.read(1, &mut buf), Ok(Some(4)));
+        assert_eq!(&buf, b"old!");
+    }
+
+    #[test]
+    fn sector_without_header_is_ignored() {
+        let mut nvs = mount(RamFlash::new());
+        nvs.write(1, b"v1").unwrap();
+        let active = nvs.active;
+
+        // A collection interrupted before the header: records, no magic.
+        let next = (active + 1) % 3;
+        let addr = nvs.sector_addr(next) + SECTOR_HEADER_LEN;
+        let mut flash = nvs.release();
+        flash.write(addr, &[1, 0, 2, 0, 0, 0, 0, 0]).unwrap();
+
+        let mut nvs = mount(flash);
+        assert_eq!(nvs.active, active);
+        assert_eq!(nvs.get(&Key::<[u8; 2]>::new(1)), Ok(Some(*b"v1")));
+    }
+
+    #[test]
+    fn full_store_is_reported() {
+        let mut nvs = mount(RamFlash::new());
+        let big = [0x55u8; 100];
+        nvs.write(1, &big).unwrap();
+        nvs.write(2, &big).unwrap();
+        assert_eq!(nvs.write(3, &big), Err(Error::Full));
+        assert_eq!(nvs.write(4, &[0u8; SECTOR]), Err(Error::ValueTooLarge));
+    }
+
+    #[test]
+    fn seeds_from_efuse_once() {
+        let mut bank0 = [0u32; 8];
+        bank0[0] = 0x1122_3344;
+        let mut bank1 = [0u32; 8];
+        // Non-zero HPSYS LDO code marks bank1 programmed.
+        bank1[0] = 0xFFFF_FFFF;
+        let mut sim = SimEfuse::new().with_bank(0, bank0).with_bank(1, bank1);
+        let efuse = Efuse::new_simulated(&mut sim, 48_000_000).unwrap();
+
+        let mut nvs = mount(RamFlash::new());
+        nvs.seed_from_efuse(&efuse).unwrap();
+        let uid = nvs.get(&keys::EFUSE_UID).unwrap().unwrap();
+        assert_eq!(&uid, efuse.uid().bytes());
+        let bank1_bytes = nvs.get(&keys::EFUSE_BANK1).unwrap().unwrap();
+        assert_eq!(bank1_bytes[..4], [0xFF; 4]);
+
+        let pos = nvs.write_pos;
+        nvs.seed_from_efuse(&efuse).unwrap();
+        assert_eq!(nvs.write_pos, pos);
+    }
+
+    #[test]
+    fn loads_stored_bd_addr() {
+        let mut nvs = mount(RamFlash::new());
+        let config = nvs.load_bd_addr(LcpuConfig::new()).unwrap();
+        assert_eq!(config.ble.bd_addr, LcpuConfig::new().ble.bd_addr);
+
+        let addr = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+        nvs.set(&keys::BD_ADDR, &addr).unwrap();
+        let config = nvs.load_bd_addr(LcpuConfig::new()).unwrap();
+        assert_eq!(config.ble.bd_addr, addr);
+        assert_eq!(config.ble.addr_type, crate::lcpu::AddressType::Public);
+    }
+}
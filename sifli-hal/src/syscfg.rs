@@ -12,10 +12,20 @@
 //! }
 //! ```
 
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
 use crate::pac;
 
+static IDR_CACHED: AtomicBool = AtomicBool::new(false);
+static IDR_RAW: AtomicU32 = AtomicU32::new(0);
+
 /// Read `HPSYS_CFG->IDR` and return a parsed [`Idr`].
 ///
+/// The IDR is fixed in silicon, so the register is only read on the first
+/// call; later calls return the cached value. This keeps the revision checks
+/// in LCPU bring-up and ROM config writes off the bus, and gives every caller
+/// the same answer. Use [`read_idr_uncached`] to force a register read.
+///
 /// ```no_run
 /// use sifli_hal::syscfg;
 ///
@@ -23,6 +33,20 @@ use crate::pac;
 /// ```
 #[inline]
 pub fn read_idr() -> Idr {
+    if IDR_CACHED.load(Ordering::Acquire) {
+        return Idr::from_raw(IDR_RAW.load(Ordering::Relaxed));
+    }
+    // Racing callers all read the same value, so storing twice is harmless.
+    let idr = read_idr_uncached();
+    IDR_RAW.store(idr.raw(), Ordering::Relaxed);
+    IDR_CACHED.store(true, Ordering::Release);
+    idr
+}
+
+/// Read `HPSYS_CFG->IDR` from hardware, bypassing the cache used by
+/// [`read_idr`].
+#[inline]
+pub fn read_idr_uncached() -> Idr {
     Idr::from_regs(pac::HPSYS_CFG)
 }
 
@@ -56,6 +80,34 @@ impl Idr {
         }
     }
 
+    /// Build an [`Idr`] from a raw 32-bit IDR value, e.g. one reported by a
+    /// device under test. Inverse of [`raw`](Self::raw).
+    #[inline]
+    pub const fn from_raw(raw: u32) -> Self {
+        Self {
+            revid: raw as u8,
+            pid: (raw >> 8) as u8,
+            cid: (raw >> 16) as u8,
+            sid: (raw >> 24) as u8,
+        }
+    }
+
+    /// Whether the series and company IDs match the expected part.
+    ///
+    /// Revision and package are ignored, so one check covers every
+    /// revision of a series:
+    ///
+    /// ```no_run
+    /// use sifli_hal::syscfg;
+    ///
+    /// const EXPECTED: syscfg::Idr = syscfg::Idr::from_raw(0x1234_0007);
+    /// assert!(syscfg::read_idr().matches(EXPECTED.sid, EXPECTED.cid));
+    /// ```
+    #[inline]
+    pub fn matches(&self, expected_sid: u8, expected_cid: u8) -> bool {
+        self.sid == expected_sid && self.cid == expected_cid
+    }
+
     /// Parse the `revid` field into a [`ChipRevision`].
     #[inline]
     pub fn revision(&self) -> ChipRevision {
@@ -112,6 +164,26 @@ impl ChipRevision {
         matches!(self, ChipRevision::A4 | ChipRevision::B4)
     }
 
+    /// Whether this is a pre-A3 engineering sample (REVID 0x00-0x02).
+    #[inline]
+    pub fn is_engineering_sample(&self) -> bool {
+        matches!(self, ChipRevision::A3OrEarlier(0x00..=0x02))
+    }
+
+    /// Whether this revision supports the given [`ChipFeature`].
+    ///
+    /// Prefer this over ad-hoc revision comparisons so erratum logic stays in
+    /// one place.
+    #[inline]
+    pub fn supports(&self, feature: ChipFeature) -> bool {
+        match feature {
+            ChipFeature::RomBleController
+            | ChipFeature::ExtendedRomConfig
+            | ChipFeature::LeCodedPhy => self.is_letter_series(),
+            ChipFeature::FactoryRfCal => self.is_valid() && !self.is_engineering_sample(),
+        }
+    }
+
     /// Get the raw REVID value for this chip revision.
     #[inline]
     pub fn revid(&self) -> u8 {
@@ -139,6 +211,24 @@ impl ChipRevision {
     }
 }
 
+/// Revision-dependent silicon capabilities, queried via [`ChipRevision::supports`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ChipFeature {
+    /// BLE controller firmware lives in LCPU ROM; no RAM image install needed
+    /// (Letter Series only).
+    RomBleController,
+    /// ROM config block carries `bt_config`, EM/ACT and IPC fields
+    /// (Letter Series only).
+    ExtendedRomConfig,
+    /// Factory RF calibration values are programmed in EFUSE bank1
+    /// (not present on engineering samples).
+    FactoryRfCal,
+    /// The BLE controller supports the LE Coded PHY (Letter Series only).
+    LeCodedPhy,
+}
+
 #[cfg(feature = "defmt")]
 impl defmt::Format for ChipRevision {
     fn format(&self, fmt: defmt::Formatter) {
@@ -1,4 +1,4 @@
-//! Time units
+//! Time units and a blocking monotonic clock.
 
 // The following code is modified from embassy-stm32 under MIT license
 // https://github.com/embassy-rs/embassy/tree/main/embassy-stm32
@@ -6,6 +6,9 @@
 
 use core::ops::{Add, Div, Mul};
 
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::peripheral::SYST;
+
 /// Hertz
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -136,3 +139,133 @@ impl From<MaybeHertz> for Option<Hertz> {
         }
     }
 }
+
+/// SysTick reload value: the counter is 24 bits wide.
+const SYST_MAX: u32 = 0x00FF_FFFF;
+
+/// Ticks a SysTick down-counter advanced from `last` to `current`, modulo
+/// 2^24.
+fn syst_delta(last: u32, current: u32) -> u32 {
+    last.wrapping_sub(current) & SYST_MAX
+}
+
+/// Free-running clock on the Cortex-M SysTick, independent of `embassy-time`.
+///
+/// For blocking code that needs to measure time without an executor (busy
+/// waits with timeouts, profiling init sequences). SysTick counts the
+/// reference clock chosen by [`ClockMux::ticksel`](crate::rcc::ClockMux)
+/// (`SEL_TICK`, divided by `TICKDIV`), so the resolution is `1 / tick_hz()`:
+///
+/// | `ticksel`             | resolution | counter wraps after |
+/// |-----------------------|------------|---------------------|
+/// | `ClkRtc` (LRC10)      | ~100 µs    | ~1678 s             |
+/// | `ClkRtc` (LXT32)      | ~30.5 µs   | ~512 s              |
+/// | `Hrc48`               | ~21 ns     | ~350 ms             |
+/// | `Hxt48`               | ~21 ns     | ~350 ms             |
+///
+/// The 24-bit hardware counter is extended to 64 bits in software on each
+/// [`now`](Self::now), so it must be called at least once per wrap period.
+///
+/// ```rust,ignore
+/// let cp = cortex_m::Peripherals::take().unwrap();
+/// let mut mono = sifli_hal::time::Monotonic::new(cp.SYST).unwrap();
+/// let start = mono.now();
+/// while busy() {
+///     if mono.elapsed(start) > core::time::Duration::from_millis(100) {
+///         break;
+///     }
+/// }
+/// ```
+pub struct Monotonic {
+    syst: SYST,
+    tick_hz: u32,
+    /// Counter value at the last [`Monotonic::now`].
+    last: u32,
+    /// Ticks counted up to `last`.
+    ticks: u64,
+}
+
+impl Monotonic {
+    /// Start SysTick as a free-running down-counter on the reference clock.
+    ///
+    /// Returns `None` if the `SEL_TICK` source is reserved or unknown. The
+    /// SysTick interrupt is left disabled.
+    pub fn new(mut syst: SYST) -> Option<Self> {
+        let tick_hz = crate::rcc::get_systick_ref_freq()?.0;
+
+        syst.disable_counter();
+        syst.disable_interrupt();
+        syst.set_clock_source(SystClkSource::External);
+        syst.set_reload(SYST_MAX);
+        syst.clear_current();
+        syst.enable_counter();
+
+        Some(Self {
+            syst,
+            tick_hz,
+            // `clear_current` leaves the counter at 0; it reloads to
+            // SYST_MAX on the first tick.
+            last: 0,
+            ticks: 0,
+        })
+    }
+
+    /// Tick frequency in Hz.
+    pub fn tick_hz(&self) -> u32 {
+        self.tick_hz
+    }
+
+    /// Ticks since [`Monotonic::new`].
+    pub fn now(&mut self) -> u64 {
+        let current = SYST::get_current();
+        let delta = syst_delta(self.last, current);
+        self.last = current;
+        self.ticks += delta as u64;
+        self.ticks
+    }
+
+    /// Time since `since`, a value returned by [`now`](Self::now).
+    pub fn elapsed(&mut self, since: u64) -> core::time::Duration {
+        let ticks = self.now().saturating_sub(since);
+        self.ticks_to_duration(ticks)
+    }
+
+    /// Convert a tick count to a duration.
+    pub fn ticks_to_duration(&self, ticks: u64) -> core::time::Duration {
+        let secs = ticks / self.tick_hz as u64;
+        let rem = ticks % self.tick_hz as u64;
+        let nanos = rem * 1_000_000_000 / self.tick_hz as u64;
+        core::time::Duration::new(secs, nanos as u32)
+    }
+
+    /// Stop the counter and give SysTick back.
+    pub fn free(mut self) -> SYST {
+        self.syst.disable_counter();
+        self.syst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syst_delta_counts_down() {
+        assert_eq!(syst_delta(1000, 1000), 0);
+        assert_eq!(syst_delta(1000, 990), 10);
+    }
+
+    #[test]
+    fn syst_delta_after_clear() {
+        // First reads after `clear_current`: still 0, then the reload.
+        assert_eq!(syst_delta(0, 0), 0);
+        assert_eq!(syst_delta(0, SYST_MAX), 1);
+        assert_eq!(syst_delta(0, SYST_MAX - 9), 10);
+    }
+
+    #[test]
+    fn syst_delta_wraps() {
+        assert_eq!(syst_delta(5, SYST_MAX - 4), 10);
+        assert_eq!(syst_delta(0, 1), SYST_MAX);
+    }
+}
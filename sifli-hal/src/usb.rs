@@ -1,11 +1,26 @@
 /// Universal Serial Bus (USB)
 ///
 /// See more: https://github.com/decaday/musb
+///
+/// # Suspend and remote wakeup
+///
+/// When the host suspends the bus, [`Bus`] reports it to embassy-usb (which
+/// calls `Handler::suspended(true)`) and lets the controller put the PHY into
+/// suspend, which stops its clock. Bus activity from the host resumes the
+/// PHY in hardware and is reported as `suspended(false)`. The current state
+/// is also available from [`is_suspended`].
+///
+/// A device whose configuration sets `supports_remote_wakeup` can wake the
+/// host with `UsbDevice::remote_wakeup()` once the host has enabled the
+/// feature; the driver then drives resume signalling on the bus.
 use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, Ordering};
 
-use embassy_usb_driver::{self as driver, EndpointAddress, EndpointType};
+use embassy_usb_driver::{
+    self as driver, Bus as _, EndpointAddress, EndpointType, Event, Unsupported,
+};
 use musb::MusbInstance;
-use musb::{Bus, ControlPipe, Endpoint, In, MusbDriver, Out, UsbInstance};
+use musb::{ControlPipe, Endpoint, In, MusbDriver, Out, UsbInstance};
 
 use crate::gpio::hpsys::HpsysPin;
 use crate::interrupt::typelevel::Interrupt;
@@ -13,6 +28,20 @@ use crate::pac::HPSYS_CFG;
 use crate::rcc::{get_clk_usb_div, get_clk_usb_source, RccEnableReset, RccGetFreq};
 use crate::{interrupt, Peripheral};
 
+/// Set while the host has the bus suspended.
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+/// How long remote wakeup drives resume signalling (USB 2.0: 1..=15 ms).
+const RESUME_SIGNAL_US: u32 = 10_000;
+
+/// Whether the host has suspended the bus.
+///
+/// Follows the `Suspend`/`Resume`/`Reset` events [`Bus`] hands to
+/// embassy-usb, so it changes together with `Handler::suspended`.
+pub fn is_suspended() -> bool {
+    SUSPENDED.load(Ordering::Relaxed)
+}
+
 fn init<T: Instance>() {
     let freq = T::frequency();
     if let Some(f) = freq {
@@ -68,6 +97,13 @@ fn init<T: Instance>() {
         w.set_soft_conn(true);
     });
 
+    // Let the controller suspend the PHY (stopping its clock) when the bus is
+    // suspended; resume signalling from the host brings it back.
+    UsbInstance::regs().power().modify(|w| {
+        w.set_enable_suspendm(true);
+    });
+    SUSPENDED.store(false, Ordering::Relaxed);
+
     // UsbInstance::regs().devctl().modify(|w| {
     //     w.set_session(true);
     // });
@@ -112,7 +148,7 @@ impl<'d, T: Instance> driver::Driver<'d> for Driver<'d, T> {
     type EndpointOut = Endpoint<'d, UsbInstance, Out>;
     type EndpointIn = Endpoint<'d, UsbInstance, In>;
     type ControlPipe = ControlPipe<'d, UsbInstance>;
-    type Bus = Bus<'d, UsbInstance>;
+    type Bus = Bus<'d, T>;
 
     fn alloc_endpoint_in(
         &mut self,
@@ -136,11 +172,77 @@ impl<'d, T: Instance> driver::Driver<'d> for Driver<'d, T> {
             .alloc_endpoint(ep_type, ep_addr, max_packet_size, interval_ms)
     }
 
-    fn start(
-        self,
-        control_max_packet_size: u16,
-    ) -> (Bus<'d, UsbInstance>, ControlPipe<'d, UsbInstance>) {
-        self.inner.start(control_max_packet_size)
+    fn start(self, control_max_packet_size: u16) -> (Bus<'d, T>, ControlPipe<'d, UsbInstance>) {
+        let (bus, pipe) = self.inner.start(control_max_packet_size);
+        let bus = Bus {
+            inner: bus,
+            phantom: PhantomData,
+        };
+        (bus, pipe)
+    }
+}
+
+/// USB bus, handed to embassy-usb by [`Driver`].
+///
+/// Wraps the musb bus to track suspend state and implement remote wakeup.
+pub struct Bus<'d, T: Instance> {
+    inner: musb::Bus<'d, UsbInstance>,
+    phantom: PhantomData<&'d mut T>,
+}
+
+impl<'d, T: Instance> driver::Bus for Bus<'d, T> {
+    async fn enable(&mut self) {
+        self.inner.enable().await
+    }
+
+    async fn disable(&mut self) {
+        self.inner.disable().await
+    }
+
+    async fn poll(&mut self) -> Event {
+        let event = self.inner.poll().await;
+        match event {
+            Event::Suspend => {
+                debug!("usb: suspended");
+                SUSPENDED.store(true, Ordering::Relaxed);
+            }
+            Event::Resume | Event::Reset => {
+                if SUSPENDED.swap(false, Ordering::Relaxed) {
+                    debug!("usb: resumed");
+                }
+            }
+            _ => {}
+        }
+        event
+    }
+
+    fn endpoint_set_enabled(&mut self, ep_addr: EndpointAddress, enabled: bool) {
+        self.inner.endpoint_set_enabled(ep_addr, enabled)
+    }
+
+    fn endpoint_set_stalled(&mut self, ep_addr: EndpointAddress, stalled: bool) {
+        self.inner.endpoint_set_stalled(ep_addr, stalled)
+    }
+
+    fn endpoint_is_stalled(&mut self, ep_addr: EndpointAddress) -> bool {
+        self.inner.endpoint_is_stalled(ep_addr)
+    }
+
+    /// Drive resume signalling to wake a suspended host.
+    ///
+    /// embassy-usb only calls this while suspended and after the host has
+    /// enabled remote wakeup. Setting `RESUME` also takes the PHY out of
+    /// suspend; the host answers with its own resume, reported by
+    /// [`poll`](driver::Bus::poll).
+    async fn remote_wakeup(&mut self) -> Result<(), Unsupported> {
+        let regs = UsbInstance::regs();
+        regs.power().modify(|w| w.set_resume(true));
+        #[cfg(feature = "time")]
+        embassy_time::Timer::after_micros(RESUME_SIGNAL_US as u64).await;
+        #[cfg(not(feature = "time"))]
+        crate::blocking_delay_us(RESUME_SIGNAL_US);
+        regs.power().modify(|w| w.set_resume(false));
+        Ok(())
     }
 }
 